@@ -0,0 +1,464 @@
+//! HTTP API to inspect and control the running bridge, see `config::Api`.
+//!
+//! Requests are answered with a JSON body and the connection is closed,
+//! e.g. `curl -X POST http://127.0.0.1:9888/recording/start`.
+
+use std::{
+    fmt::Display,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use log::{debug, error, info, warn};
+use serde_json::json;
+use stream_cancel::Tripwire;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+
+use crate::{
+    metrics::SinkStatsSnapshot,
+    topology::{ComponentID, TopologyConfigError, TopologyState},
+};
+
+/// Longest request line and headers of a request.
+const MAX_HEAD_SIZE: u64 = 8 * 1024;
+
+/// Longest request body, which is read and discarded.
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Time a client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Connection failed: {0}")]
+    Io(#[source] std::io::Error),
+
+    #[error("Malformed request: {0}")]
+    Malformed(String),
+
+    #[error("Request was not received in time")]
+    Timeout,
+}
+
+/// The parts of a request the API routes on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    /// Path of the request target, without its query.
+    path: String,
+    content_length: u64,
+}
+
+impl Request {
+    /// Parse the request line and headers of a HTTP/1 request.
+    fn parse(head: &str) -> Result<Self, ApiError> {
+        let mut lines = head.lines();
+        let request_line = lines.next().unwrap_or_default();
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ApiError::Malformed(format!(
+                "invalid request line '{request_line}'"
+            )));
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(ApiError::Malformed(format!(
+                "unsupported version '{version}'"
+            )));
+        }
+        let mut content_length = 0;
+        for (name, value) in lines.filter_map(|line| line.split_once(':')) {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| {
+                    ApiError::Malformed(format!("invalid content length '{}'", value.trim()))
+                })?;
+            }
+        }
+        let path = target.split_once('?').map_or(target, |(path, _)| path);
+        Ok(Self {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            content_length,
+        })
+    }
+}
+
+/// Read a request, discarding its body, as no route takes one.
+async fn read_request(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<Request, ApiError> {
+    let mut head = String::new();
+    while !head.ends_with("\r\n\r\n") && !head.ends_with("\n\n") {
+        let limit = MAX_HEAD_SIZE.saturating_sub(head.len() as u64);
+        let read = (&mut *reader)
+            .take(limit)
+            .read_line(&mut head)
+            .await
+            .map_err(ApiError::Io)?;
+        if read == 0 {
+            return Err(ApiError::Malformed(
+                "request headers are incomplete or too long".to_owned(),
+            ));
+        }
+    }
+    let request = Request::parse(&head)?;
+    if request.content_length > MAX_BODY_SIZE {
+        return Err(ApiError::Malformed("request body is too long".to_owned()));
+    }
+    let mut body = (&mut *reader).take(request.content_length);
+    tokio::io::copy(&mut body, &mut tokio::io::sink())
+        .await
+        .map_err(ApiError::Io)?;
+    Ok(request)
+}
+
+/// Endpoints of the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Health,
+    Topics,
+    Sinks,
+    StartRecording,
+    StopRecording,
+}
+
+/// Method and path of each route.
+const ROUTES: &[(&str, &str, Route)] = &[
+    ("GET", "/health", Route::Health),
+    ("GET", "/topics", Route::Topics),
+    ("GET", "/sinks", Route::Sinks),
+    ("POST", "/recording/start", Route::StartRecording),
+    ("POST", "/recording/stop", Route::StopRecording),
+];
+
+impl Route {
+    /// The route of a request, or the error response if there is none.
+    fn find(method: &str, path: &str) -> Result<Self, Response> {
+        let mut routes = ROUTES
+            .iter()
+            .filter(|(_, route_path, _)| *route_path == path)
+            .peekable();
+        if routes.peek().is_none() {
+            return Err(Response::error(404, format!("No route for {path}")));
+        }
+        routes
+            .find(|(route_method, _, _)| *route_method == method)
+            .map(|(_, _, route)| *route)
+            .ok_or_else(|| Response::error(405, format!("{path} does not accept {method}")))
+    }
+}
+
+/// A JSON response with a status code.
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Display) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+
+    /// The response as sent on the connection, which is closed after it.
+    fn to_bytes(&self) -> Vec<u8> {
+        let body = self.body.to_string();
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            _ => "Internal Server Error",
+        };
+        format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.status,
+            body.len()
+        )
+        .into_bytes()
+    }
+}
+
+/// Answer a request to `route`.
+async fn respond(route: Route, topology: &TopologyState) -> Response {
+    match route {
+        Route::Health => Response::ok(health(topology)),
+        Route::Topics => Response::ok(topics(topology)),
+        Route::Sinks => {
+            let sinks: Vec<_> = topology
+                .sink_stats()
+                .iter()
+                .map(|(id, stats)| sink_stats(id, stats))
+                .collect();
+            Response::ok(json!({ "sinks": sinks }))
+        }
+        Route::StartRecording => match topology.start_recording() {
+            Ok(file) => Response::ok(json!({ "file": file.display().to_string() })),
+            Err(err @ TopologyConfigError::NotRunning(_)) => Response::error(409, err),
+            Err(err) => Response::error(500, err),
+        },
+        Route::StopRecording => {
+            let file = topology.stop_recording().await;
+            Response::ok(json!({ "file": file.map(|file| file.display().to_string()) }))
+        }
+    }
+}
+
+/// The problems the bridge detected, and whether there are any.
+///
+/// Subscriptions that publishers do not connect to because of incompatible
+/// QoS are listed with the mismatching policy, see `TopologyState::check_qos`.
+fn health(topology: &TopologyState) -> serde_json::Value {
+    let mismatches: Vec<_> = topology
+        .qos_mismatches()
+        .iter()
+        .map(|(id, topic, mismatch)| {
+            json!({
+                "id": id.name(),
+                "topic": topic,
+                "publisher": mismatch.publisher,
+                "policy": mismatch.policy,
+                "offered": mismatch.offered,
+                "requested": mismatch.requested,
+            })
+        })
+        .collect();
+    let status = if mismatches.is_empty() {
+        "ok"
+    } else {
+        "degraded"
+    };
+    json!({ "qos_mismatches": mismatches, "status": status })
+}
+
+/// The running topic subscriptions with the ROS type and archetypes they
+/// were resolved to, and the configured ones that are not running.
+fn topics(topology: &TopologyState) -> serde_json::Value {
+    let active: Vec<_> = topology
+        .active_topics()
+        .into_iter()
+        .filter_map(|(id, topic)| {
+            let worker = topology.subscription(&id)?;
+            let archetypes: Vec<_> = worker
+                .rerun_names()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            Some(json!({
+                "id": id.name(),
+                "topic": topic,
+                "ros_type": worker.ros_type().schema_name(),
+                "archetypes": archetypes,
+            }))
+        })
+        .collect();
+    let inactive: Vec<_> = topology
+        .inactive_topics()
+        .into_iter()
+        .map(|(id, topic)| json!({ "id": id.name(), "topic": topic }))
+        .collect();
+    json!({ "active": active, "inactive": inactive })
+}
+
+/// The counters of a sink, with the last flush in seconds since the Unix epoch.
+fn sink_stats(id: &ComponentID, stats: &SinkStatsSnapshot) -> serde_json::Value {
+    let last_flush = stats
+        .last_flush
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs_f64());
+    json!({
+        "id": id.name(),
+        "messages_logged": stats.messages_logged,
+        "log_errors": stats.log_errors,
+        "flush_errors": stats.flush_errors,
+        "last_flush": last_flush,
+        "bytes_written": stats.bytes_written,
+    })
+}
+
+/// Answer the request on a connection.
+async fn handle(mut stream: TcpStream, topology: &Mutex<TopologyState>) -> Result<(), ApiError> {
+    let (reader, mut writer) = stream.split();
+    let mut reader = BufReader::new(reader);
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut reader))
+        .await
+        .map_err(|_| ApiError::Timeout)?;
+    let response = match request {
+        Ok(request) => match Route::find(&request.method, &request.path) {
+            Ok(route) => respond(route, &*topology.lock().await).await,
+            Err(response) => response,
+        },
+        Err(err @ ApiError::Malformed(_)) => Response::error(400, err),
+        Err(err) => return Err(err),
+    };
+    writer
+        .write_all(&response.to_bytes())
+        .await
+        .map_err(ApiError::Io)?;
+    writer.shutdown().await.map_err(ApiError::Io)
+}
+
+/// Serve the API on `address` until `stop` is triggered.
+///
+/// An address that cannot be bound is logged, the bridge keeps running
+/// without the API.
+pub async fn serve(address: SocketAddr, topology: Arc<Mutex<TopologyState>>, stop: Tripwire) {
+    match TcpListener::bind(address).await {
+        Ok(listener) => {
+            info!("API listening on http://{address}");
+            run(listener, topology, stop).await;
+        }
+        Err(err) => error!("Failed to start the API on {address}: {err}"),
+    }
+}
+
+async fn run(listener: TcpListener, topology: Arc<Mutex<TopologyState>>, mut stop: Tripwire) {
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    let topology = topology.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle(stream, &topology).await {
+                            debug!("API request from {peer} failed: {err}");
+                        }
+                    });
+                }
+                Err(err) => warn!("Failed to accept an API connection: {err}"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use super::*;
+
+    #[test]
+    fn parses_request_head() {
+        let request =
+            Request::parse("POST /recording/start?now=1 HTTP/1.1\r\nContent-Length: 2\r\n\r\n")
+                .unwrap();
+        assert_eq!(
+            request,
+            Request {
+                method: "POST".to_owned(),
+                path: "/recording/start".to_owned(),
+                content_length: 2,
+            }
+        );
+        assert!(Request::parse("POST /recording/start\r\n\r\n").is_err());
+        assert!(Request::parse("GET / HTTP/2\r\n\r\n").is_err());
+        assert!(Request::parse("GET / HTTP/1.1\r\ncontent-length: x\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn routes_by_method_and_path() {
+        assert_eq!(
+            Route::find("POST", "/recording/stop"),
+            Ok(Route::StopRecording)
+        );
+        assert_eq!(
+            Route::find("GET", "/recording/stop").unwrap_err().status,
+            405
+        );
+        assert_eq!(Route::find("POST", "/recording").unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn sink_stats_as_json() {
+        let stats = SinkStatsSnapshot {
+            messages_logged: 3,
+            log_errors: 1,
+            last_flush: Some(UNIX_EPOCH + Duration::from_millis(1500)),
+            bytes_written: Some(1024),
+            ..Default::default()
+        };
+        assert_eq!(
+            sink_stats(&ComponentID::DBSink, &stats),
+            json!({
+                "id": "db",
+                "messages_logged": 3,
+                "log_errors": 1,
+                "flush_errors": 0,
+                "last_flush": 1.5,
+                "bytes_written": 1024,
+            })
+        );
+        let unflushed = sink_stats(
+            &ComponentID::GRPCSink("viewer".to_owned()),
+            &Default::default(),
+        );
+        assert_eq!(unflushed["last_flush"], serde_json::Value::Null);
+        assert_eq!(unflushed["bytes_written"], serde_json::Value::Null);
+    }
+
+    async fn request(address: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn answers_recording_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (trigger, stop) = Tripwire::new();
+        let topology = Arc::new(Mutex::new(TopologyState::default()));
+        let server = tokio::spawn(run(listener, topology, stop));
+
+        let response = request(address, "POST /recording/start HTTP/1.1\r\n\r\n").await;
+        assert!(
+            response.starts_with("HTTP/1.1 409 Conflict\r\n"),
+            "{response}"
+        );
+        assert!(response.ends_with(r#"{"error":"Component Database is not running"}"#));
+
+        let response = request(address, "POST /recording/stop HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.ends_with(r#"{"file":null}"#), "not recording");
+
+        let response = request(address, "GET /topics HTTP/1.1\r\n\r\n").await;
+        assert!(
+            response.ends_with(r#"{"active":[],"inactive":[]}"#),
+            "{response}"
+        );
+
+        let response = request(address, "GET /health HTTP/1.1\r\n\r\n").await;
+        assert!(
+            response.ends_with(r#"{"qos_mismatches":[],"status":"ok"}"#),
+            "{response}"
+        );
+
+        let response = request(address, "GET /sinks HTTP/1.1\r\n\r\n").await;
+        assert!(response.ends_with(r#"{"sinks":[]}"#), "{response}");
+
+        let response = request(address, "garbage\r\n\r\n").await;
+        assert!(
+            response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+            "{response}"
+        );
+
+        drop(trigger);
+        server.await.unwrap();
+    }
+}
@@ -1,29 +1,219 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
 
+use log::{info, warn};
 use rerun::AsComponents;
 use ros_rerun_types::converter::Header;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::mpsc::{error::SendError, UnboundedReceiver, UnboundedSender};
+
+use crate::{config::Priority, metrics::METRICS};
 
 /// Represents any log data that can be sent between topology components
+///
+/// Sinks log all variants the same way, the variant tells where the data
+/// comes from for the components in between.
+#[derive(Clone)]
 pub enum LogData {
+    /// Output of a topic's converter, the archetype the topic is configured
+    /// with, on the topic's entity or below it.
     Archetype(LogComponents),
+    /// Several outputs of a topic's converter, e.g. one per entity of a batch.
     ArchetypeArray(Vec<LogComponents>),
+    /// Components the bridge logs alongside the converted data, e.g. static
+    /// metadata of a topic or messages missing from its sequence.
     AnyComponents(LogComponents),
+    /// Several `AnyComponents` at once, e.g. the self metrics of the bridge.
     AnyComponentsArray(Vec<LogComponents>),
+    Columns(LogColumns),
+}
+
+impl LogData {
+    /// Entity path of the data, used to track per-topic state.
+    pub fn entity_path(&self) -> Option<&Arc<String>> {
+        match self {
+            Self::Archetype(comps) | Self::AnyComponents(comps) => Some(&comps.entity_path),
+            Self::ArchetypeArray(comps) | Self::AnyComponentsArray(comps) => {
+                comps.first().map(|comps| &comps.entity_path)
+            }
+            Self::Columns(columns) => Some(&columns.entity_path),
+        }
+    }
+
+    /// Whether all contained components are static.
+    pub fn is_static(&self) -> bool {
+        match self {
+            Self::Archetype(comps) | Self::AnyComponents(comps) => comps.is_static,
+            Self::ArchetypeArray(comps) | Self::AnyComponentsArray(comps) => {
+                comps.iter().all(|comps| comps.is_static)
+            }
+            Self::Columns(_) => false,
+        }
+    }
+
+    /// Highest priority of the contained components.
+    pub fn priority(&self) -> Priority {
+        match self {
+            Self::Archetype(comps) | Self::AnyComponents(comps) => comps.priority,
+            Self::ArchetypeArray(comps) | Self::AnyComponentsArray(comps) => comps
+                .iter()
+                .map(|comps| comps.priority)
+                .max()
+                .unwrap_or_default(),
+            Self::Columns(columns) => columns.priority,
+        }
+    }
 }
 
 /// All data for logging a Rerun archetype or custom components
+#[derive(Clone)]
 pub struct LogComponents {
     pub entity_path: Arc<String>,
     pub header: Option<Arc<Header>>,
     pub components: Arc<dyn AsComponents + Send + Sync>,
+    pub priority: Priority,
+    /// Log the components as static, i.e. valid for all points in time.
+    pub is_static: bool,
+}
+
+/// Rows of components logged at once, each at its own time on the ROS
+/// timeline, see `ros_rerun_types::converter::ColumnData`.
+#[derive(Clone)]
+pub struct LogColumns {
+    pub entity_path: Arc<String>,
+    /// Timestamp of each row in nanoseconds since the Unix epoch.
+    pub times: Arc<Vec<i64>>,
+    pub columns: Arc<Vec<rerun::SerializedComponentColumn>>,
+    pub priority: Priority,
+}
+
+/// Tracks the messages queued for a sink that it has not logged yet.
+///
+/// The Rerun SDK does not expose how much data is buffered inside a
+/// `RecordingStream`, so the depth of the sink's input channel is used
+/// to detect that the sink cannot keep up.
+#[derive(Clone, Debug)]
+pub struct InFlight {
+    name: Arc<String>,
+    count: Arc<AtomicUsize>,
+    dropping: Arc<AtomicBool>,
+    limit: usize,
+}
+
+impl InFlight {
+    pub fn new(name: &str, limit: usize) -> Self {
+        Self {
+            name: Arc::new(name.to_owned()),
+            count: Arc::new(AtomicUsize::new(0)),
+            dropping: Arc::new(AtomicBool::new(false)),
+            limit,
+        }
+    }
+
+    /// Number of messages currently queued for the sink.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Whether a message with the given priority should be queued.
+    ///
+    /// See `Priority` for the drop policy.
+    pub fn admits(&self, priority: Priority) -> bool {
+        let count = self.count();
+        match priority {
+            Priority::Low => count < self.limit,
+            Priority::Normal => count < self.limit.saturating_mul(2),
+            Priority::High => true,
+        }
+    }
+
+    /// Mark a message as logged by the sink.
+    pub fn release(&self) {
+        let previous = self.count.fetch_sub(1, Ordering::Relaxed);
+        if previous <= self.limit / 2 && self.dropping.swap(false, Ordering::Relaxed) {
+            info!("Sink {} caught up, no longer dropping messages", self.name);
+        }
+    }
+
+    fn acquire(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn dropped(&self, priority: Priority) {
+        METRICS.backpressure_drop(priority);
+        if !self.dropping.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Sink {} has {} queued messages, dropping lower priority topics",
+                self.name,
+                self.count()
+            );
+        }
+    }
+}
+
+/// Sending half of a sink's input channel.
+#[derive(Clone)]
+pub struct SinkSender {
+    pub tx: UnboundedSender<LogData>,
+    pub in_flight: InFlight,
+}
+
+impl SinkSender {
+    /// Queue data for the sink, unless the sink is backed up
+    /// and the priority of the data is too low.
+    ///
+    /// Returns whether the data was queued.
+    ///
+    /// # Errors
+    /// Returns a `SendError` if the sink has shut down.
+    pub fn send(&self, data: LogData) -> Result<bool, SendError<LogData>> {
+        let priority = data.priority();
+        if !self.in_flight.admits(priority) {
+            self.in_flight.dropped(priority);
+            return Ok(false);
+        }
+        self.in_flight.acquire();
+        self.tx
+            .send(data)
+            .inspect_err(|_| self.in_flight.release())?;
+        Ok(true)
+    }
 }
 
 #[derive(Clone)]
 pub struct ArchetypeSender {
-    pub tx: Vec<UnboundedSender<LogData>>,
+    pub tx: Vec<SinkSender>,
 }
 
 pub struct ArchetypeReceiver {
     pub rx: UnboundedReceiver<LogData>,
+    pub in_flight: InFlight,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_by_priority() {
+        let in_flight = InFlight::new("test", 2);
+        for _ in 0..2 {
+            in_flight.acquire();
+        }
+        assert!(!in_flight.admits(Priority::Low), "low dropped at limit");
+        assert!(in_flight.admits(Priority::Normal), "normal kept at limit");
+        for _ in 0..2 {
+            in_flight.acquire();
+        }
+        assert!(!in_flight.admits(Priority::Normal), "normal dropped at 2x");
+        assert!(in_flight.admits(Priority::High), "high is never dropped");
+        for _ in 0..4 {
+            in_flight.release();
+        }
+        assert!(
+            in_flight.admits(Priority::Low),
+            "low kept after catching up"
+        );
+    }
 }
@@ -49,6 +49,8 @@ impl Default for Options {
 #[derive(Subcommand, Debug)]
 pub enum Subcommands {
     Configure(ConfigureOptions),
+    /// Print the fields of a ROS message type, for writing converter configs.
+    Schema(SchemaOptions),
 }
 
 #[derive(Args, Debug)]
@@ -57,6 +59,16 @@ pub struct ConfigureOptions {
     pub config: Option<PathBuf>,
 }
 
+#[derive(Args, Debug)]
+pub struct SchemaOptions {
+    /// ROS message type, e.g. `sensor_msgs/msg/Imu`
+    pub ros_type: String,
+
+    /// Print the fields as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::CONFIG;
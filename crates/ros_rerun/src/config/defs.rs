@@ -1,10 +1,18 @@
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
+use ros_rerun_types::{color::ColorSetting, wildcard_match};
 use serde::{Deserialize, Serialize};
 
 use super::ConfigError;
 
+/// Default seconds between self metrics, see `Config::self_metrics`.
+pub const DEFAULT_SELF_METRICS_INTERVAL: f64 = 1.0;
+
+/// Default Rerun application ID of the recordings, see `Config::application_id`.
+pub const DEFAULT_APPLICATION_ID: &str = "ros_rerun";
+
 /// Top level configuration
 ///
 /// Any changes to the configuration will eventually be reflected
@@ -12,7 +20,7 @@ use super::ConfigError;
 /// to allow pending logs to flush.
 #[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq)]
 pub struct Config {
-    /// GRPC server configuration
+    /// HTTP API configuration, see `crate::api`
     #[serde(default)]
     pub api: Api,
 
@@ -28,6 +36,59 @@ pub struct Config {
     #[serde(default)]
     pub db: DBConfig,
 
+    /// Rerun application ID of the recordings logged by the sinks, e.g. to
+    /// tell apart several bridges logging to one viewer.
+    /// Defaults to `DEFAULT_APPLICATION_ID`.
+    #[serde(default)]
+    pub application_id: Option<String>,
+
+    /// Rerun recording ID of the recordings logged by the sinks, so that the
+    /// viewer merges them into one recording. When unset, each recording
+    /// gets a random ID.
+    #[serde(default)]
+    pub recording_id: Option<String>,
+
+    /// Intermediate transforms between topics and sinks,
+    /// e.g. to stream a decimated view while recording at full rate.
+    #[serde(default)]
+    pub transforms: HashMap<String, TransformConfig>,
+
+    /// Orientation of the world, logged once as a static `ViewCoordinates`
+    /// on the root entity. See `Config::world_view_coordinates`.
+    #[serde(default)]
+    pub world_view_coordinates: Option<String>,
+
+    /// Topics recorded to MCAP as the original ROS messages.
+    #[serde(default)]
+    pub mcap_passthrough: Option<McapPassthroughConfig>,
+
+    /// Log the ROS domain ID, RMW implementation and distro as a static
+    /// recording property at startup, for reproducing the recording setup.
+    #[serde(default)]
+    pub log_environment: bool,
+
+    /// Log the converter, its version, and the topic and ROS type of every
+    /// topic entity once as static components, to tell which converter
+    /// produced the data of an entity.
+    #[serde(default)]
+    pub log_converter_info: bool,
+
+    /// Log the throughput, backpressure drops, sink queue depths and
+    /// conversion latency of the bridge as scalars under `_bridge/metrics`.
+    #[serde(default)]
+    pub self_metrics: bool,
+
+    /// Seconds between self metrics, defaults to `DEFAULT_SELF_METRICS_INTERVAL`.
+    #[serde(default)]
+    pub self_metrics_interval: Option<f64>,
+
+    /// Convert messages on a dedicated pool of this many threads, so CPU heavy
+    /// converters, e.g. of large point clouds or images, do not delay the ROS
+    /// executor and the sinks. Messages are converted on the main runtime when unset.
+    /// Changes apply when the bridge restarts.
+    #[serde(default)]
+    pub converter_threads: Option<NonZeroUsize>,
+
     /// Path where config was loaded from.
     #[serde(skip)]
     pub config_paths: Vec<PathBuf>,
@@ -41,8 +102,93 @@ impl Config {
     pub fn streams(&self) -> impl IntoIterator<Item = (&String, &StreamConfig)> {
         self.streams.iter().collect::<Vec<_>>()
     }
+
+    pub fn transforms(&self) -> impl IntoIterator<Item = (&String, &TransformConfig)> {
+        self.transforms.iter().collect::<Vec<_>>()
+    }
+
+    /// Interval at which self metrics are logged, `None` when disabled.
+    ///
+    /// # Errors
+    /// Returns an error if the interval is not a positive number of seconds.
+    pub fn self_metrics_interval(&self) -> anyhow::Result<Option<std::time::Duration>> {
+        let secs = self
+            .self_metrics_interval
+            .unwrap_or(DEFAULT_SELF_METRICS_INTERVAL);
+        let interval = std::time::Duration::try_from_secs_f64(secs)
+            .ok()
+            .filter(|interval| !interval.is_zero())
+            .ok_or_else(|| anyhow::anyhow!("self_metrics_interval must be a positive number"))?;
+        Ok(self.self_metrics.then_some(interval))
+    }
+
+    /// Rerun application ID of the recordings, see `Config::application_id`.
+    pub fn application_id(&self) -> &str {
+        self.application_id
+            .as_deref()
+            .unwrap_or(DEFAULT_APPLICATION_ID)
+    }
+
+    /// Validate the configured Rerun application and recording IDs
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if an ID is empty.
+    pub fn validate_recording_ids(&self) -> Result<(), ConfigError> {
+        if self.application_id().trim().is_empty() {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "application_id must not be empty"
+            )));
+        }
+        if self
+            .recording_id
+            .as_ref()
+            .is_some_and(|id| id.trim().is_empty())
+        {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "recording_id must not be empty"
+            )));
+        }
+        Ok(())
+    }
+
+    /// View coordinates of the world, defaulting to the ROS convention `FLU`
+    /// (X forward, Y left, Z up).
+    ///
+    /// Accepts axis codes like `FLU` or `RDF`, and the `RIGHT_HAND_Z_UP` style
+    /// names. View coordinates logged on an entity, e.g. by a camera converter,
+    /// apply to that entity's subtree instead.
+    ///
+    /// # Errors
+    /// Returns an error if the name is not a known view coordinate system.
+    pub fn world_view_coordinates(&self) -> anyhow::Result<rerun::ViewCoordinates> {
+        let name = self.world_view_coordinates.as_deref().unwrap_or("FLU");
+        let coordinates = match name.to_ascii_uppercase().as_str() {
+            "FLU" => rerun::ViewCoordinates::FLU(),
+            "FRD" => rerun::ViewCoordinates::FRD(),
+            "RDF" => rerun::ViewCoordinates::RDF(),
+            "RFU" => rerun::ViewCoordinates::RFU(),
+            "RUB" => rerun::ViewCoordinates::RUB(),
+            "LUF" => rerun::ViewCoordinates::LUF(),
+            "RIGHT_HAND_X_UP" => rerun::ViewCoordinates::RIGHT_HAND_X_UP(),
+            "RIGHT_HAND_X_DOWN" => rerun::ViewCoordinates::RIGHT_HAND_X_DOWN(),
+            "RIGHT_HAND_Y_UP" => rerun::ViewCoordinates::RIGHT_HAND_Y_UP(),
+            "RIGHT_HAND_Y_DOWN" => rerun::ViewCoordinates::RIGHT_HAND_Y_DOWN(),
+            "RIGHT_HAND_Z_UP" => rerun::ViewCoordinates::RIGHT_HAND_Z_UP(),
+            "RIGHT_HAND_Z_DOWN" => rerun::ViewCoordinates::RIGHT_HAND_Z_DOWN(),
+            "LEFT_HAND_X_UP" => rerun::ViewCoordinates::LEFT_HAND_X_UP(),
+            "LEFT_HAND_X_DOWN" => rerun::ViewCoordinates::LEFT_HAND_X_DOWN(),
+            "LEFT_HAND_Y_UP" => rerun::ViewCoordinates::LEFT_HAND_Y_UP(),
+            "LEFT_HAND_Y_DOWN" => rerun::ViewCoordinates::LEFT_HAND_Y_DOWN(),
+            "LEFT_HAND_Z_UP" => rerun::ViewCoordinates::LEFT_HAND_Z_UP(),
+            "LEFT_HAND_Z_DOWN" => rerun::ViewCoordinates::LEFT_HAND_Z_DOWN(),
+            _ => anyhow::bail!("unknown world view coordinates '{name}'"),
+        };
+        Ok(coordinates)
+    }
 }
 
+/// HTTP API to control the running bridge. Changes apply when the bridge
+/// restarts.
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct Api {
     pub enabled: bool,
@@ -59,38 +205,619 @@ impl Default for Api {
     }
 }
 
+/// `ros_type` of a topic that may have any type, see `TopicSource::ros_type`.
+pub const ANY_ROS_TYPE: &str = "*";
+
+/// Archetype of topics logged as video, see `TopicSource::validate_video`.
+pub const VIDEO_ARCHETYPE: &str = "VideoStream";
+
 #[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
 pub struct TopicSource {
     pub topic: String,
+
+    /// Whether the topic is subscribed to, `true` when unset. Disabling a
+    /// topic keeps its block in the config, e.g. to toggle it while debugging.
+    pub enabled: Option<bool>,
+
+    /// ROS type of the topic, e.g. `std_msgs/msg/String`.
+    ///
+    /// When omitted, the type is discovered from the ROS graph once the topic
+    /// appears, and the converter is picked for that type and the archetype.
+    /// `"*"` (`ANY_ROS_TYPE`) also discovers the type, but always uses the
+    /// generic converter of the archetype, which must have one.
     pub ros_type: Option<String>,
     pub archetype: String,
 
+    /// Priority of the topic when a sink is backed up.
+    #[serde(default)]
+    pub priority: Priority,
+
+    /// Whether the entity should be shown when the recording is first opened.
+    ///
+    /// Recorded as a static `ros_rerun.EntityHints:visible` component.
+    /// The Rerun viewer takes visibility from the blueprint and does not
+    /// act on this hint yet.
+    pub visible: Option<bool>,
+
+    /// Color of the topic's data, unless the converter sets one per message.
+    ///
+    /// Logged as the static color component of the topic's archetype,
+    /// which the viewer does honor for all points in time.
+    pub default_color: Option<ColorSetting>,
+
+    /// Whether the entity should be expanded in the streams tree.
+    ///
+    /// Recorded as a static `ros_rerun.EntityHints:expand` component,
+    /// which the viewer does not act on yet.
+    pub expand: Option<bool>,
+
+    /// Seconds added to the timestamps of the topic, which may be negative,
+    /// to align sensors whose clocks are offset from the rest of the system.
+    ///
+    /// Applied to the logged time, after falling back to the receive time
+    /// for messages without a stamp.
+    pub time_offset: Option<f64>,
+
+    /// Clear the child entities of the topic that a message no longer logs,
+    /// e.g. the rings of a split cloud that are missing from the next cloud.
+    /// Otherwise the viewer keeps showing the last data of such entities.
+    #[serde(default)]
+    pub clear_stale: bool,
+
+    /// Whether stale child entities are cleared along with their subtree.
+    ///
+    /// A recursive `Clear` also hides everything below an entity, a flat one
+    /// only the entity itself. Defaults to recursive for entities that the
+    /// converter logged children below, e.g. nested frames, and to flat
+    /// for the others.
+    pub clear_recursive: Option<bool>,
+
+    /// Dotted path to an integer sequence number of the messages, e.g.
+    /// `header.seq` or `seq`, to detect messages lost before the bridge.
+    ///
+    /// When the sequence skips ahead, the number of missing messages is
+    /// logged as a scalar and a warning on the `dropped` child entity.
+    /// Messages without the field are not checked.
+    pub sequence_field: Option<String>,
+
+    /// Quality of service of the subscription, see `TopicSource::qos_profile`.
+    pub qos: Option<QosConfig>,
+
+    /// Maximum rate in Hz at which messages of the topic are converted.
+    ///
+    /// Messages received sooner after the previous converted one are
+    /// dropped before conversion, unlike the `rate` of a transform, which
+    /// only applies after the messages were converted.
+    pub max_rate_hz: Option<f64>,
+
+    /// Convert only every n-th message of the topic, see `max_rate_hz`.
+    pub decimate: Option<usize>,
+
+    /// Entity path the topic is logged to instead of the topic name,
+    /// e.g. `robot/{frame_id}/{topic}`.
+    ///
+    /// `{topic}` expands to the topic name and `{frame_id}` to the frame
+    /// of each message's header. Messages without a frame are logged to
+    /// the topic's entity. Converters that choose the entity of a message
+    /// themselves, e.g. the transforms of the TF tree, are not affected.
+    pub entity_path: Option<String>,
+
     /// Additional settings for the converter
     #[serde(flatten)]
     pub converter: toml::Table,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+impl TopicSource {
+    /// Whether the topic is subscribed to, see `TopicSource::enabled`.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Whether the topic accepts any ROS type and uses a generic converter.
+    pub fn is_any_ros_type(&self) -> bool {
+        self.ros_type.as_deref() == Some(ANY_ROS_TYPE)
+    }
+
+    /// The `time_offset` in nanoseconds, zero when unset.
+    pub fn time_offset_nanos(&self) -> i64 {
+        self.time_offset
+            .map_or(0, |offset| (offset * 1e9).round() as i64)
+    }
+
+    /// Validate the topic configuration.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if the time offset is not finite,
+    /// the QoS policies, rate limit or entity path are invalid, or the
+    /// topic cannot be logged as video, see `TopicSource::validate_video`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.validate_video()?;
+        if self.time_offset.is_some_and(|offset| !offset.is_finite()) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Time offset of topic '{}' must be a finite number",
+                self.topic
+            )));
+        }
+        if let Some(qos) = &self.qos {
+            qos.validate()?;
+        }
+        if self
+            .max_rate_hz
+            .is_some_and(|rate| !(rate.is_finite() && rate > 0.0))
+        {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Maximum rate of topic '{}' must be a positive number",
+                self.topic
+            )));
+        }
+        if self.decimate == Some(0) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Decimation of topic '{}' must be at least 1",
+                self.topic
+            )));
+        }
+        if let Some(template) = &self.entity_path {
+            let rest = template
+                .replace(TOPIC_PLACEHOLDER, "")
+                .replace(FRAME_ID_PLACEHOLDER, "");
+            if rest.contains(['{', '}']) {
+                return Err(ConfigError::Validation(anyhow::anyhow!(
+                    "Entity path '{template}' of topic '{}' only supports the {TOPIC_PLACEHOLDER} \
+                     and {FRAME_ID_PLACEHOLDER} placeholders",
+                    self.topic
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a topic with the `VideoStream` archetype can be logged.
+    ///
+    /// Only H.264 frames of `sensor_msgs/msg/CompressedImage` topics are
+    /// logged as video, without re-encoding them, and only with the `video`
+    /// feature. Raw images are not encoded, as that needs an H.264 encoder.
+    /// The video goes to every sink of the topic, there is no per-frame
+    /// fallback for streams.
+    fn validate_video(&self) -> Result<(), ConfigError> {
+        if self.archetype != VIDEO_ARCHETYPE {
+            return Ok(());
+        }
+        if !cfg!(feature = "video") {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Topic '{}' is logged as video, which needs the `video` feature",
+                self.topic
+            )));
+        }
+        let is_compressed_image = |ros_type: &str| {
+            matches!(
+                ros_type,
+                "sensor_msgs/msg/CompressedImage" | "sensor_msgs/CompressedImage"
+            )
+        };
+        match self.known_ros_type() {
+            Some(ros_type) if !is_compressed_image(ros_type) => {
+                Err(ConfigError::Validation(anyhow::anyhow!(
+                    "Topic '{}' of type '{ros_type}' cannot be logged as video, only H.264 \
+                     sensor_msgs/msg/CompressedImage frames are",
+                    self.topic
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the `entity_path` depends on the frame of each message.
+    pub fn entity_path_uses_frame(&self) -> bool {
+        self.entity_path
+            .as_deref()
+            .is_some_and(|template| template.contains(FRAME_ID_PLACEHOLDER))
+    }
+
+    /// Entity path of a message in `frame`, see `TopicSource::entity_path`.
+    ///
+    /// Without a configured `entity_path` or a frame to fill in, this is
+    /// the topic name.
+    pub fn resolve_entity_path(&self, frame: Option<&str>) -> String {
+        self.entity_path
+            .as_deref()
+            .and_then(|template| expand_entity_path(template, &self.topic, frame))
+            .unwrap_or_else(|| self.topic.clone())
+    }
+
+    /// The configured ROS type, unless it has to be discovered at runtime.
+    pub fn known_ros_type(&self) -> Option<&str> {
+        self.ros_type
+            .as_deref()
+            .filter(|ros_type| *ros_type != ANY_ROS_TYPE)
+    }
+
+    /// Quality of service the topic is subscribed with.
+    ///
+    /// Starts from the ROS sensor data profile for topics matching
+    /// `SENSOR_DATA_TOPICS` and from the default profile otherwise, then
+    /// applies the configured `qos` policies. The sensor data profile is
+    /// best effort, which also receives the messages of reliable publishers.
+    pub fn qos_profile(&self) -> rclrs::QoSProfile {
+        let sensor_data = SENSOR_DATA_TOPICS
+            .iter()
+            .any(|pattern| glob_match(&path_parts(pattern), &path_parts(&self.topic)));
+        let profile = if sensor_data {
+            rclrs::QOS_PROFILE_SENSOR_DATA
+        } else {
+            rclrs::QOS_PROFILE_DEFAULT
+        };
+        self.qos.unwrap_or_default().apply(profile)
+    }
+}
+
+/// Placeholder for the topic name in `TopicSource::entity_path`.
+pub const TOPIC_PLACEHOLDER: &str = "{topic}";
+
+/// Placeholder for the header frame in `TopicSource::entity_path`.
+pub const FRAME_ID_PLACEHOLDER: &str = "{frame_id}";
+
+/// Expand the placeholders of an entity path `template`, see
+/// `TopicSource::entity_path`. Returns `None` when the template needs
+/// a frame and `frame` is missing or empty.
+pub fn expand_entity_path(template: &str, topic: &str, frame: Option<&str>) -> Option<String> {
+    let path = template.replace(TOPIC_PLACEHOLDER, topic.trim_start_matches('/'));
+    if !path.contains(FRAME_ID_PLACEHOLDER) {
+        return Some(path);
+    }
+    let frame = frame
+        .map(|frame| frame.trim_matches('/'))
+        .filter(|frame| !frame.is_empty())?;
+    Some(path.replace(FRAME_ID_PLACEHOLDER, frame))
+}
+
+/// Topic patterns subscribed with the sensor data QoS profile unless
+/// configured otherwise, see `TopicSource::qos_profile`.
+pub const SENSOR_DATA_TOPICS: [&str; 5] = [
+    "**/image*",
+    "**/*points*",
+    "**/scan*",
+    "**/imu*",
+    "**/camera_info",
+];
+
+/// Quality of service policies of a topic subscription, each keeping the
+/// policy of the topic's default profile when unset.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct QosConfig {
+    pub reliability: Option<QosReliability>,
+    pub durability: Option<QosDurability>,
+    pub history: Option<QosHistory>,
+    /// Number of messages kept with the `keep_last` history.
+    pub depth: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum QosReliability {
+    Reliable,
+    BestEffort,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum QosDurability {
+    Volatile,
+    /// Also receive the last messages published before subscribing, e.g. maps.
+    TransientLocal,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum QosHistory {
+    KeepLast,
+    KeepAll,
+}
+
+impl QosConfig {
+    /// Override the policies of `profile` with the configured ones.
+    pub fn apply(&self, mut profile: rclrs::QoSProfile) -> rclrs::QoSProfile {
+        if let Some(reliability) = self.reliability {
+            profile.reliability = match reliability {
+                QosReliability::Reliable => rclrs::QoSReliabilityPolicy::Reliable,
+                QosReliability::BestEffort => rclrs::QoSReliabilityPolicy::BestEffort,
+            };
+        }
+        if let Some(durability) = self.durability {
+            profile.durability = match durability {
+                QosDurability::Volatile => rclrs::QoSDurabilityPolicy::Volatile,
+                QosDurability::TransientLocal => rclrs::QoSDurabilityPolicy::TransientLocal,
+            };
+        }
+        let profile_depth = match profile.history {
+            rclrs::QoSHistoryPolicy::KeepLast { depth }
+            | rclrs::QoSHistoryPolicy::SystemDefault { depth } => Some(depth),
+            rclrs::QoSHistoryPolicy::KeepAll => None,
+        };
+        match self.history {
+            Some(QosHistory::KeepAll) => profile.history = rclrs::QoSHistoryPolicy::KeepAll,
+            Some(QosHistory::KeepLast) => {
+                profile.history = rclrs::QoSHistoryPolicy::KeepLast {
+                    depth: self.depth.or(profile_depth).unwrap_or(DEFAULT_QOS_DEPTH),
+                };
+            }
+            // A depth alone implies the keep_last history
+            None => {
+                if let Some(depth) = self.depth {
+                    profile.history = rclrs::QoSHistoryPolicy::KeepLast { depth };
+                }
+            }
+        }
+        profile
+    }
+
+    /// Validate the policies.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if a depth is set for `keep_all`, or is zero.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.depth == Some(0) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "QoS depth must be at least 1"
+            )));
+        }
+        if self.history == Some(QosHistory::KeepAll) && self.depth.is_some() {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "QoS depth only applies to the keep_last history"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Depth of a `keep_last` history when neither the config nor the
+/// profile it starts from has one.
+pub const DEFAULT_QOS_DEPTH: u32 = 10;
+
+/// Priority of a topic's messages when a sink applies backpressure.
+///
+/// Each sink counts the messages queued for it that it has not logged yet.
+/// Once that count exceeds the sink's `max_in_flight`, messages from `low`
+/// priority topics are dropped for that sink. Above twice the limit, `normal`
+/// priority messages are dropped as well. `high` priority messages are never
+/// dropped. Messages are dropped per sink, so a slow stream does not affect
+/// what is recorded by the others.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Default number of messages that may be queued for a sink
+/// before it starts dropping low priority topics.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 1024;
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq, Eq)]
 pub struct StreamConfig {
     pub inputs: Vec<String>,
+    /// URL of the Rerun gRPC server, required unless `spawn` is set.
+    #[serde(default)]
     pub url: String,
+
+    /// Spawn a local Rerun viewer and stream to it instead of connecting
+    /// to `url`, for quick local debugging. When no display is available,
+    /// or the viewer cannot be spawned, the stream is saved to a `.rrd`
+    /// file in `spawn_fallback_dir` instead.
+    #[serde(default)]
+    pub spawn: bool,
+
+    /// Directory the stream is saved to when `spawn` cannot show a viewer.
+    /// Defaults to the working directory.
+    #[serde(default)]
+    pub spawn_fallback_dir: Option<PathBuf>,
+
+    /// Queued message count at which the stream applies backpressure,
+    /// see `Priority`. Defaults to `DEFAULT_MAX_IN_FLIGHT`.
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+
+    /// Entity path globs, e.g. `/camera/**`, selecting what the stream logs.
+    ///
+    /// Routes are applied after `inputs`: only data from the inputs reaches
+    /// the stream, and of that only entity paths matching a route are logged.
+    /// `*` matches within a path part and `**` matches any number of parts.
+    /// When empty, everything from the inputs is logged.
+    #[serde(default)]
+    pub route: Vec<String>,
+
+    /// Log the start and the shutdown of the stream as a `TextLog` on the
+    /// `_bridge/status` entity. The Rerun SDK reconnects in the background
+    /// without reporting dropped connections, so those are not logged.
+    #[serde(default)]
+    pub log_status: bool,
+
+    /// Log a recursive `Clear` on the root entity when the bridge shuts down,
+    /// so that connected viewers do not keep showing stale data.
+    #[serde(default)]
+    pub clear_on_shutdown: bool,
+
+    /// Largest message logged to the stream in bytes, as estimated from the
+    /// size of its components. Larger messages, e.g. huge meshes or dense
+    /// clouds, exceed the gRPC message size limit and are dropped with an
+    /// error instead. Defaults to no limit.
+    #[serde(default)]
+    pub max_grpc_message_bytes: Option<usize>,
+
+    #[serde(flatten)]
+    pub time_dedup: TimeDedupConfig,
+}
+
+impl StreamConfig {
+    /// Validate the stream configuration
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if the stream configuration is invalid.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.time_dedup.validate()?;
+        if !self.spawn && self.url.trim().is_empty() {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Stream needs a url unless it spawns a viewer"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the stream logs data for `entity_path`.
+    pub fn routes(&self, entity_path: &str) -> bool {
+        self.route.is_empty()
+            || self
+                .route
+                .iter()
+                .any(|pattern| glob_match(&path_parts(pattern), &path_parts(entity_path)))
+    }
+}
+
+fn path_parts(path: &str) -> Vec<&str> {
+    path.split('/').filter(|part| !part.is_empty()).collect()
+}
+
+/// Match entity path parts against pattern parts, see `StreamConfig::route`.
+fn glob_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_match(rest, &path[skip..])),
+        Some((part, rest)) => path
+            .split_first()
+            .is_some_and(|(name, path)| wildcard_match(part, name) && glob_match(rest, path)),
+    }
+}
+
+/// Default of `TimeDedupConfig::dedup_epsilon_ns`.
+pub const DEFAULT_DEDUP_EPSILON_NS: u64 = 1;
+
+/// How a sink separates messages with the same timestamp on an entity.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeDedup {
+    /// Log a duplicate `dedup_epsilon_ns` after the previous message of the entity.
+    Nudge,
+    /// Keep the timestamps, and also log each message of the entity at its
+    /// index on the `ros_sequence` timeline, where none of them coincide.
+    Sequence,
+}
+
+/// Handling of messages on an entity with the same timestamp, e.g. several
+/// messages stamped at once during startup, which otherwise overwrite each
+/// other on the timeline. Off by default.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TimeDedupConfig {
+    /// How duplicate timestamps are separated, see `TimeDedup`.
+    #[serde(default)]
+    pub dedup_time: Option<TimeDedup>,
+
+    /// Nanoseconds a duplicate timestamp is moved by with `nudge`,
+    /// defaults to `DEFAULT_DEDUP_EPSILON_NS`.
+    #[serde(default)]
+    pub dedup_epsilon_ns: Option<u64>,
+}
+
+impl TimeDedupConfig {
+    /// Validate the deduplication configuration
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if the epsilon is zero.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.dedup_epsilon_ns == Some(0) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "dedup_epsilon_ns must be at least 1"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A transform forwarding a reduced rate of its inputs.
+///
+/// Rate limiting and decimation are applied per input topic.
+/// When both are set, a message must pass both to be forwarded.
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct TransformConfig {
+    /// Topics or other transforms feeding this transform.
+    pub inputs: Vec<String>,
+
+    /// Maximum rate in Hz at which messages are forwarded.
+    #[serde(default)]
+    pub rate: Option<f64>,
+
+    /// Forward only every n-th message.
+    #[serde(default)]
+    pub decimate: Option<usize>,
+}
+
+impl TransformConfig {
+    /// Validate the transform configuration
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if the rate or decimation is invalid.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self
+            .rate
+            .is_some_and(|rate| !(rate.is_finite() && rate > 0.0))
+        {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Transform rate must be a positive number"
+            )));
+        }
+        if self.decimate == Some(0) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "Transform decimation must be at least 1"
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq, Eq)]
 pub struct DBConfig {
+    /// Whether the bridge records to the database. When disabled, no
+    /// database sink is created and its inputs are not subscribed for it.
     pub enabled: Option<bool>,
     pub data_dir: PathBuf,
     pub inputs: Vec<String>,
+
+    /// Queued message count at which the database applies backpressure,
+    /// see `Priority`. Defaults to `DEFAULT_MAX_IN_FLIGHT`.
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+
+    /// Size in megabytes after which recording continues in a new file.
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    /// Duration in seconds after which recording continues in a new file.
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+
+    #[serde(flatten)]
+    pub time_dedup: TimeDedupConfig,
 }
 
 impl DBConfig {
+    /// Whether the database sink is created, see `DBConfig::enabled`.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
     /// Validate the DB configuration
     ///
     /// # Errors
     /// Returns `ConfigError::Validation` if the DB configuration is invalid.
     pub fn validate(&self) -> Result<(), ConfigError> {
+        self.time_dedup.validate()?;
+        if self.max_file_size_mb == Some(0) || self.max_duration_secs == Some(0) {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "DB file size and duration limits must be at least 1"
+            )));
+        }
         // If the DB config is disabled, skip validation
-        if self.enabled.is_some_and(|e| !e) {
+        if !self.is_enabled() {
             return Ok(());
         }
         // Check if the data directory is valid
@@ -102,3 +829,38 @@ impl DBConfig {
         Ok(())
     }
 }
+
+/// Records the messages of some topics unconverted, with their ROS schemas,
+/// so the output is a ROS 2 bag that `ros2 bag` and other ROS tooling can read.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct McapPassthroughConfig {
+    /// Directory the MCAP file is written to.
+    pub data_dir: PathBuf,
+
+    /// Names of the `[topics]` entries to record.
+    pub topics: Vec<String>,
+}
+
+impl McapPassthroughConfig {
+    /// Validate the MCAP passthrough configuration
+    ///
+    /// # Errors
+    /// Returns `ConfigError::Validation` if the data directory or a topic is invalid.
+    pub fn validate(&self, topics: &HashMap<String, TopicSource>) -> Result<(), ConfigError> {
+        if !self.data_dir.is_dir() {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "MCAP passthrough data directory must be a valid directory"
+            )));
+        }
+        if let Some(topic) = self
+            .topics
+            .iter()
+            .find(|topic| !topics.contains_key(*topic))
+        {
+            return Err(ConfigError::Validation(anyhow::anyhow!(
+                "MCAP passthrough topic '{topic}' is not defined in [topics]"
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,19 @@
 use anyhow::Result;
 use log::error;
 use parking_lot::RwLock;
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 use thiserror::Error;
+use tokio::sync::mpsc::UnboundedSender;
 use toml::de::Error as TomlError;
 use toml::ser::Error as TomlSeError;
 
 pub mod defs;
-pub use defs::{Api, Config, DBConfig, StreamConfig, TopicSource};
+pub use defs::{
+    Api, Config, DBConfig, McapPassthroughConfig, Priority, QosConfig, StreamConfig, TimeDedup,
+    TimeDedupConfig, TopicSource, TransformConfig, ANY_ROS_TYPE, VIDEO_ARCHETYPE,
+};
 
 use crate::cli::Options;
 
@@ -64,6 +69,67 @@ pub fn load(options: &Options) -> Result<(), ConfigError> {
     }
 }
 
+/// Read the configuration file the bridge was started with again, e.g.
+/// after it changed, see `watch`.
+///
+/// `CONFIG` is left as is, so it can be replaced once the new configuration
+/// has been applied. The API settings are kept, since the server keeps
+/// running and they may have been overridden on the command line.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or the new configuration is invalid.
+pub fn reload() -> Result<Config, ConfigError> {
+    let (path, api) = {
+        let config = CONFIG.read();
+        let path = config.config_paths.first().ok_or(ConfigError::NotFound)?;
+        (path.clone(), config.api.clone())
+    };
+    let mut config = read_config(&path)?;
+    config.api = api;
+    Ok(config)
+}
+
+/// Watch the configuration files at `paths`, sending on `changes`
+/// whenever one of them is written, created or replaced.
+///
+/// The directories of the files are watched, as editors often replace
+/// a file instead of writing to it. Events stop when the returned watcher
+/// is dropped.
+///
+/// # Errors
+/// Returns an error if a directory cannot be watched.
+pub fn watch(
+    paths: &[PathBuf],
+    changes: UnboundedSender<()>,
+) -> notify::Result<notify::RecommendedWatcher> {
+    let files: Vec<_> = paths
+        .iter()
+        .map(|path| fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        .collect();
+    let watched = files.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if is_config_change(&event, &watched) => {
+                let _ = changes.send(());
+            }
+            Ok(_) => {}
+            Err(err) => error!("Failed to watch the configuration: {err}"),
+        })?;
+    let dirs: BTreeSet<_> = files.iter().filter_map(|file| file.parent()).collect();
+    for dir in dirs {
+        watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}
+
+/// Whether a file system event changes one of the configuration `files`.
+fn is_config_change(event: &notify::Event, files: &[PathBuf]) -> bool {
+    matches!(
+        event.kind,
+        notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+    ) && event.paths.iter().any(|path| files.contains(path))
+}
+
 /// Load configuration file and log errors.
 fn load_from_path(path: &Path) -> Result<(), ConfigError> {
     match read_config(path) {
@@ -97,6 +163,21 @@ fn read_config(path: &Path) -> Result<Config, ConfigError> {
 
 fn validate_config(config: &Config) -> Result<(), ConfigError> {
     config.db.validate()?;
+    config.validate_recording_ids()?;
+    config.world_view_coordinates()?;
+    config.self_metrics_interval()?;
+    for topic in config.topics.values() {
+        topic.validate()?;
+    }
+    for stream in config.streams.values() {
+        stream.validate()?;
+    }
+    for transform in config.transforms.values() {
+        transform.validate()?;
+    }
+    if let Some(passthrough) = &config.mcap_passthrough {
+        passthrough.validate(&config.topics)?;
+    }
 
     Ok(())
 }
@@ -110,6 +191,97 @@ mod tests {
         toml::from_str::<Config>("").unwrap();
     }
 
+    #[test]
+    fn world_view_coordinates_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.world_view_coordinates().is_ok(), "defaults to FLU");
+        let config: Config =
+            toml::from_str(r#"world_view_coordinates = "right_hand_y_up""#).unwrap();
+        assert!(config.world_view_coordinates().is_ok());
+        let config: Config = toml::from_str(r#"world_view_coordinates = "up""#).unwrap();
+        assert!(config.world_view_coordinates().is_err());
+    }
+
+    #[test]
+    fn recording_ids_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.application_id(), defs::DEFAULT_APPLICATION_ID);
+        assert_eq!(config.recording_id, None);
+        let config: Config =
+            toml::from_str("application_id = \"robot_a\"\nrecording_id = \"run_1\"").unwrap();
+        assert_eq!(config.application_id(), "robot_a");
+        assert_eq!(config.recording_id.as_deref(), Some("run_1"));
+        assert!(config.validate_recording_ids().is_ok());
+        let config: Config = toml::from_str(r#"recording_id = "  ""#).unwrap();
+        assert!(config.validate_recording_ids().is_err());
+    }
+
+    #[test]
+    fn stream_routes() {
+        let stream = StreamConfig {
+            route: vec!["/camera/**".into(), "/lidar_*/points".into()],
+            ..Default::default()
+        };
+        assert!(stream.routes("/camera"));
+        assert!(stream.routes("/camera/left/image"));
+        assert!(stream.routes("/lidar_front/points"));
+        assert!(!stream.routes("/lidar_front/scan"));
+        assert!(!stream.routes("/cameras"));
+        assert!(StreamConfig::default().routes("/anything"), "no routes");
+    }
+
+    #[test]
+    fn stream_time_dedup() {
+        let config: Config = toml::from_str(
+            r#"
+            [streams.viewer]
+            inputs = []
+            url = "rerun+http://127.0.0.1:9876/proxy"
+            dedup_time = "nudge"
+            dedup_epsilon_ns = 1000
+            "#,
+        )
+        .unwrap();
+        let dedup = config.streams["viewer"].time_dedup;
+        assert_eq!(dedup.dedup_time, Some(TimeDedup::Nudge));
+        assert_eq!(dedup.dedup_epsilon_ns, Some(1000));
+        assert!(dedup.validate().is_ok());
+        let invalid = TimeDedupConfig {
+            dedup_epsilon_ns: Some(0),
+            ..dedup
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn stream_spawn_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [streams.local]
+            inputs = []
+            spawn = true
+            "#,
+        )
+        .unwrap();
+        let stream = &config.streams["local"];
+        assert!(stream.spawn && stream.url.is_empty());
+        assert!(stream.validate().is_ok(), "spawned viewers need no url");
+        let stream = StreamConfig {
+            spawn: false,
+            ..stream.clone()
+        };
+        assert!(stream.validate().is_err());
+    }
+
+    #[test]
+    fn converter_threads_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.converter_threads, None, "main runtime by default");
+        let config: Config = toml::from_str("converter_threads = 4").unwrap();
+        assert_eq!(config.converter_threads.map(usize::from), Some(4));
+        assert!(toml::from_str::<Config>("converter_threads = 0").is_err());
+    }
+
     #[test]
     fn default_config() {
         let config: Config = toml::from_str("").unwrap();
@@ -137,6 +309,186 @@ mod tests {
         assert_eq!(topic.archetype, "TextLog");
     }
 
+    #[test]
+    fn config_changes_from_events() {
+        use notify::event::{AccessKind, CreateKind, EventKind, ModifyKind, RenameMode};
+
+        let file = PathBuf::from("/etc/ros_rerun/config.toml");
+        let event = |kind, path: &str| notify::Event::new(kind).add_path(PathBuf::from(path));
+        let files = [file.clone()];
+        let written = EventKind::Modify(ModifyKind::Any);
+        assert!(is_config_change(
+            &event(written, "/etc/ros_rerun/config.toml"),
+            &files
+        ));
+        assert!(
+            is_config_change(
+                &event(
+                    EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+                    "/etc/ros_rerun/config.toml"
+                ),
+                &files
+            ),
+            "replaced by an editor"
+        );
+        assert!(is_config_change(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/etc/ros_rerun/config.toml"
+            ),
+            &files
+        ));
+        assert!(
+            !is_config_change(&event(written, "/etc/ros_rerun/.config.toml.swp"), &files),
+            "other files in the directory"
+        );
+        assert!(!is_config_change(
+            &event(
+                EventKind::Access(AccessKind::Any),
+                "/etc/ros_rerun/config.toml"
+            ),
+            &files
+        ));
+    }
+
+    #[test]
+    fn topics_entity_path_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [topics.camera]
+            topic = "/camera/image_raw"
+            archetype = "Image"
+            entity_path = "robot/{frame_id}/{topic}"
+
+            [topics.odom]
+            topic = "/odom"
+            archetype = "Transform3D"
+            entity_path = "robot/{topic}"
+
+            [topics.scan]
+            topic = "/scan"
+            archetype = "Points3D"
+            "#,
+        )
+        .unwrap();
+        assert!(config.topics.values().all(|topic| topic.validate().is_ok()));
+
+        let camera = &config.topics["camera"];
+        assert!(camera.entity_path_uses_frame());
+        assert_eq!(
+            camera.resolve_entity_path(Some("/camera_link")),
+            "robot/camera_link/camera/image_raw"
+        );
+        assert_eq!(
+            camera.resolve_entity_path(None),
+            "/camera/image_raw",
+            "the topic without a frame"
+        );
+        assert_eq!(camera.resolve_entity_path(Some("")), "/camera/image_raw");
+
+        let odom = &config.topics["odom"];
+        assert!(!odom.entity_path_uses_frame());
+        assert_eq!(odom.resolve_entity_path(Some("base_link")), "robot/odom");
+        assert_eq!(odom.resolve_entity_path(None), "robot/odom");
+        assert_eq!(
+            config.topics["scan"].resolve_entity_path(Some("laser")),
+            "/scan"
+        );
+
+        let invalid = TopicSource {
+            topic: "/scan".to_owned(),
+            entity_path: Some("robot/{frame}".to_owned()),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err(), "unknown placeholder");
+    }
+
+    #[test]
+    fn topics_video_config() {
+        let video = |ros_type: &str| TopicSource {
+            topic: "/camera/h264".to_owned(),
+            ros_type: Some(ros_type.to_owned()),
+            archetype: VIDEO_ARCHETYPE.to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(
+            video("sensor_msgs/msg/CompressedImage").validate().is_ok(),
+            cfg!(feature = "video"),
+            "H.264 frames are logged with the video feature"
+        );
+        assert!(
+            video("sensor_msgs/msg/Image").validate().is_err(),
+            "raw images are not encoded"
+        );
+    }
+
+    #[test]
+    fn topics_qos_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [topics.map]
+            topic = "/map"
+            archetype = "Image"
+            qos = { reliability = "reliable", durability = "transient_local", depth = 1 }
+
+            [topics.cloud]
+            topic = "/lidar/points"
+            archetype = "Points3D"
+
+            [topics.status]
+            topic = "/status"
+            archetype = "TextLog"
+            qos = { history = "keep_all" }
+            "#,
+        )
+        .unwrap();
+        assert!(config.topics.values().all(|topic| topic.validate().is_ok()));
+        assert!(!config.topics["map"].converter.contains_key("qos"));
+
+        let map = config.topics["map"].qos_profile();
+        assert_eq!(map.reliability, rclrs::QoSReliabilityPolicy::Reliable);
+        assert_eq!(map.durability, rclrs::QoSDurabilityPolicy::TransientLocal);
+        assert_eq!(map.history, rclrs::QoSHistoryPolicy::KeepLast { depth: 1 });
+
+        let cloud = config.topics["cloud"].qos_profile();
+        assert_eq!(
+            cloud.reliability,
+            rclrs::QoSReliabilityPolicy::BestEffort,
+            "sensor data by default"
+        );
+        let status = config.topics["status"].qos_profile();
+        assert_eq!(status.reliability, rclrs::QOS_PROFILE_DEFAULT.reliability);
+        assert_eq!(status.history, rclrs::QoSHistoryPolicy::KeepAll);
+
+        let invalid = TopicSource {
+            qos: Some(QosConfig {
+                depth: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn topics_priority_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [topics.example_msg]
+            topic = "example_topic"
+            archetype = "TextLog"
+            priority = "low"
+            field = "example_field"
+            "#,
+        )
+        .unwrap();
+
+        let topic = config.topics.get("example_msg").unwrap();
+        assert_eq!(topic.priority, Priority::Low);
+        assert_eq!(topic.converter.get("priority"), None);
+        assert_eq!(topic.converter.len(), 1);
+    }
+
     #[test]
     fn topics_settings_config() {
         let config: Config = toml::from_str(
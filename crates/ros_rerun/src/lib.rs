@@ -8,9 +8,12 @@
 
 pub mod ros_introspection;
 
+pub mod api;
 pub mod channel;
 pub mod cli;
 pub mod config;
+pub mod metrics;
 pub mod node;
+pub mod passthrough;
 pub mod topology;
 pub mod worker;
@@ -1,11 +1,14 @@
 use log::{error, info};
 use rclrs::{CreateBasicExecutor as _, InitOptions, RclrsErrorFilter as _, SpinOptions};
 use ros_rerun::{
-    cli::{Options, Subcommands},
+    cli::{Options, SchemaOptions, Subcommands},
     config,
     node::NodeGraph,
+    worker,
 };
+use ros_rerun_types::{dynamic_message::schema, ROSTypeName};
 use std::env;
+use stream_cancel::Tripwire;
 
 fn main() -> anyhow::Result<()> {
     let options = Options::new();
@@ -16,6 +19,9 @@ fn main() -> anyhow::Result<()> {
         .init();
 
     config::load(&options)?;
+    if let Some(threads) = config::CONFIG.read().converter_threads {
+        worker::start_conversion_runtime(threads)?;
+    }
 
     let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -25,9 +31,31 @@ fn main() -> anyhow::Result<()> {
         Some(Subcommands::Configure(configure_options)) => {
             info!("Configuring with options: {configure_options:?}");
         }
+        Some(Subcommands::Schema(schema_options)) => print_schema(&schema_options)?,
         None => rt.block_on(run())?,
     }
+    worker::stop_conversion_runtime();
+
+    Ok(())
+}
 
+/// Print the fields of a ROS message type, resolved from the ROS environment.
+fn print_schema(options: &SchemaOptions) -> anyhow::Result<()> {
+    let ros_type = ROSTypeName::try_from(options.ros_type.as_str()).map_err(|err| {
+        anyhow::anyhow!(
+            "ROS type '{}' is not available, is its package sourced? {err}",
+            options.ros_type
+        )
+    })?;
+    let msg = rclrs::DynamicMessage::new(ros_type.into())?;
+    let fields = schema(&msg.view());
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&fields)?);
+    } else {
+        for field in fields {
+            println!("{field}");
+        }
+    }
     Ok(())
 }
 
@@ -37,8 +65,22 @@ async fn run() -> anyhow::Result<()> {
     let context = rclrs::Context::new(env::args(), InitOptions::new())?;
     let mut executor = context.create_basic_executor();
     let graph = NodeGraph::new(&executor, "ros_rerun_bridge")?;
+    let (stop, stopped) = Tripwire::new();
+    let graph_handle = tokio::spawn(async move {
+        graph.run(stopped).await;
+    });
+
+    // rclrs installs no SIGINT handler, so halt spinning on Ctrl+C to
+    // shut down through the sinks instead of being killed
+    let commands = executor.commands().clone();
     tokio::spawn(async move {
-        graph.run().await;
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => {
+                info!("Received Ctrl+C, stopping the executor");
+                commands.halt_spinning();
+            }
+            Err(err) => error!("Failed to listen for Ctrl+C: {err}"),
+        }
     });
 
     info!("Bridge is running. Press Ctrl+C to exit.");
@@ -48,5 +90,11 @@ async fn run() -> anyhow::Result<()> {
         }
     });
 
+    // Spinning stops on Ctrl+C, the sinks still log the data in flight
+    stop.cancel();
+    if let Err(err) = graph_handle.await {
+        error!("Failed to shut down the bridge: {err}");
+    }
+
     Ok(())
 }
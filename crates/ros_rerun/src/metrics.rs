@@ -0,0 +1,226 @@
+//! Process-wide counters describing the health of the bridge.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::config::Priority;
+
+/// Global metrics, updated by the workers.
+pub static METRICS: Metrics = Metrics::new();
+
+/// Counters for messages flowing through the topology.
+#[derive(Debug)]
+pub struct Metrics {
+    messages_received: AtomicU64,
+    conversion_errors: AtomicU64,
+    partial_conversion_errors: AtomicU64,
+    dropped_low_priority: AtomicU64,
+    dropped_normal_priority: AtomicU64,
+    conversions: AtomicU64,
+    conversion_nanos: AtomicU64,
+}
+
+/// A point-in-time copy of the global metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub messages_received: u64,
+    pub conversion_errors: u64,
+    /// Parts of messages skipped while the rest of the message was logged.
+    pub partial_conversion_errors: u64,
+    pub dropped_low_priority: u64,
+    pub dropped_normal_priority: u64,
+    /// Messages passed to a converter, successfully or not.
+    pub conversions: u64,
+    /// Total time spent converting messages.
+    pub conversion_time: Duration,
+}
+
+impl MetricsSnapshot {
+    /// Messages dropped because of backpressure, of any priority.
+    pub fn dropped(&self) -> u64 {
+        self.dropped_low_priority + self.dropped_normal_priority
+    }
+
+    /// Rates of change since an `earlier` snapshot taken `elapsed` ago.
+    pub fn rates_since(&self, earlier: &Self, elapsed: Duration) -> MetricsRates {
+        let secs = elapsed.as_secs_f64();
+        let per_sec = |now: u64, then: u64| {
+            if secs > 0.0 {
+                now.saturating_sub(then) as f64 / secs
+            } else {
+                0.0
+            }
+        };
+        let conversions = self.conversions.saturating_sub(earlier.conversions);
+        MetricsRates {
+            messages_per_sec: per_sec(self.messages_received, earlier.messages_received),
+            dropped_per_sec: per_sec(self.dropped(), earlier.dropped()),
+            conversion_latency: (conversions > 0).then(|| {
+                self.conversion_time.saturating_sub(earlier.conversion_time)
+                    / u32::try_from(conversions).unwrap_or(u32::MAX)
+            }),
+        }
+    }
+}
+
+/// Throughput of the bridge between two metrics snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetricsRates {
+    pub messages_per_sec: f64,
+    pub dropped_per_sec: f64,
+    /// Mean time to convert a message, `None` when no message was converted.
+    pub conversion_latency: Option<Duration>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            messages_received: AtomicU64::new(0),
+            conversion_errors: AtomicU64::new(0),
+            partial_conversion_errors: AtomicU64::new(0),
+            dropped_low_priority: AtomicU64::new(0),
+            dropped_normal_priority: AtomicU64::new(0),
+            conversions: AtomicU64::new(0),
+            conversion_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub fn message_received(&self) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn conversion_error(&self) {
+        self.conversion_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the time taken to convert a message.
+    pub fn converted(&self, elapsed: Duration) {
+        self.conversions.fetch_add(1, Ordering::Relaxed);
+        self.conversion_nanos.fetch_add(
+            u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Record parts of a message that could not be converted.
+    pub fn partial_conversion_errors(&self, count: usize) {
+        self.partial_conversion_errors
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Record a message dropped because a sink was backed up.
+    pub fn backpressure_drop(&self, priority: Priority) {
+        let counter = match priority {
+            Priority::Low => &self.dropped_low_priority,
+            Priority::Normal => &self.dropped_normal_priority,
+            // High priority messages are never dropped.
+            Priority::High => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            conversion_errors: self.conversion_errors.load(Ordering::Relaxed),
+            partial_conversion_errors: self.partial_conversion_errors.load(Ordering::Relaxed),
+            dropped_low_priority: self.dropped_low_priority.load(Ordering::Relaxed),
+            dropped_normal_priority: self.dropped_normal_priority.load(Ordering::Relaxed),
+            conversions: self.conversions.load(Ordering::Relaxed),
+            conversion_time: Duration::from_nanos(self.conversion_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Counters of a single sink, for diagnosing gaps in its recording.
+#[derive(Debug, Default)]
+pub struct SinkStats {
+    messages_logged: AtomicU64,
+    log_errors: AtomicU64,
+    flush_errors: AtomicU64,
+    /// Time of the last successful flush in nanoseconds since the Unix epoch,
+    /// zero before the first flush.
+    last_flush: AtomicU64,
+}
+
+/// A point-in-time copy of the counters of a sink.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SinkStatsSnapshot {
+    pub messages_logged: u64,
+    pub log_errors: u64,
+    pub flush_errors: u64,
+    pub last_flush: Option<SystemTime>,
+    /// Size of the recording file, for sinks writing to disk.
+    pub bytes_written: Option<u64>,
+}
+
+impl SinkStats {
+    /// Record the result of logging a message.
+    pub fn message_logged(&self, ok: bool) {
+        let counter = if ok {
+            &self.messages_logged
+        } else {
+            &self.log_errors
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the result of flushing the sink.
+    pub fn flushed(&self, ok: bool) {
+        if !ok {
+            self.flush_errors.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|elapsed| u64::try_from(elapsed.as_nanos()).ok())
+            .unwrap_or_default();
+        self.last_flush.store(now, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> SinkStatsSnapshot {
+        let last_flush = self.last_flush.load(Ordering::Relaxed);
+        SinkStatsSnapshot {
+            messages_logged: self.messages_logged.load(Ordering::Relaxed),
+            log_errors: self.log_errors.load(Ordering::Relaxed),
+            flush_errors: self.flush_errors.load(Ordering::Relaxed),
+            last_flush: (last_flush > 0).then(|| UNIX_EPOCH + Duration::from_nanos(last_flush)),
+            bytes_written: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_between_snapshots() {
+        let earlier = MetricsSnapshot {
+            messages_received: 10,
+            dropped_low_priority: 1,
+            conversions: 10,
+            conversion_time: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let now = MetricsSnapshot {
+            messages_received: 30,
+            dropped_low_priority: 2,
+            dropped_normal_priority: 3,
+            conversions: 20,
+            conversion_time: Duration::from_millis(40),
+            ..Default::default()
+        };
+        let rates = now.rates_since(&earlier, Duration::from_secs(2));
+        assert_eq!(rates.messages_per_sec, 10.0);
+        assert_eq!(rates.dropped_per_sec, 2.0);
+        assert_eq!(rates.conversion_latency, Some(Duration::from_millis(3)));
+
+        let idle = now.rates_since(&now, Duration::ZERO);
+        assert_eq!(idle.messages_per_sec, 0.0);
+        assert_eq!(idle.conversion_latency, None);
+    }
+}
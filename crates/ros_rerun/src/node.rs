@@ -2,17 +2,25 @@ use std::{sync::Arc, time::Duration};
 
 use ahash::{HashMap, HashMapExt as _};
 use anyhow::Result;
-use log::error;
+use log::{error, info, warn};
 use parking_lot::Mutex;
 use rclrs::{Executor, Node, Promise};
-use rerun::external::re_log::error_once;
+use rerun::external::re_log::{error_once, warn_once};
 use ros_rerun_types::{converter::ConverterRegistry, ROSTypeName};
+use stream_cancel::Tripwire;
+use tokio::signal::unix::{signal, Signal, SignalKind};
 
 use crate::{
-    config::CONFIG,
-    topology::{parse_topology_config, TopologyState},
+    api,
+    config::{self, CONFIG},
+    topology::{parse_topology_config, TopologyState, RECONFIGURE_DRAIN_TIMEOUT},
+    worker::QOS_CHECK_INTERVAL,
 };
 
+/// Time to wait for further changes after the configuration file changed,
+/// as editors often write a file in several steps.
+const CONFIG_RELOAD_DELAY: Duration = Duration::from_millis(200);
+
 /// Encapsulates the ROS2 node
 ///
 /// Handles querying the ROS2 graph for auto-discovery of topics
@@ -43,7 +51,9 @@ impl NodeGraph {
         Ok(graph)
     }
 
-    pub async fn run(mut self) {
+    /// Run the topology until `stop` is triggered, e.g. on Ctrl+C, then
+    /// shut it down, letting the sinks log the data in flight.
+    pub async fn run(mut self, mut stop: Tripwire) {
         let topology_config = match parse_topology_config(&CONFIG.read()) {
             Ok(config) => config,
             Err(err) => {
@@ -58,16 +68,63 @@ impl NodeGraph {
         let topology_handle = tokio::spawn(async move {
             let mut topo = cloned_topology.lock().await;
             if let Err(err) = topo.apply_config(node, &topology_config, &registry).await {
-                error!("Failed to apply topology config: {err}");
+                for failure in &err.0 {
+                    error!("Failed to apply topology config: {failure}");
+                }
             }
+            topo.log_active_topics(&topology_config);
         });
+        let api = CONFIG.read().api.clone();
+        if api.enabled {
+            tokio::spawn(api::serve(api.address, topology.clone(), stop.clone()));
+        }
+        let (changes_tx, mut config_changes) = tokio::sync::mpsc::unbounded_channel();
+        let watcher = config::watch(&CONFIG.read().config_paths, changes_tx)
+            .inspect_err(|err| error!("Configuration changes are not applied: {err}"))
+            .ok();
+        // Recording of the database sink is toggled with `kill -USR1 <pid>`
+        let mut toggle_recording = signal(SignalKind::user_defined1())
+            .inspect_err(|err| error!("Recording cannot be toggled with SIGUSR1: {err}"))
+            .ok();
         let main_loop_handle = tokio::spawn(async move {
+            let _watcher = watcher;
+            let mut qos_check = tokio::time::interval(QOS_CHECK_INTERVAL);
             loop {
                 tokio::select! {
+                     _ = &mut stop => {
+                        info!("Shutting down the bridge");
+                        topology.lock().await.shutdown(RECONFIGURE_DRAIN_TIMEOUT).await;
+                        break;
+                     }
+                     Some(()) = config_changes.recv() => {
+                        tokio::time::sleep(CONFIG_RELOAD_DELAY).await;
+                        while config_changes.try_recv().is_ok() {}
+                        let mut topo = topology.lock().await;
+                        self.reload_config(&mut topo).await;
+                     }
+                     Some(()) = next_signal(toggle_recording.as_mut()) => {
+                        toggle_recording_file(&*topology.lock().await).await;
+                     }
                      _ = &mut self.change_notifier => {
                         if let Err(err) = self.refresh_graph() {
                             error!("Failed to refresh graph: {err}");
                         }
+                        let mut topo = topology.lock().await;
+                        let resolve = |topic: &str| self.get_topic_type(topic);
+                        let failures = topo.subscribe_deferred(&self.node, &self.registry, resolve);
+                        for failure in failures {
+                            error!("Failed to subscribe to deferred topic: {failure}");
+                        }
+                     }
+                     _ = qos_check.tick() => {
+                        let mut topo = topology.lock().await;
+                        topo.check_qos(|topic| self.publisher_qos(topic));
+                        for (id, topic, mismatch) in topo.qos_mismatches() {
+                            warn_once!(
+                                "{id} receives nothing from a publisher of '{topic}' with \
+                                 incompatible QoS: {mismatch}"
+                            );
+                        }
                      }
                 }
             }
@@ -77,6 +134,47 @@ impl NodeGraph {
         }
     }
 
+    /// Read the changed configuration file and reconcile the running
+    /// topology with it. An invalid configuration is logged and ignored,
+    /// keeping the running topology.
+    async fn reload_config(&self, topology: &mut TopologyState) {
+        let config = match config::reload() {
+            Ok(config) => config,
+            Err(err) => {
+                error!("Failed to reload config, keeping the running one: {err:#}");
+                return;
+            }
+        };
+        let topology_config = match parse_topology_config(&config) {
+            Ok(topology_config) => topology_config,
+            Err(err) => {
+                error!("Failed to parse reloaded topology config, keeping the running one: {err}");
+                return;
+            }
+        };
+        info!("Configuration changed, reconfiguring the bridge");
+        if config.converter_threads != CONFIG.read().converter_threads {
+            warn!("Changes to `converter_threads` apply when the bridge restarts");
+        }
+        *CONFIG.write() = config;
+        // Packages may have been installed or rebuilt since the types were resolved
+        self.registry.clear_type_cache();
+        let result = topology
+            .reconcile(self.node.clone(), &topology_config, &self.registry)
+            .await;
+        if let Err(err) = result {
+            for failure in &err.0 {
+                error!("Failed to apply topology config: {failure}");
+            }
+        }
+        // Topics already in the graph do not wait for the next graph change
+        let resolve = |topic: &str| self.get_topic_type(topic);
+        for failure in topology.subscribe_deferred(&self.node, &self.registry, resolve) {
+            error!("Failed to subscribe to deferred topic: {failure}");
+        }
+        topology.log_active_topics(&topology_config);
+    }
+
     pub fn get_topic_type(&self, topic: &str) -> Option<ROSTypeName> {
         let msg_topics = self.msg_topics.lock();
         match msg_topics.get(topic) {
@@ -85,6 +183,24 @@ impl NodeGraph {
         }
     }
 
+    /// The publishers of `topic` by fully qualified node name, with their QoS.
+    fn publisher_qos(&self, topic: &str) -> Vec<(String, rclrs::QoSProfile)> {
+        let publishers = match self.node.get_publishers_info_by_topic(topic) {
+            Ok(publishers) => publishers,
+            Err(err) => {
+                warn_once!("Failed to get the publishers of '{topic}': {err}");
+                return Vec::new();
+            }
+        };
+        publishers
+            .into_iter()
+            .map(|info| {
+                let namespace = info.node_namespace.trim_end_matches('/');
+                (format!("{namespace}/{}", info.node_name), info.qos_profile)
+            })
+            .collect()
+    }
+
     fn refresh_graph(&self) -> Result<()> {
         let topics_and_types = self.node.get_topic_names_and_types()?;
         let topics_and_types: Vec<_> = topics_and_types.into_iter().collect();
@@ -100,3 +216,23 @@ impl NodeGraph {
         Ok(())
     }
 }
+
+/// Wait for the next delivery of `signal`, forever if it is not installed.
+async fn next_signal(signal: Option<&mut Signal>) -> Option<()> {
+    match signal {
+        Some(signal) => signal.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Stop the recording of the database sink, or start a fresh one if stopped.
+async fn toggle_recording_file(topology: &TopologyState) {
+    if let Some(file) = topology.stop_recording().await {
+        info!("Stopped recording to {}", file.display());
+        return;
+    }
+    match topology.start_recording() {
+        Ok(file) => info!("Recording to {}", file.display()),
+        Err(err) => error!("Failed to start recording: {err}"),
+    }
+}
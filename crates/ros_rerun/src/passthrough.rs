@@ -0,0 +1,213 @@
+//! Recording of unconverted ROS messages to MCAP.
+//!
+//! Messages are serialized back into CDR from the dynamic message, since
+//! subscriptions only hand out the deserialized message. The resulting file
+//! uses the `ros2msg` schema and `cdr` message encodings of ROS 2 bags, with
+//! the `.msg` definitions of each type embedded so that readers can decode it.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Seek, Write},
+    sync::Arc,
+};
+
+use ahash::{HashMap, HashMapExt as _};
+use log::{debug, error};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use crate::{
+    config::McapPassthroughConfig, ros_introspection::MsgSpec, topology::ComponentID,
+    worker::WorkerError,
+};
+
+/// A serialized ROS message, ready to be written to MCAP.
+pub struct RawMessage {
+    pub topic: Arc<String>,
+    /// Full ROS type name, e.g. `std_msgs/msg/String`.
+    pub schema_name: Arc<String>,
+    /// Receive time in nanoseconds since the Unix epoch.
+    pub log_time: u64,
+    /// CDR encoded message.
+    pub data: Vec<u8>,
+}
+
+/// Writes raw messages to MCAP, adding a channel for each new topic.
+pub struct McapRecorder<W: Write + Seek> {
+    writer: mcap::Writer<W>,
+    /// Schema of each ROS type, by its full type name.
+    schemas: HashMap<Arc<String>, u16>,
+    channels: HashMap<Arc<String>, u16>,
+    sequence: u32,
+}
+
+impl<W: Write + Seek> McapRecorder<W> {
+    /// # Errors
+    /// Returns a `McapError` if the MCAP header cannot be written.
+    pub fn new(writer: W) -> Result<Self, mcap::McapError> {
+        Ok(Self {
+            writer: mcap::Writer::new(writer)?,
+            schemas: HashMap::new(),
+            channels: HashMap::new(),
+            sequence: 0,
+        })
+    }
+
+    /// # Errors
+    /// Returns a `McapError` if the message cannot be written.
+    pub fn write(&mut self, msg: &RawMessage) -> Result<(), mcap::McapError> {
+        let channel_id = match self.channels.get(&msg.topic) {
+            Some(channel_id) => *channel_id,
+            None => {
+                let schema_id = self.schema(&msg.schema_name)?;
+                let channel_id =
+                    self.writer
+                        .add_channel(schema_id, &msg.topic, "cdr", &BTreeMap::new())?;
+                self.channels.insert(msg.topic.clone(), channel_id);
+                channel_id
+            }
+        };
+        self.sequence = self.sequence.wrapping_add(1);
+        self.writer.write_to_known_channel(
+            &mcap::records::MessageHeader {
+                channel_id,
+                sequence: self.sequence,
+                log_time: msg.log_time,
+                publish_time: msg.log_time,
+            },
+            &msg.data,
+        )
+    }
+
+    /// Schema of the ROS type `schema_name`, added on first use.
+    ///
+    /// A type whose definition cannot be read gets a schema without data,
+    /// which names the type but is not enough to decode its messages.
+    fn schema(&mut self, schema_name: &Arc<String>) -> Result<u16, mcap::McapError> {
+        if let Some(schema_id) = self.schemas.get(schema_name) {
+            return Ok(*schema_id);
+        }
+        let definition = MsgSpec::ros2msg_definition(schema_name).unwrap_or_else(|err| {
+            error!("Failed to read the definition of '{schema_name}' for MCAP: {err}");
+            String::new()
+        });
+        let schema_id = self
+            .writer
+            .add_schema(schema_name, "ros2msg", definition.as_bytes())?;
+        self.schemas.insert(schema_name.clone(), schema_id);
+        Ok(schema_id)
+    }
+
+    /// Write the MCAP summary and footer.
+    ///
+    /// # Errors
+    /// Returns a `McapError` if the file cannot be finished.
+    pub fn finish(&mut self) -> Result<(), mcap::McapError> {
+        self.writer.finish()
+    }
+}
+
+/// Records the topics listed in `McapPassthroughConfig` to a new MCAP file.
+///
+/// The file is finished once all subscriptions sending to the worker
+/// and the worker itself are dropped.
+pub struct McapPassthroughWorker {
+    topics: Vec<String>,
+    tx: UnboundedSender<RawMessage>,
+}
+
+impl McapPassthroughWorker {
+    /// # Errors
+    /// Returns a `WorkerError` if the MCAP file cannot be created.
+    pub fn new(config: &McapPassthroughConfig) -> Result<Self, WorkerError> {
+        let store_id = rerun::StoreId::random(rerun::StoreKind::Recording, "ros_rerun");
+        let file_name = format!("ros_rerun_{}.mcap", store_id.recording_id().as_str());
+        let path = config.data_dir.join(file_name);
+        let file = File::create(&path).map_err(|err| WorkerError::CreateFile(path.clone(), err))?;
+        let mut recorder = McapRecorder::new(BufWriter::new(file))?;
+        let (tx, mut rx) = unbounded_channel::<RawMessage>();
+        tokio::task::spawn_blocking(move || {
+            while let Some(msg) = rx.blocking_recv() {
+                if let Err(err) = recorder.write(&msg) {
+                    error!("Failed to write message on '{}' to MCAP: {err}", msg.topic);
+                }
+            }
+            debug!("Finishing MCAP passthrough file {}", path.display());
+            if let Err(err) = recorder.finish() {
+                error!("Failed to finish MCAP file {}: {err}", path.display());
+            }
+        });
+
+        Ok(Self {
+            topics: config.topics.clone(),
+            tx,
+        })
+    }
+
+    /// Sender for the raw messages of `id`, if the component is recorded.
+    pub fn sender(&self, id: &ComponentID) -> Option<UnboundedSender<RawMessage>> {
+        match id {
+            ComponentID::TopicSubscriber(name) if self.topics.contains(name) => {
+                Some(self.tx.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn records_channel_per_topic() {
+        let mut recorder = McapRecorder::new(Cursor::new(Vec::new())).expect("header");
+        for topic in ["/a", "/b", "/a"] {
+            recorder
+                .write(&RawMessage {
+                    topic: Arc::new(topic.to_owned()),
+                    schema_name: Arc::new("std_msgs/msg/Empty".to_owned()),
+                    log_time: 1,
+                    data: vec![0, 1, 0, 0, 0],
+                })
+                .expect("message");
+        }
+        assert_eq!(recorder.channels.len(), 2);
+        assert_eq!(recorder.schemas.len(), 1, "schema shared by the topics");
+        recorder.finish().expect("footer");
+    }
+
+    #[tokio::test]
+    async fn passthrough_records_listed_topics() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_mcap_passthrough");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let worker = McapPassthroughWorker::new(&McapPassthroughConfig {
+            data_dir: data_dir.clone(),
+            topics: vec!["left".to_owned()],
+        })
+        .unwrap();
+        let subscriber = |name: &str| ComponentID::TopicSubscriber(name.to_owned());
+        assert!(worker.sender(&subscriber("left")).is_some());
+        assert!(worker.sender(&subscriber("right")).is_none());
+        assert!(worker.sender(&ComponentID::DBSink).is_none());
+        let files: Vec<_> = std::fs::read_dir(&data_dir).unwrap().collect();
+        assert_eq!(files.len(), 1, "a new file per passthrough");
+    }
+
+    #[test]
+    fn embeds_dependencies_in_schema() {
+        let definition =
+            MsgSpec::ros2msg_definition("std_msgs/msg/Header").expect("std_msgs is available");
+        assert!(definition.contains("string frame_id"), "{definition}");
+        assert!(
+            definition.contains(&format!(
+                "{}\nMSG: builtin_interfaces/Time\n",
+                "=".repeat(80)
+            )),
+            "{definition}"
+        );
+    }
+}
@@ -1,8 +1,14 @@
 use crate::ros_introspection::{self, BuiltinType, Message, Type};
 use anyhow::{anyhow, Error, Result};
+use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::sync::Arc;
 
+/// Line between the definitions of a message and of its dependencies
+/// in a `ros2msg` schema.
+const DEFINITION_SEPARATOR: &str =
+    "================================================================================";
+
 /// Represents a ROS message specification.
 pub struct MsgSpec {
     data: Arc<Message>,
@@ -79,6 +85,31 @@ impl MsgSpec {
         topic_type: &str,
         parent_package: &str,
     ) -> Result<Arc<Message>, Error> {
+        let (message_type, contents) = Self::read_message_file(topic_type, parent_package)?;
+
+        let msg_parsed = ros_introspection::parse_message_definitions(&contents, &message_type)?;
+
+        let msg_def = Arc::clone(&msg_parsed[0]);
+        Ok(msg_def)
+    }
+
+    /// Reads the `.msg` file of the given topic type from its package share directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_type` - A string slice that holds the type of the topic.
+    /// * `parent_package` - A string slice that holds the name of the parent package.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Type, String), Error>` - A result containing the resolved type and the file
+    ///   contents or an error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message type is invalid, the package share
+    /// directory cannot be found, or the message file cannot be read.
+    fn read_message_file(topic_type: &str, parent_package: &str) -> Result<(Type, String), Error> {
         let message_type = {
             let message_type = Type::new(topic_type)?;
             if message_type.pkg_name().is_empty() && message_type.id() == &BuiltinType::Other {
@@ -100,11 +131,59 @@ impl MsgSpec {
         msg_file_path.push(format!("{}.msg", message_type.msg_name()));
 
         let contents = fs::read_to_string(msg_file_path)?;
+        Ok((message_type, contents))
+    }
 
-        let msg_parsed = ros_introspection::parse_message_definitions(&contents, &message_type)?;
+    /// Returns the `ros2msg` schema of the given topic type, as written to MCAP files.
+    ///
+    /// The schema is the `.msg` definition of the type, followed by the definition
+    /// of each message it depends on after a separator line and a `MSG: package/Name` line.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_type` - A string slice that holds the type of the topic, e.g.
+    ///   `std_msgs/msg/Header`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Error>` - A result containing the schema or an error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the definition of the type or of one of its
+    /// dependencies cannot be read.
+    pub fn ros2msg_definition(topic_type: &str) -> Result<String, Error> {
+        let (message_type, contents) = Self::read_message_file(topic_type, "")?;
+        let mut definition = contents.clone();
+        let mut seen = HashSet::from([format!(
+            "{}/{}",
+            message_type.pkg_name(),
+            message_type.msg_name()
+        )]);
+        let mut pending = VecDeque::from([(message_type, contents)]);
 
-        let msg_def = Arc::clone(&msg_parsed[0]);
-        Ok(msg_def)
+        while let Some((message_type, contents)) = pending.pop_front() {
+            let msg_parsed =
+                ros_introspection::parse_message_definitions(&contents, &message_type)?;
+            for field in msg_parsed[0].fields() {
+                if field.type_().id() != &BuiltinType::Other {
+                    continue;
+                }
+                let (dependency, contents) =
+                    Self::read_message_file(field.type_().name(), message_type.pkg_name())?;
+                let name = format!("{}/{}", dependency.pkg_name(), dependency.msg_name());
+                if !seen.insert(name.clone()) {
+                    continue;
+                }
+                if !definition.ends_with('\n') {
+                    definition.push('\n');
+                }
+                definition.push_str(&format!("{DEFINITION_SEPARATOR}\nMSG: {name}\n{contents}"));
+                pending.push_back((dependency, contents));
+            }
+        }
+
+        Ok(definition)
     }
 
     /// Returns a reference to the message data.
@@ -1,16 +1,32 @@
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use ahash::{HashMap, HashMapExt as _, HashSet, HashSetExt as _};
-use log::{debug, error};
-use ros_rerun_types::converter::ConverterRegistry;
+use log::{debug, error, info, warn};
+use rerun::ComponentBatch as _;
+use ros_rerun_types::{converter::ConverterRegistry, ROSTypeName};
 use stream_cancel::{Trigger, Tripwire};
 use thiserror::Error;
 use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
-    channel::{ArchetypeReceiver, ArchetypeSender, LogData},
-    config::{defs::Config, DBConfig, StreamConfig, TopicSource},
-    worker::{DBSinkWorker, GRPCSinkWorker, SubscriptionWorker},
+    channel::{ArchetypeReceiver, ArchetypeSender, InFlight, LogComponents, LogData, SinkSender},
+    config::{
+        defs::{Config, DEFAULT_MAX_IN_FLIGHT},
+        DBConfig, McapPassthroughConfig, Priority, QosConfig, StreamConfig, TopicSource,
+        TransformConfig,
+    },
+    metrics::SinkStatsSnapshot,
+    passthrough::McapPassthroughWorker,
+    worker::{
+        DBSinkWorker, GRPCSinkWorker, QosMismatch, RecordingIds, SelfMetricsWorker,
+        SubscriptionWorker, TransformWorker, WorkerError,
+    },
 };
 
 #[derive(Error, Debug)]
@@ -21,24 +37,89 @@ pub enum TopologyConfigError {
     #[error("Component {0} cannot define itself as an input")]
     SelfReference(ComponentID),
 
-    #[error("Component {0} failed to initialize")]
-    InitializationError(ComponentID),
+    #[error("Component {0} is part of an input cycle")]
+    Cycle(ComponentID),
+
+    #[error("Component {0} failed to initialize: {1}")]
+    InitializationError(ComponentID, #[source] WorkerError),
+
+    #[error("Component {0} shares the subscription of {1}, which failed to initialize")]
+    SharedSubscription(ComponentID, ComponentID),
+
+    #[error("Invalid world view coordinates: {0}")]
+    WorldViewCoordinates(#[source] anyhow::Error),
+
+    #[error("Invalid self metrics: {0}")]
+    SelfMetrics(#[source] anyhow::Error),
+
+    #[error("Component {0} is not running")]
+    NotRunning(ComponentID),
 
     #[error("Component {0} failed to initialize the Rerun SDK: {1}")]
     RerunInitializationError(ComponentID, #[source] Box<rerun::RecordingStreamError>),
 }
 
+impl TopologyConfigError {
+    /// Attribute a worker error to the component that failed to initialize.
+    fn from_worker(id: ComponentID, err: WorkerError) -> Self {
+        match err {
+            WorkerError::Rerun(err) => Self::RerunInitializationError(id, Box::new(err)),
+            err => Self::InitializationError(id, err),
+        }
+    }
+
+    /// The errors of the `shared` subscribers of a subscription that failed.
+    fn shared_failures<'a>(
+        id: &'a ComponentID,
+        shared: &'a [(&ComponentID, &TopicSource)],
+    ) -> impl Iterator<Item = Self> + 'a {
+        shared
+            .iter()
+            .map(|(shared_id, _)| Self::SharedSubscription((*shared_id).clone(), id.clone()))
+    }
+
+    /// Whether a topic failed to subscribe because its ROS type could not
+    /// be resolved, e.g. as its package is not sourced yet.
+    fn is_unresolved_type(&self) -> bool {
+        matches!(self, Self::InitializationError(_, WorkerError::ROSType(_)))
+    }
+}
+
+/// Components that failed to initialize while applying a topology.
+#[derive(Error, Debug)]
+#[error("{} component(s) failed to initialize: {}", .0.len(), join_errors(.0))]
+pub struct TopologyApplyError(pub Vec<TopologyConfigError>);
+
+fn join_errors(errors: &[TopologyConfigError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Configuration describing the flow of data from ROS topics to Rerun.
 ///
 /// This is derived from a Config struct.
 /// To perform runtime modifications to the state, a new `TopologyConfig`
 /// will be constructed, compared to the current `TopologyState`, and
-/// and changes will be asynchronously applied.
-#[derive(Debug)]
+/// and changes will be asynchronously applied, see `TopologyState::reconcile`.
+#[derive(Clone, Debug)]
 pub struct TopologyConfig {
     topic_subscriptions: BTreeMap<ComponentID, TopicSource>,
-    grpc_sinks: BTreeMap<ComponentID, String>,
+    /// Topic subscribers sharing the subscription of another subscriber
+    /// with the same topic and ROS type, mapped to that subscriber.
+    shared_subscriptions: BTreeMap<ComponentID, ComponentID>,
+    grpc_sinks: BTreeMap<ComponentID, StreamConfig>,
+    transforms: BTreeMap<ComponentID, TransformConfig>,
     db_sink: DBConfig,
+    mcap_passthrough: Option<McapPassthroughConfig>,
+    world_view_coordinates: rerun::ViewCoordinates,
+    log_environment: bool,
+    log_converter_info: bool,
+    recording_ids: RecordingIds,
+    /// Interval of the self metrics, when enabled.
+    self_metrics: Option<Duration>,
     edges: BTreeMap<ComponentID, Vec<ComponentID>>,
 }
 
@@ -53,6 +134,7 @@ impl TopologyConfig {
     pub fn validate(&self) -> anyhow::Result<(), TopologyConfigError> {
         self.check_duplicate_ids()?;
         self.check_invalid_edges()?;
+        self.check_cycles()?;
         Ok(())
     }
 
@@ -62,19 +144,171 @@ impl TopologyConfig {
         self.topic_subscriptions
             .keys()
             .chain(self.grpc_sinks.keys())
+            .chain(self.transforms.keys())
             .try_for_each(|k| match k {
-                ComponentID::GRPCSink(name) | ComponentID::TopicSubscriber(name) => {
+                ComponentID::GRPCSink(name)
+                | ComponentID::TopicSubscriber(name)
+                | ComponentID::Transform(name) => {
                     if !seen.insert(name) {
                         Err(TopologyConfigError::DuplicateID(name.to_owned()))
                     } else {
                         Ok(())
                     }
                 }
-                ComponentID::DBSink => Ok(()),
+                ComponentID::DBSink | ComponentID::McapPassthrough => Ok(()),
             })?;
         Ok(())
     }
 
+    /// Components connected as inputs to the given component.
+    pub fn inputs(&self, id: &ComponentID) -> Vec<ComponentID> {
+        self.edges.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Whether the options of the whole topology, which only apply when
+    /// it starts, are configured the same as in `other`.
+    fn same_options(&self, other: &Self) -> bool {
+        self.mcap_passthrough == other.mcap_passthrough
+            && self.world_view_coordinates == other.world_view_coordinates
+            && self.log_environment == other.log_environment
+            && self.log_converter_info == other.log_converter_info
+            && self.recording_ids == other.recording_ids
+    }
+
+    /// Components that `id` is an input of.
+    fn outputs_of(&self, id: &ComponentID) -> Vec<&ComponentID> {
+        self.edges
+            .iter()
+            .filter(|(_, inputs)| inputs.contains(id))
+            .map(|(output, _)| output)
+            .collect()
+    }
+
+    /// Whether the sink or transform `id` is configured differently in
+    /// `next`. Its inputs are not compared, as they change in place.
+    fn output_changed(&self, next: &Self, id: &ComponentID) -> bool {
+        match id {
+            ComponentID::GRPCSink(_) => {
+                let stream = |config: &Self| {
+                    config.grpc_sinks.get(id).map(|stream| StreamConfig {
+                        inputs: Vec::new(),
+                        ..stream.clone()
+                    })
+                };
+                stream(self) != stream(next)
+            }
+            ComponentID::Transform(_) => {
+                let transform = |config: &Self| {
+                    config.transforms.get(id).map(|transform| TransformConfig {
+                        inputs: Vec::new(),
+                        ..transform.clone()
+                    })
+                };
+                transform(self) != transform(next)
+            }
+            ComponentID::DBSink => {
+                let db = |config: &Self| DBConfig {
+                    inputs: Vec::new(),
+                    ..config.db_sink.clone()
+                };
+                db(self) != db(next)
+            }
+            ComponentID::TopicSubscriber(_) | ComponentID::McapPassthrough => false,
+        }
+    }
+
+    /// Components to replace to get from this configuration to `next`.
+    fn reconcile_plan(&self, next: &Self) -> ReconcilePlan {
+        let mut stopped: BTreeSet<_> = self
+            .edges
+            .keys()
+            .filter(|id| !next.edges.contains_key(*id) || self.output_changed(next, id))
+            .cloned()
+            .collect();
+        let mut started: BTreeSet<_> = next
+            .edges
+            .keys()
+            .filter(|id| !self.edges.contains_key(*id) || self.output_changed(next, id))
+            .cloned()
+            .collect();
+        // A transform sends to the channels it started with, so it restarts
+        // along with them, which may in turn restart the transforms feeding it.
+        loop {
+            let restarted: Vec<_> = next
+                .transforms
+                .keys()
+                .filter(|id| !started.contains(*id))
+                .filter(|id| {
+                    let outputs = next.outputs_of(id);
+                    self.outputs_of(id) != outputs
+                        || outputs.iter().any(|output| started.contains(*output))
+                })
+                .cloned()
+                .collect();
+            if restarted.is_empty() {
+                break;
+            }
+            stopped.extend(restarted.iter().cloned());
+            started.extend(restarted);
+        }
+
+        let subscriber = |config: &Self, id: &ComponentID| {
+            config.topic_subscriptions.get(id).map(|source| {
+                (
+                    source.clone(),
+                    config.shared_subscriptions.get(id).cloned(),
+                    config.outputs_of(id),
+                )
+            })
+        };
+        let topics = self
+            .topic_subscriptions
+            .keys()
+            .chain(next.topic_subscriptions.keys())
+            .filter(|id| {
+                subscriber(self, id) != subscriber(next, id)
+                    || next
+                        .outputs_of(id)
+                        .iter()
+                        .any(|output| started.contains(*output))
+            })
+            .cloned()
+            .collect();
+        ReconcilePlan {
+            stopped,
+            started,
+            topics,
+        }
+    }
+
+    /// Topic subscribers that share the subscription of `id`.
+    fn shared_with(&self, id: &ComponentID) -> Vec<(&ComponentID, &TopicSource)> {
+        self.shared_subscriptions
+            .iter()
+            .filter(|(_, primary)| *primary == id)
+            .filter_map(|(shared, _)| {
+                self.topic_subscriptions
+                    .get(shared)
+                    .map(|source| (shared, source))
+            })
+            .collect()
+    }
+
+    /// Queued message count at which a sink starts dropping messages.
+    fn max_in_flight(&self, id: &ComponentID) -> usize {
+        let limit = match id {
+            ComponentID::GRPCSink(_) => self
+                .grpc_sinks
+                .get(id)
+                .and_then(|stream| stream.max_in_flight),
+            ComponentID::DBSink => self.db_sink.max_in_flight,
+            ComponentID::TopicSubscriber(_)
+            | ComponentID::Transform(_)
+            | ComponentID::McapPassthrough => None,
+        };
+        limit.unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+    }
+
     fn check_invalid_edges(&self) -> anyhow::Result<(), TopologyConfigError> {
         for (sink, sources) in &self.edges {
             if let Some(source) = sources.iter().find(|source| *source == sink) {
@@ -83,6 +317,36 @@ impl TopologyConfig {
         }
         Ok(())
     }
+
+    /// Transforms can feed each other, so make sure they do not form a loop.
+    fn check_cycles(&self) -> anyhow::Result<(), TopologyConfigError> {
+        fn visit<'a>(
+            id: &'a ComponentID,
+            edges: &'a BTreeMap<ComponentID, Vec<ComponentID>>,
+            path: &mut Vec<&'a ComponentID>,
+            done: &mut HashSet<&'a ComponentID>,
+        ) -> Result<(), TopologyConfigError> {
+            if done.contains(id) {
+                return Ok(());
+            }
+            if path.contains(&id) {
+                return Err(TopologyConfigError::Cycle(id.clone()));
+            }
+            path.push(id);
+            for input in edges.get(id).into_iter().flatten() {
+                visit(input, edges, path, done)?;
+            }
+            path.pop();
+            done.insert(id);
+            Ok(())
+        }
+
+        let mut done = HashSet::new();
+        for id in self.edges.keys() {
+            visit(id, &self.edges, &mut Vec::new(), &mut done)?;
+        }
+        Ok(())
+    }
 }
 
 /// Parse the topology configuration from the given config.
@@ -94,35 +358,55 @@ pub fn parse_topology_config(
 ) -> anyhow::Result<TopologyConfig, TopologyConfigError> {
     let mut topic_subscriptions = BTreeMap::new();
     let mut grpc_sinks = BTreeMap::new();
+    let mut transforms = BTreeMap::new();
     let mut edges: BTreeMap<ComponentID, Vec<ComponentID>> = BTreeMap::new();
 
-    for (name, source) in config.topics() {
+    // Disabled topics are left out, so sinks and transforms skip them as inputs
+    for (name, source) in config
+        .topics()
+        .into_iter()
+        .filter(|(_, source)| source.is_enabled())
+    {
         let source_id = ComponentID::TopicSubscriber(name.clone());
         topic_subscriptions.insert(source_id.clone(), source.clone());
     }
+    for (name, transform) in config.transforms() {
+        transforms.insert(ComponentID::Transform(name.clone()), transform.clone());
+    }
 
-    // Set up a single default database sink
-    let mut db_inputs = Vec::new();
-    config.db.inputs.iter().for_each(|input| {
-        if topic_subscriptions.contains_key(&ComponentID::TopicSubscriber(input.clone())) {
-            db_inputs.push(ComponentID::TopicSubscriber(input.clone()));
-        }
-    });
-    edges.insert(ComponentID::DBSink, db_inputs);
+    // Inputs can be topics or transforms
+    let resolve_input = |input: &String| {
+        [
+            ComponentID::TopicSubscriber(input.clone()),
+            ComponentID::Transform(input.clone()),
+        ]
+        .into_iter()
+        .find(|id| topic_subscriptions.contains_key(id) || transforms.contains_key(id))
+    };
+
+    // Connect transforms to their inputs
+    for (id, transform) in &transforms {
+        let inputs = transform.inputs.iter().filter_map(resolve_input).collect();
+        edges.insert(id.clone(), inputs);
+    }
+
+    // Set up a single default database sink, unless disabled
+    if config.db.is_enabled() {
+        let db_inputs = config.db.inputs.iter().filter_map(resolve_input).collect();
+        edges.insert(ComponentID::DBSink, db_inputs);
+    }
 
     // Setup gRPC sinks
     for (name, stream) in config.streams() {
         let sink_id = ComponentID::GRPCSink(name.clone());
-        grpc_sinks.insert(sink_id.clone(), stream.url.clone());
+        grpc_sinks.insert(sink_id.clone(), stream.clone());
+        let sink_inputs = edges.entry(sink_id).or_default();
 
         // Connect appropriate sources to this sink
         for input in &stream.inputs {
-            if topic_subscriptions.contains_key(&ComponentID::TopicSubscriber(input.clone())) {
-                edges
-                    .entry(sink_id.clone())
-                    .or_default()
-                    .push(ComponentID::TopicSubscriber(input.clone()));
-            } else if grpc_sinks.contains_key(&ComponentID::GRPCSink(input.clone())) {
+            if let Some(input_id) = resolve_input(input) {
+                sink_inputs.push(input_id);
+            } else if config.streams.contains_key(input) {
                 return Err(TopologyConfigError::SelfReference(ComponentID::GRPCSink(
                     input.clone(),
                 )));
@@ -131,9 +415,21 @@ pub fn parse_topology_config(
     }
 
     let topo_cfg = TopologyConfig {
+        shared_subscriptions: shared_subscriptions(&topic_subscriptions),
         topic_subscriptions,
         grpc_sinks,
+        transforms,
         db_sink: config.db.clone(),
+        mcap_passthrough: config.mcap_passthrough.clone(),
+        world_view_coordinates: config
+            .world_view_coordinates()
+            .map_err(TopologyConfigError::WorldViewCoordinates)?,
+        log_environment: config.log_environment,
+        log_converter_info: config.log_converter_info,
+        recording_ids: RecordingIds::new(config),
+        self_metrics: config
+            .self_metrics_interval()
+            .map_err(TopologyConfigError::SelfMetrics)?,
         edges,
     };
     topo_cfg.validate()?;
@@ -141,109 +437,840 @@ pub fn parse_topology_config(
     Ok(topo_cfg)
 }
 
+/// Find the topic subscribers that can share a single ROS subscription.
+///
+/// Entries with the same topic, ROS type and QoS are coalesced into the one with
+/// the lowest ID, which then runs the converters of all of them, so the
+/// topic's messages are only received once. Entries waiting for their ROS
+/// type to be discovered are never coalesced.
+fn shared_subscriptions(
+    topic_subscriptions: &BTreeMap<ComponentID, TopicSource>,
+) -> BTreeMap<ComponentID, ComponentID> {
+    let mut primaries: HashMap<(&str, &str, Option<QosConfig>), &ComponentID> = HashMap::new();
+    let mut shared = BTreeMap::new();
+    for (id, source) in topic_subscriptions {
+        let Some(ros_type) = source.known_ros_type() else {
+            continue;
+        };
+        let primary = *primaries
+            .entry((source.topic.as_str(), ros_type, source.qos))
+            .or_insert(id);
+        if primary != id {
+            debug!(
+                "{id} shares the subscription to '{}' of {primary}",
+                source.topic
+            );
+            shared.insert(id.clone(), primary.clone());
+        }
+    }
+    shared
+}
+
+/// Components replaced when reconfiguring a running topology,
+/// see `TopologyState::reconcile`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ReconcilePlan {
+    /// Sinks and transforms that stop, as they were removed or changed.
+    stopped: BTreeSet<ComponentID>,
+    /// Sinks and transforms that start, as they were added or changed.
+    started: BTreeSet<ComponentID>,
+    /// Topic subscribers whose subscription is added, removed or replaced,
+    /// e.g. as they changed or feed a component that starts.
+    topics: BTreeSet<ComponentID>,
+}
+
+impl ReconcilePlan {
+    /// Whether a sink starts or stops, which changes the self metrics.
+    fn changes_sinks(&self) -> bool {
+        self.stopped
+            .iter()
+            .chain(&self.started)
+            .any(|id| matches!(id, ComponentID::GRPCSink(_) | ComponentID::DBSink))
+    }
+}
+
 /// The state of a running topology.
 #[derive(Default)]
 pub struct TopologyState {
     topic_subscriptions: HashMap<ComponentID, SubscriptionWorker>,
+    /// Topic subscribers fed by the subscription of another subscriber.
+    shared_subscriptions: HashMap<ComponentID, ComponentID>,
     grpc_sinks: HashMap<ComponentID, GRPCSinkWorker>,
+    transforms: HashMap<ComponentID, TransformWorker>,
     db_sink: Option<DBSinkWorker>,
+    mcap_passthrough: Option<McapPassthroughWorker>,
+    /// The self metrics worker and the trigger stopping it, which restarts
+    /// whenever the sinks change.
+    self_metrics: Option<(SelfMetricsWorker, Trigger)>,
+    /// Log the converter of each topic, see `Config::log_converter_info`.
+    log_converter_info: bool,
+    /// Topics waiting for their ROS type to appear in the graph, or to be
+    /// published when their configured type could not be resolved.
+    deferred_subscriptions: HashMap<ComponentID, TopicSource>,
     edges: HashMap<ComponentID, InputChannel>,
     shutdown_trigger: Option<Trigger>,
+    /// Stops the sinks and transforms along with `shutdown_trigger`.
+    shutdown_tripwire: Option<Tripwire>,
+    /// The configuration the state was built from, see `TopologyState::reconcile`.
+    applied: Option<TopologyConfig>,
+    /// See `TopologyState::check_qos`.
+    qos_mismatches: Vec<(ComponentID, String, QosMismatch)>,
 }
 
+/// Group topics by the subscription they share, see
+/// `TopologyConfig::shared_subscriptions`, keeping the order of the primaries.
+///
+/// Topics whose primary is not among `topics` get a subscription of their own.
+fn group_shared<T>(
+    topics: impl IntoIterator<Item = (ComponentID, T)>,
+    primaries: &BTreeMap<ComponentID, ComponentID>,
+) -> Vec<((ComponentID, T), Vec<(ComponentID, T)>)> {
+    let topics: Vec<_> = topics.into_iter().collect();
+    let ids: HashSet<_> = topics.iter().map(|(id, _)| id.clone()).collect();
+    let mut groups = Vec::new();
+    let mut shared: HashMap<ComponentID, Vec<(ComponentID, T)>> = HashMap::new();
+    for (id, topic) in topics {
+        match primaries.get(&id) {
+            Some(primary) if ids.contains(primary) => {
+                shared.entry(primary.clone()).or_default().push((id, topic));
+            }
+            _ => groups.push(((id, topic), Vec::new())),
+        }
+    }
+    for ((id, _), sharers) in &mut groups {
+        *sharers = shared.remove(id).unwrap_or_default();
+    }
+    groups
+}
+
+/// Longest time a reconfiguration waits for the sinks and transforms it
+/// stops to log the messages in flight.
+pub const RECONFIGURE_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl TopologyState {
     /// Apply a new topology configuration to the current state.
     ///
+    /// Components that fail to initialize are skipped, so that the rest of
+    /// the topology still runs.
+    ///
     /// # Errors
     ///
-    /// Returns a `TopologyConfigError` if all or part of the configuration
-    /// fails to initialize and start running.
+    /// Returns a `TopologyApplyError` with every component that
+    /// failed to initialize and start running.
     pub async fn apply_config(
         &mut self,
         node: rclrs::Node,
         config: &TopologyConfig,
         registry: &ConverterRegistry,
-    ) -> anyhow::Result<(), TopologyConfigError> {
+    ) -> anyhow::Result<(), TopologyApplyError> {
         let (shutdown_trigger, shutdown) = Tripwire::new();
         self.shutdown_trigger = Some(shutdown_trigger);
+        self.shutdown_tripwire = Some(shutdown.clone());
+        let mut failures = Vec::new();
+        let mut rx_map = self.apply_edges(config);
+
+        self.log_converter_info = config.log_converter_info;
+        self.log_world_view_coordinates(&config.world_view_coordinates);
+        if config.log_environment {
+            self.log_environment();
+        }
+
+        // Apply MCAP passthrough
+        self.mcap_passthrough = config
+            .mcap_passthrough
+            .as_ref()
+            .map(McapPassthroughWorker::new)
+            .transpose()
+            .unwrap_or_else(|err| {
+                failures.push(TopologyConfigError::from_worker(
+                    ComponentID::McapPassthrough,
+                    err,
+                ));
+                None
+            });
+
+        // Apply topic subscriptions
+        failures.extend(self.apply_subscriptions(&node, config, registry));
+        failures.extend(self.apply_outputs(config, &mut rx_map, &shutdown));
+
+        debug!("Applied topology config {config:?}");
+        self.applied = Some(config.clone());
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(TopologyApplyError(failures))
+        }
+    }
+
+    /// Change the running topology to a new configuration, e.g. after the
+    /// configuration file changed.
+    ///
+    /// Only the components that changed are replaced, see `ReconcilePlan`.
+    /// The others keep running, so the sinks keep their queued messages and
+    /// connections. The sinks and transforms that stop first log the
+    /// messages in flight, see `RECONFIGURE_DRAIN_TIMEOUT`, and the sinks
+    /// flush when dropped. When options of the whole topology change, it is
+    /// restarted instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TopologyApplyError` with every component that
+    /// failed to initialize and start running.
+    pub async fn reconcile(
+        &mut self,
+        node: rclrs::Node,
+        config: &TopologyConfig,
+        registry: &ConverterRegistry,
+    ) -> anyhow::Result<(), TopologyApplyError> {
+        let (Some(current), Some(shutdown)) = (self.applied.take(), self.shutdown_tripwire.clone())
+        else {
+            return self.apply_config(node, config, registry).await;
+        };
+        if !current.same_options(config) {
+            info!("Options of the topology changed, restarting it");
+            self.shutdown(RECONFIGURE_DRAIN_TIMEOUT).await;
+            return self.apply_config(node, config, registry).await;
+        }
+        let plan = current.reconcile_plan(config);
+        debug!("Reconciling the topology: {plan:?}");
+
+        // Subscriptions go first, so no new messages reach stopping components
+        for id in &plan.topics {
+            // The subscription of a changed topic is replaced along with
+            // the topics sharing it, both before and after the change.
+            let running = self.shared_subscriptions.get(id).unwrap_or(id).clone();
+            self.unsubscribe(&running);
+            if let Some(primary) = config.shared_subscriptions.get(id) {
+                self.unsubscribe(primary);
+            }
+            self.deferred_subscriptions.remove(id);
+        }
+        let mut failures = self
+            .reconcile_outputs(&current, config, &plan, &shutdown)
+            .await;
+        failures.extend(self.apply_subscriptions(&node, config, registry));
+        self.applied = Some(config.clone());
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(TopologyApplyError(failures))
+        }
+    }
+
+    /// Stop and start the sinks and transforms of `plan`, going from the
+    /// `current` configuration to `config`, and returning the ones that
+    /// failed to start.
+    async fn reconcile_outputs(
+        &mut self,
+        current: &TopologyConfig,
+        config: &TopologyConfig,
+        plan: &ReconcilePlan,
+        shutdown: &Tripwire,
+    ) -> Vec<TopologyConfigError> {
+        if plan.changes_sinks() || current.self_metrics != config.self_metrics {
+            self.self_metrics = None;
+        }
+
+        // Stopping components exit once their inputs are closed and drained
+        let mut stopping = Vec::new();
+        for id in &plan.stopped {
+            if let Some(input) = self.edges.remove(id) {
+                stopping.extend(input.channel.tx.into_iter().map(|tx| tx.in_flight));
+            }
+            self.transforms.remove(id);
+        }
+        let queued = drain(&stopping, RECONFIGURE_DRAIN_TIMEOUT).await;
+        if queued > 0 {
+            warn!("Dropping {queued} queued message(s) of stopped components");
+        }
+        for id in &plan.stopped {
+            match id {
+                ComponentID::GRPCSink(_) => drop(self.grpc_sinks.remove(id)),
+                ComponentID::DBSink => self.db_sink = None,
+                _ => {}
+            }
+        }
+
+        let mut rx_map = self.apply_edges(config);
+        let failures = self.apply_outputs(config, &mut rx_map, shutdown);
+        if plan
+            .started
+            .iter()
+            .any(|id| !matches!(id, ComponentID::Transform(_)))
+        {
+            // Started sinks have not seen the static data of the recording yet
+            self.log_world_view_coordinates(&config.world_view_coordinates);
+            if config.log_environment {
+                self.log_environment();
+            }
+        }
+        failures
+    }
+
+    /// Subscribe to the topics of `config` that are neither running nor
+    /// waiting for their ROS type, returning the ones that failed.
+    fn apply_subscriptions(
+        &mut self,
+        node: &rclrs::Node,
+        config: &TopologyConfig,
+        registry: &ConverterRegistry,
+    ) -> Vec<TopologyConfigError> {
+        let mut failures = Vec::new();
+        let subscribe_start = Instant::now();
+        for (id, worker) in &config.topic_subscriptions {
+            if config.shared_subscriptions.contains_key(id)
+                || self.topic_subscriptions.contains_key(id)
+                || self.deferred_subscriptions.contains_key(id)
+            {
+                continue;
+            }
+            let Some(ros_type) = worker.known_ros_type() else {
+                info!(
+                    "Topic '{}' has no ROS type yet, subscribing once it appears in the graph",
+                    worker.topic
+                );
+                self.deferred_subscriptions
+                    .insert(id.clone(), worker.clone());
+                continue;
+            };
+            let shared = config.shared_with(id);
+            match self.subscribe(node, id, ros_type, worker, &shared, registry) {
+                Err(err) if err.is_unresolved_type() => {
+                    warn!(
+                        "ROS type '{ros_type}' of topic '{}' is not available, \
+                         retrying once the topic appears in the graph: {err}",
+                        worker.topic
+                    );
+                    self.deferred_subscriptions.extend(
+                        std::iter::once((id, worker))
+                            .chain(shared.iter().copied())
+                            .map(|(id, source)| (id.clone(), source.clone())),
+                    );
+                }
+                Err(err) => {
+                    failures.push(err);
+                    failures.extend(TopologyConfigError::shared_failures(id, &shared));
+                }
+                Ok(()) => {}
+            }
+        }
+
+        debug!(
+            "Created {} topic subscriptions in {:?}",
+            self.topic_subscriptions.len(),
+            subscribe_start.elapsed()
+        );
+        failures
+    }
+
+    /// Drop the subscription worker of `id` and the subscribers sharing it.
+    ///
+    /// Messages that are being converted are still sent to the outputs.
+    fn unsubscribe(&mut self, id: &ComponentID) {
+        if self.topic_subscriptions.remove(id).is_some() {
+            debug!("Unsubscribed {id}");
+        }
+        self.shared_subscriptions
+            .retain(|shared, primary| primary != id && shared != id);
+        self.qos_mismatches
+            .retain(|(mismatched, ..)| mismatched != id);
+    }
+
+    /// Stop every component, after the sinks logged the messages in flight
+    /// or `timeout` passed.
+    ///
+    /// The subscriptions stop first, so no new messages are queued, and
+    /// the sinks shut down via the shutdown trigger before being dropped.
+    pub async fn shutdown(&mut self, timeout: Duration) {
+        self.topic_subscriptions.clear();
+        self.shared_subscriptions.clear();
+        self.deferred_subscriptions.clear();
+        let in_flight: Vec<_> = self
+            .edges
+            .values()
+            .flat_map(|input| &input.channel.tx)
+            .map(|tx| tx.in_flight.clone())
+            .collect();
+        let queued = drain(&in_flight, timeout).await;
+        if queued > 0 {
+            warn!("Dropping {queued} queued message(s) while shutting down the topology");
+        }
+        if let Some(trigger) = self.shutdown_trigger.take() {
+            trigger.cancel();
+        }
+        *self = Self::default();
+    }
+
+    /// Subscribe to deferred topics whose ROS type has become known.
+    ///
+    /// Call this whenever the ROS graph changes, `resolve` looks up the
+    /// type of a topic in the graph. Topics with a configured type are
+    /// subscribed with it once they appear. Topics are subscribed at most once.
+    /// A deferred topic that fails to subscribe is dropped and returned as
+    /// an error, so the failure is not repeated on every graph change.
+    /// Topics that share a subscription are subscribed together again.
+    pub fn subscribe_deferred(
+        &mut self,
+        node: &rclrs::Node,
+        registry: &ConverterRegistry,
+        resolve: impl Fn(&str) -> Option<ROSTypeName>,
+    ) -> Vec<TopologyConfigError> {
+        let primaries = self
+            .applied
+            .as_ref()
+            .map(|config| config.shared_subscriptions.clone())
+            .unwrap_or_default();
+        let resolved = self
+            .resolve_deferred(resolve)
+            .into_iter()
+            .map(|(id, worker, ros_type)| (id, (worker, ros_type)));
+        let mut failures = Vec::new();
+        for ((id, (worker, ros_type)), shared) in group_shared(resolved, &primaries) {
+            info!(
+                "Deferred topic '{}' came online with ROS type '{ros_type}'",
+                worker.topic
+            );
+            let ros_type = worker
+                .known_ros_type()
+                .map_or_else(|| ros_type.to_string(), str::to_owned);
+            let shared: Vec<_> = shared
+                .iter()
+                .map(|(shared_id, (source, _))| (shared_id, source))
+                .collect();
+            if let Err(err) = self.subscribe(node, &id, &ros_type, &worker, &shared, registry) {
+                failures.push(err);
+                failures.extend(TopologyConfigError::shared_failures(&id, &shared));
+            }
+        }
+        failures
+    }
+
+    /// Remove the deferred topics with a known type, returning them with
+    /// their type. The type is also filled in unless the topic accepts any type.
+    fn resolve_deferred(
+        &mut self,
+        resolve: impl Fn(&str) -> Option<ROSTypeName>,
+    ) -> Vec<(ComponentID, TopicSource, ROSTypeName)> {
+        let mut resolved = Vec::new();
+        self.deferred_subscriptions.retain(|id, worker| {
+            if self.topic_subscriptions.contains_key(id) {
+                return false;
+            }
+            let Some(ros_type) = resolve(&worker.topic) else {
+                return true;
+            };
+            let mut source = worker.clone();
+            if source.ros_type.is_none() {
+                source.ros_type = Some(ros_type.to_string());
+            }
+            resolved.push((id.clone(), source, ros_type));
+            false
+        });
+        resolved
+    }
+
+    /// Create the subscription worker of a topic with a known ROS type.
+    ///
+    /// The subscription also feeds the converters of the `shared` subscribers.
+    fn subscribe(
+        &mut self,
+        node: &rclrs::Node,
+        id: &ComponentID,
+        ros_type: &str,
+        worker: &TopicSource,
+        shared: &[(&ComponentID, &TopicSource)],
+        registry: &ConverterRegistry,
+    ) -> Result<(), TopologyConfigError> {
+        let ids: Vec<_> = std::iter::once(id)
+            .chain(shared.iter().map(|(shared_id, _)| *shared_id))
+            .collect();
+        let raw = self.mcap_passthrough.as_ref().and_then(|passthrough| {
+            ids.iter()
+                .find_map(|component_id| passthrough.sender(component_id))
+        });
+        let sources = std::iter::once(worker)
+            .chain(shared.iter().map(|(_, source)| *source))
+            .zip(
+                ids.iter()
+                    .map(|component_id| self.output_channel(component_id)),
+            )
+            .collect();
+        let subscription_worker = SubscriptionWorker::new(
+            node,
+            ros_type,
+            sources,
+            registry,
+            raw,
+            self.log_converter_info,
+        )
+        .map_err(|err| TopologyConfigError::from_worker(id.clone(), err))?;
+        self.topic_subscriptions
+            .insert(id.clone(), subscription_worker);
+        for (shared_id, _) in shared {
+            self.shared_subscriptions
+                .insert((*shared_id).clone(), id.clone());
+        }
+        Ok(())
+    }
+
+    /// Create the input channel of every component that has inputs and no
+    /// channel yet, returning the receivers of the created channels.
+    fn apply_edges(&mut self, config: &TopologyConfig) -> HashMap<ComponentID, ArchetypeReceiver> {
         let mut rx_map = HashMap::new();
-        // Apply edges
         for (id, channel) in &config.edges {
+            // Running components keep their channel, only their inputs change
+            if let Some(input) = self.edges.get_mut(id) {
+                input.components.clone_from(channel);
+                continue;
+            }
             let (tx, rx) = unbounded_channel::<LogData>();
+            let in_flight = InFlight::new(&id.to_string(), config.max_in_flight(id));
             self.edges.insert(
                 id.clone(),
                 InputChannel {
                     components: channel.clone(),
-                    channel: ArchetypeSender { tx: vec![tx] },
+                    channel: ArchetypeSender {
+                        tx: vec![SinkSender {
+                            tx,
+                            in_flight: in_flight.clone(),
+                        }],
+                    },
                 },
             );
-            rx_map.insert(id, ArchetypeReceiver { rx });
+            rx_map.insert(id.clone(), ArchetypeReceiver { rx, in_flight });
         }
+        rx_map
+    }
 
-        // Apply topic subscriptions
-        for (id, worker) in &config.topic_subscriptions {
-            let connecting_components = self
-                .edges
-                .iter()
-                .filter_map(|(edge_id, input)| {
-                    if input.components.contains(id) {
-                        Some(edge_id.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
-            let input_channel = connecting_components
-                .iter()
-                .map(|comp_id| {
-                    self.edges
-                        .get(comp_id)
-                        .map(|input| input.channel.clone())
-                        .expect("No channel for component")
-                })
-                .collect::<Vec<_>>();
-            // Create a new SubscriptionWorker
-            let subscription_worker = SubscriptionWorker::new(
-                &node,
-                worker,
-                registry,
-                ArchetypeSender {
-                    tx: input_channel
-                        .iter()
-                        .map(|ch| ch.tx.first().expect("No tx channel").clone())
-                        .collect::<Vec<_>>(),
-                },
-            )
-            .map_err(|_err| TopologyConfigError::InitializationError(id.clone()))?;
-            self.topic_subscriptions
-                .insert(id.clone(), subscription_worker);
+    /// Start the transforms and sinks, returning the ones that failed.
+    fn apply_outputs(
+        &mut self,
+        config: &TopologyConfig,
+        rx_map: &mut HashMap<ComponentID, ArchetypeReceiver>,
+        shutdown: &Tripwire,
+    ) -> Vec<TopologyConfigError> {
+        let mut failures = Vec::new();
+
+        // Components without a new channel keep running, see `apply_edges`.
+        // Apply transforms
+        for (id, transform) in &config.transforms {
+            let Some(rx_channel) = rx_map.remove(id) else {
+                continue;
+            };
+            let transform_worker = TransformWorker::new(transform);
+            transform_worker.run(rx_channel, self.output_channel(id), shutdown.clone());
+            self.transforms.insert(id.clone(), transform_worker);
         }
 
         // Apply GRPC sinks
-        for (id, url) in &config.grpc_sinks {
-            let rx_channel = rx_map.remove(id).expect("No channel for component");
+        for (id, stream) in &config.grpc_sinks {
+            let Some(rx_channel) = rx_map.remove(id) else {
+                if let Some(grpc_sink_worker) = self.grpc_sinks.get_mut(id) {
+                    grpc_sink_worker.set_inputs(config.inputs(id));
+                }
+                continue;
+            };
             // Create a new GRPCSinkWorker
-            let grpc_sink_worker = GRPCSinkWorker::new(&StreamConfig {
-                url: url.clone(),
-                inputs: vec![],
-            })
-            .map_err(|_err| TopologyConfigError::InitializationError(id.clone()))?;
-            grpc_sink_worker.run(rx_channel, shutdown.clone());
-            self.grpc_sinks.insert(id.clone(), grpc_sink_worker);
+            match GRPCSinkWorker::new(stream, config.inputs(id), &config.recording_ids) {
+                Ok(grpc_sink_worker) => {
+                    grpc_sink_worker.run(rx_channel, shutdown.clone());
+                    self.grpc_sinks.insert(id.clone(), grpc_sink_worker);
+                }
+                Err(err) => failures.push(TopologyConfigError::from_worker(id.clone(), err)),
+            }
         }
 
         // Apply DB sink
-        let rx_channel = rx_map
-            .remove(&ComponentID::DBSink)
-            .expect("No channel for component");
-        let db_sink_worker = DBSinkWorker::new(&config.db_sink)
-            .map_err(|_err| TopologyConfigError::InitializationError(ComponentID::DBSink))?;
-        db_sink_worker.run(rx_channel, shutdown.clone());
-        self.db_sink = Some(db_sink_worker);
+        if let Some(rx_channel) = rx_map.remove(&ComponentID::DBSink) {
+            match DBSinkWorker::new(&config.db_sink, &config.recording_ids) {
+                Ok(db_sink_worker) => {
+                    db_sink_worker.run(rx_channel, shutdown.clone());
+                    self.db_sink = Some(db_sink_worker);
+                }
+                Err(err) => {
+                    failures.push(TopologyConfigError::from_worker(ComponentID::DBSink, err));
+                }
+            }
+        }
 
-        debug!("Applied topology config {config:?}");
-        Ok(())
+        // Apply self metrics, logged to every sink
+        if self.self_metrics.is_none() {
+            self.self_metrics = config.self_metrics.map(|interval| {
+                let (trigger, shutdown) = Tripwire::new();
+                let worker = SelfMetricsWorker::new(interval, self.sink_senders());
+                worker.run(shutdown);
+                (worker, trigger)
+            });
+        }
+
+        failures
+    }
+
+    /// Running topic subscriptions and their topics, sorted by ID.
+    pub fn active_topics(&self) -> Vec<(ComponentID, String)> {
+        let mut topics: Vec<_> = self
+            .topic_subscriptions
+            .iter()
+            .map(|(id, worker)| (id.clone(), worker.topic().to_owned()))
+            .chain(
+                self.shared_subscriptions
+                    .iter()
+                    .filter_map(|(id, primary)| {
+                        let worker = self.topic_subscriptions.get(primary)?;
+                        Some((id.clone(), worker.topic().to_owned()))
+                    }),
+            )
+            .collect();
+        topics.sort();
+        topics
+    }
+
+    /// The subscription feeding a topic subscriber, shared with other
+    /// subscribers or not.
+    pub fn subscription(&self, id: &ComponentID) -> Option<&SubscriptionWorker> {
+        let primary = self.shared_subscriptions.get(id).unwrap_or(id);
+        self.topic_subscriptions.get(primary)
+    }
+
+    /// Configured topic subscriptions that are not running and their topics,
+    /// e.g. as their ROS type is not available yet, sorted by ID.
+    pub fn inactive_topics(&self) -> Vec<(ComponentID, String)> {
+        let Some(applied) = &self.applied else {
+            return Vec::new();
+        };
+        applied
+            .topic_subscriptions
+            .iter()
+            .filter(|(id, _)| self.subscription(id).is_none())
+            .map(|(id, source)| (id.clone(), source.topic.clone()))
+            .collect()
+    }
+
+    /// Compare the QoS of the publishers of every subscribed topic with its
+    /// subscription, see `SubscriptionWorker::qos_mismatches`, keeping the
+    /// mismatches for `TopologyState::qos_mismatches`.
+    ///
+    /// `publishers` lists the publishers of a topic in the graph with their
+    /// QoS. Call this periodically, as publishers come and go.
+    pub fn check_qos(&mut self, publishers: impl Fn(&str) -> Vec<(String, rclrs::QoSProfile)>) {
+        let mut mismatches: Vec<_> = self
+            .topic_subscriptions
+            .iter()
+            .flat_map(|(id, worker)| {
+                let topic = worker.topic();
+                worker
+                    .qos_mismatches(&publishers(topic))
+                    .into_iter()
+                    .map(move |mismatch| (id.clone(), topic.to_owned(), mismatch))
+            })
+            .collect();
+        mismatches.sort();
+        self.qos_mismatches = mismatches;
+    }
+
+    /// Subscriptions that publishers of their topic do not connect to, with
+    /// the topic and the mismatching policy, as of the last
+    /// `TopologyState::check_qos`, sorted by ID.
+    pub fn qos_mismatches(&self) -> &[(ComponentID, String, QosMismatch)] {
+        &self.qos_mismatches
+    }
+
+    /// Start recording to a fresh `.rrd` file in the database directory,
+    /// returning its path. Does nothing when already recording.
+    ///
+    /// The database sink keeps its inputs while stopped, so the subscriptions
+    /// are recorded again as soon as recording starts. The running bridge
+    /// starts recording on `POST /recording/start`, see `crate::api`, and
+    /// toggles recording on SIGUSR1, see `NodeGraph::run`.
+    ///
+    /// # Errors
+    /// Returns an error if the database sink is not running, e.g. when it is
+    /// disabled in the config, or if the recording file cannot be created.
+    pub fn start_recording(&self) -> Result<PathBuf, TopologyConfigError> {
+        let db_sink = self
+            .db_sink
+            .as_ref()
+            .ok_or(TopologyConfigError::NotRunning(ComponentID::DBSink))?;
+        db_sink
+            .start()
+            .map_err(|err| TopologyConfigError::from_worker(ComponentID::DBSink, err))
+    }
+
+    /// Stop recording after writing the messages in flight,
+    /// returning the path of the finished file, if recording.
+    pub async fn stop_recording(&self) -> Option<PathBuf> {
+        self.db_sink.as_ref()?.stop().await
+    }
+
+    /// The file the database sink is recording to, if recording.
+    pub fn recording_file(&self) -> Option<PathBuf> {
+        self.db_sink.as_ref()?.recording_file()
+    }
+
+    /// Counters of every running sink, sorted by ID.
+    pub fn sink_stats(&self) -> Vec<(ComponentID, SinkStatsSnapshot)> {
+        let mut stats: Vec<_> = self
+            .grpc_sinks
+            .iter()
+            .map(|(id, worker)| (id.clone(), worker.stats()))
+            .chain(
+                self.db_sink
+                    .iter()
+                    .map(|worker| (ComponentID::DBSink, worker.stats())),
+            )
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
+    }
+
+    /// Log the running subscriptions, and warn about configured
+    /// subscriptions that are not running.
+    pub fn log_active_topics(&self, config: &TopologyConfig) {
+        let mut workers: Vec<_> = self.topic_subscriptions.iter().collect();
+        workers.sort_by_key(|(id, _)| *id);
+        for (id, worker) in workers {
+            let archetypes: Vec<_> = worker
+                .rerun_names()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            info!(
+                "{id} subscribed to '{}' as '{}' -> {}",
+                worker.topic(),
+                worker.ros_type(),
+                archetypes.join(", ")
+            );
+        }
+        for (id, primary) in &self.shared_subscriptions {
+            info!("{id} shares the subscription of {primary}");
+        }
+        for (id, source) in &config.topic_subscriptions {
+            if !self.topic_subscriptions.contains_key(id)
+                && !self.shared_subscriptions.contains_key(id)
+            {
+                warn!(
+                    "{id} is configured but not subscribed to '{}'",
+                    source.topic
+                );
+            }
+        }
+    }
+
+    /// Log the world orientation once, as static data on the root entity of every sink.
+    fn log_world_view_coordinates(&self, coordinates: &rerun::ViewCoordinates) {
+        self.send_to_sinks("/", Arc::new(coordinates.clone()), "world view coordinates");
     }
+
+    /// Log the ROS environment once, as a static recording property of every sink.
+    fn log_environment(&self) {
+        let batches: Vec<_> = ros_environment(|name| std::env::var(name).ok())
+            .into_iter()
+            .filter_map(|(name, value)| {
+                [rerun::datatypes::Utf8::from(value)].serialized(
+                    rerun::ComponentDescriptor::partial(format!("ros_rerun.Environment:{name}")),
+                )
+            })
+            .collect();
+        self.send_to_sinks(
+            "/__properties/ros_environment",
+            Arc::new(batches),
+            "ROS environment",
+        );
+    }
+
+    /// Input channels of the sinks, named by their part of the entity path
+    /// of the sink's self metrics.
+    fn sink_senders(&self) -> Vec<(String, SinkSender)> {
+        self.edges
+            .iter()
+            .filter_map(|(id, input)| {
+                let name = match id {
+                    ComponentID::GRPCSink(name) => name.clone(),
+                    ComponentID::DBSink => "db".to_owned(),
+                    _ => return None,
+                };
+                Some(
+                    input
+                        .channel
+                        .tx
+                        .iter()
+                        .map(move |tx| (name.clone(), tx.clone())),
+                )
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Send static data to every sink, e.g. properties of the whole recording.
+    fn send_to_sinks(
+        &self,
+        entity_path: &str,
+        components: Arc<dyn rerun::AsComponents + Send + Sync>,
+        what: &str,
+    ) {
+        let data = LogData::AnyComponents(LogComponents {
+            entity_path: Arc::new(entity_path.to_owned()),
+            header: None,
+            components,
+            priority: Priority::High,
+            is_static: true,
+        });
+        let sinks = self
+            .edges
+            .iter()
+            .filter(|(id, _)| matches!(id, ComponentID::GRPCSink(_) | ComponentID::DBSink));
+        for (id, input) in sinks {
+            for tx in &input.channel.tx {
+                if let Err(err) = tx.send(data.clone()) {
+                    error!("Failed to send {what} to {id}: {err:?}");
+                }
+            }
+        }
+    }
+
+    /// Channel sending to every component that has `id` as an input.
+    fn output_channel(&self, id: &ComponentID) -> ArchetypeSender {
+        ArchetypeSender {
+            tx: self
+                .edges
+                .values()
+                .filter(|input| input.components.contains(id))
+                .flat_map(|input| input.channel.tx.iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+/// Wait until the queues counted by `in_flight` are empty or `timeout`
+/// passed, returning the number of messages still queued.
+async fn drain(in_flight: &[InFlight], timeout: Duration) -> usize {
+    let queued = || in_flight.iter().map(InFlight::count).sum::<usize>();
+    let deadline = Instant::now() + timeout;
+    while queued() > 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    queued()
+}
+
+/// The ROS environment variables describing the runtime context.
+///
+/// Unset variables are recorded as the default ROS uses for them.
+fn ros_environment(var: impl Fn(&str) -> Option<String>) -> Vec<(&'static str, String)> {
+    [
+        ("domain_id", "ROS_DOMAIN_ID", "0"),
+        ("rmw_implementation", "RMW_IMPLEMENTATION", "default"),
+        ("distro", "ROS_DISTRO", "unknown"),
+    ]
+    .into_iter()
+    .map(|(name, variable, default)| {
+        (
+            name,
+            var(variable)
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| default.to_owned()),
+        )
+    })
+    .collect()
 }
 
 struct InputChannel {
@@ -259,7 +1286,20 @@ struct InputChannel {
 pub enum ComponentID {
     TopicSubscriber(String),
     GRPCSink(String),
+    Transform(String),
     DBSink,
+    McapPassthrough,
+}
+
+impl ComponentID {
+    /// Name of the component in the configuration.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::TopicSubscriber(name) | Self::GRPCSink(name) | Self::Transform(name) => name,
+            Self::DBSink => "db",
+            Self::McapPassthrough => "mcap_passthrough",
+        }
+    }
 }
 
 impl Display for ComponentID {
@@ -267,7 +1307,9 @@ impl Display for ComponentID {
         match self {
             Self::TopicSubscriber(name) => write!(f, "Message subscriber '{name}'"),
             Self::GRPCSink(name) => write!(f, "Rerun SDK stream '{name}'"),
+            Self::Transform(name) => write!(f, "Transform '{name}'"),
             Self::DBSink => write!(f, "Database"),
+            Self::McapPassthrough => write!(f, "MCAP passthrough"),
         }
     }
 }
@@ -303,6 +1345,7 @@ mod tests {
                 config::StreamConfig {
                     url: "http://localhost:8080".parse().expect("Invalid address"),
                     inputs: vec![],
+                    ..Default::default()
                 },
             )]),
             ..Default::default()
@@ -311,6 +1354,88 @@ mod tests {
         assert!(topology.is_ok());
     }
 
+    #[test]
+    fn skips_disabled_topics() {
+        let cfg = config::Config {
+            topics: HashMap::from([(
+                "comp1".into(),
+                config::TopicSource {
+                    topic: "example_topic".into(),
+                    ros_type: Some("std_msgs/String".into()),
+                    archetype: "TextLog".into(),
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )]),
+            streams: HashMap::from([(
+                "stream1".into(),
+                config::StreamConfig {
+                    url: "http://localhost:8080".parse().expect("Invalid address"),
+                    inputs: vec!["comp1".into()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        assert!(topology.topic_subscriptions.is_empty());
+        assert!(topology
+            .inputs(&ComponentID::GRPCSink("stream1".into()))
+            .is_empty());
+    }
+
+    #[test]
+    fn coalesces_duplicate_topics() {
+        let source = |topic: &str, ros_type: Option<&str>, archetype: &str| config::TopicSource {
+            topic: topic.into(),
+            ros_type: ros_type.map(Into::into),
+            archetype: archetype.into(),
+            ..Default::default()
+        };
+        let cfg = config::Config {
+            topics: HashMap::from([
+                (
+                    "a".into(),
+                    source("/cloud", Some("sensor_msgs/PointCloud2"), "Points3D"),
+                ),
+                (
+                    "b".into(),
+                    source("/cloud", Some("sensor_msgs/PointCloud2"), "Boxes3D"),
+                ),
+                (
+                    "c".into(),
+                    source("/cloud", Some("sensor_msgs/PointCloud2"), "Points3D"),
+                ),
+                (
+                    "d".into(),
+                    source("/other", Some("sensor_msgs/PointCloud2"), "Points3D"),
+                ),
+                ("e".into(), source("/cloud", None, "Points3D")),
+            ]),
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+
+        let primary = ComponentID::TopicSubscriber("a".into());
+        assert_eq!(
+            topology.shared_subscriptions,
+            BTreeMap::from([
+                (ComponentID::TopicSubscriber("b".into()), primary.clone()),
+                (ComponentID::TopicSubscriber("c".into()), primary.clone()),
+            ])
+        );
+        let shared: Vec<_> = topology
+            .shared_with(&primary)
+            .into_iter()
+            .map(|(id, source)| (id.to_string(), source.archetype.as_str()))
+            .collect();
+        assert_eq!(shared.len(), 2);
+        assert!(shared.iter().any(|(_, archetype)| *archetype == "Boxes3D"));
+        assert!(topology
+            .shared_with(&ComponentID::TopicSubscriber("d".into()))
+            .is_empty());
+    }
+
     #[test]
     fn invalid_duplicates() {
         let cfg = config::Config {
@@ -328,6 +1453,7 @@ mod tests {
                 config::StreamConfig {
                     url: "http://localhost:8080".parse().expect("Invalid address"),
                     inputs: vec![],
+                    ..Default::default()
                 },
             )]),
             ..Default::default()
@@ -336,6 +1462,597 @@ mod tests {
         assert!(topology.is_err());
     }
 
+    #[test]
+    fn transform_between_topic_and_stream() {
+        let cfg = config::Config {
+            topics: HashMap::from([(
+                "comp1".into(),
+                config::TopicSource {
+                    topic: "example_topic".into(),
+                    ros_type: Some("std_msgs/String".into()),
+                    archetype: "TextLog".into(),
+                    ..Default::default()
+                },
+            )]),
+            transforms: HashMap::from([(
+                "decimated".into(),
+                config::TransformConfig {
+                    inputs: vec!["comp1".into()],
+                    decimate: Some(10),
+                    ..Default::default()
+                },
+            )]),
+            streams: HashMap::from([(
+                "stream1".into(),
+                config::StreamConfig {
+                    url: "http://localhost:8080".parse().expect("Invalid address"),
+                    inputs: vec!["decimated".into()],
+                    ..Default::default()
+                },
+            )]),
+            db: config::DBConfig {
+                inputs: vec!["comp1".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        assert_eq!(
+            topology.edges.get(&ComponentID::GRPCSink("stream1".into())),
+            Some(&vec![ComponentID::Transform("decimated".into())])
+        );
+        assert_eq!(
+            topology
+                .edges
+                .get(&ComponentID::Transform("decimated".into())),
+            Some(&vec![ComponentID::TopicSubscriber("comp1".into())])
+        );
+        assert_eq!(
+            topology.edges.get(&ComponentID::DBSink),
+            Some(&vec![ComponentID::TopicSubscriber("comp1".into())])
+        );
+    }
+
+    #[test]
+    fn ros_environment_defaults() {
+        let environment =
+            ros_environment(|name| (name == "ROS_DISTRO").then(|| "jazzy".to_owned()));
+        assert_eq!(
+            environment,
+            [
+                ("domain_id", "0".to_owned()),
+                ("rmw_implementation", "default".to_owned()),
+                ("distro", "jazzy".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn deferred_topics_resolve_once() {
+        let topic = |name: &str| config::TopicSource {
+            topic: name.into(),
+            archetype: "TextLog".into(),
+            ..Default::default()
+        };
+        let mut state = TopologyState::default();
+        state.deferred_subscriptions.extend([
+            (
+                ComponentID::TopicSubscriber("online".into()),
+                topic("/online"),
+            ),
+            (
+                ComponentID::TopicSubscriber("offline".into()),
+                topic("/offline"),
+            ),
+        ]);
+        let resolve = |name: &str| {
+            (name == "/online")
+                .then(|| ROSTypeName::try_from("std_msgs/msg/String").expect("valid type"))
+        };
+
+        let resolved = state.resolve_deferred(resolve);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, ComponentID::TopicSubscriber("online".into()));
+        assert!(resolved[0].1.ros_type.is_some(), "type is filled in");
+        assert!(
+            state.resolve_deferred(resolve).is_empty(),
+            "a topic is never resolved twice"
+        );
+        assert_eq!(state.deferred_subscriptions.len(), 1, "still waiting");
+
+        state.deferred_subscriptions.insert(
+            ComponentID::TopicSubscriber("any".into()),
+            config::TopicSource {
+                ros_type: Some(config::ANY_ROS_TYPE.into()),
+                ..topic("/online")
+            },
+        );
+        let resolved = state.resolve_deferred(resolve);
+        assert_eq!(resolved.len(), 1);
+        assert!(
+            resolved[0].1.is_any_ros_type(),
+            "still uses a generic converter"
+        );
+        assert_eq!(
+            resolved[0].2,
+            ROSTypeName::try_from("std_msgs/msg/String").expect("valid type")
+        );
+    }
+
+    #[test]
+    fn shared_subscription_failures_name_every_subscriber() {
+        let primary = ComponentID::TopicSubscriber("cloud".into());
+        let (points, boxes) = (
+            ComponentID::TopicSubscriber("cloud_points".into()),
+            ComponentID::TopicSubscriber("cloud_boxes".into()),
+        );
+        let source = config::TopicSource::default();
+        let shared = [(&points, &source), (&boxes, &source)];
+        let failures: Vec<_> = TopologyConfigError::shared_failures(&primary, &shared)
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(
+            failures,
+            [
+                "Component Message subscriber 'cloud_points' shares the subscription of \
+                 Message subscriber 'cloud', which failed to initialize",
+                "Component Message subscriber 'cloud_boxes' shares the subscription of \
+                 Message subscriber 'cloud', which failed to initialize",
+            ]
+        );
+    }
+
+    #[test]
+    fn deferred_sharers_subscribe_with_their_primary() {
+        let id = |name: &str| ComponentID::TopicSubscriber(name.into());
+        let primaries = BTreeMap::from([
+            (id("cloud_boxes"), id("cloud")),
+            (id("scan_points"), id("scan")),
+        ]);
+        let groups = group_shared(
+            [
+                (id("cloud"), 1),
+                (id("cloud_boxes"), 2),
+                (id("scan_points"), 3),
+            ],
+            &primaries,
+        );
+        assert_eq!(
+            groups,
+            [
+                ((id("cloud"), 1), vec![(id("cloud_boxes"), 2)]),
+                ((id("scan_points"), 3), vec![]),
+            ],
+            "a sharer without its primary subscribes on its own"
+        );
+    }
+
+    #[test]
+    fn unresolved_types_wait_for_topic() {
+        let id = ComponentID::TopicSubscriber("status".into());
+        let err = ROSTypeName::try_from("missing_msgs/msg/Status").expect_err("not sourced");
+        let err = TopologyConfigError::from_worker(id.clone(), WorkerError::from(err));
+        assert!(err.is_unresolved_type());
+        assert!(!TopologyConfigError::NotRunning(id.clone()).is_unresolved_type());
+
+        let mut state = TopologyState::default();
+        state.deferred_subscriptions.insert(
+            id.clone(),
+            config::TopicSource {
+                topic: "/status".into(),
+                ros_type: Some("missing_msgs/msg/Status".into()),
+                archetype: "TextLog".into(),
+                ..Default::default()
+            },
+        );
+        let graph = |published: bool| {
+            move |topic: &str| {
+                (published && topic == "/status")
+                    .then(|| ROSTypeName::try_from("std_msgs/msg/String").expect("valid type"))
+            }
+        };
+        assert!(
+            state.resolve_deferred(graph(false)).is_empty(),
+            "not published yet"
+        );
+        let resolved = state.resolve_deferred(graph(true));
+        assert_eq!(resolved.len(), 1, "retried once the topic appears");
+        assert_eq!(
+            resolved[0].1.known_ros_type(),
+            Some("missing_msgs/msg/Status"),
+            "the configured type is kept"
+        );
+        assert!(state.deferred_subscriptions.is_empty());
+    }
+
+    #[test]
+    fn reconcile_plan_replaces_changed_topics() {
+        let source = |topic: &str| config::TopicSource {
+            topic: topic.into(),
+            ros_type: Some("sensor_msgs/PointCloud2".into()),
+            archetype: "Points3D".into(),
+            ..Default::default()
+        };
+        let stream = |inputs: &[&str]| config::StreamConfig {
+            url: "http://localhost:8080".parse().expect("Invalid address"),
+            inputs: inputs.iter().map(|input| (*input).into()).collect(),
+            ..Default::default()
+        };
+        let current = config::Config {
+            topics: HashMap::from([
+                ("front".into(), source("/front/points")),
+                ("rear".into(), source("/rear/points")),
+                ("old".into(), source("/old/points")),
+            ]),
+            streams: HashMap::from([("viewer".into(), stream(&["front", "rear", "new"]))]),
+            ..Default::default()
+        };
+        let mut next = current.clone();
+        next.topics.remove("old");
+        next.topics.insert("new".into(), source("/new/points"));
+        next.topics.insert(
+            "rear".into(),
+            config::TopicSource {
+                max_rate_hz: Some(5.0),
+                ..source("/rear/points")
+            },
+        );
+        next.topics.insert("shared".into(), source("/front/points"));
+
+        let current = parse_topology_config(&current).expect("valid topology");
+        let next = parse_topology_config(&next).expect("valid topology");
+        assert!(current.same_options(&next));
+        let plan = current.reconcile_plan(&next);
+        assert!(
+            plan.stopped.is_empty() && plan.started.is_empty(),
+            "{plan:?}"
+        );
+        let id = |name: &str| ComponentID::TopicSubscriber(name.into());
+        assert_eq!(
+            plan.topics,
+            BTreeSet::from([id("new"), id("old"), id("rear"), id("shared")]),
+            "'front' is unchanged, but replaced with 'shared' sharing it"
+        );
+        assert_eq!(next.reconcile_plan(&next), ReconcilePlan::default());
+
+        let mut restarted = next.clone();
+        restarted.log_converter_info = !restarted.log_converter_info;
+        assert!(!next.same_options(&restarted), "restarts the topology");
+    }
+
+    #[test]
+    fn reconcile_plan_adds_only_new_topic() {
+        let source = |topic: &str| config::TopicSource {
+            topic: topic.into(),
+            ros_type: Some("sensor_msgs/Imu".into()),
+            archetype: "Scalars".into(),
+            ..Default::default()
+        };
+        let stream = |inputs: &[&str]| config::StreamConfig {
+            url: "http://localhost:8080".parse().expect("Invalid address"),
+            inputs: inputs.iter().map(|input| (*input).into()).collect(),
+            ..Default::default()
+        };
+        let two_topics = config::Config {
+            topics: HashMap::from([
+                ("left".into(), source("/left/imu")),
+                ("right".into(), source("/right/imu")),
+            ]),
+            streams: HashMap::from([("viewer".into(), stream(&["left", "right"]))]),
+            ..Default::default()
+        };
+        let mut three_topics = two_topics.clone();
+        three_topics
+            .topics
+            .insert("center".into(), source("/center/imu"));
+        three_topics
+            .streams
+            .insert("viewer".into(), stream(&["left", "right", "center"]));
+
+        let current = parse_topology_config(&two_topics).expect("valid topology");
+        let next = parse_topology_config(&three_topics).expect("valid topology");
+        assert_eq!(
+            current.reconcile_plan(&next),
+            ReconcilePlan {
+                topics: BTreeSet::from([ComponentID::TopicSubscriber("center".into())]),
+                ..Default::default()
+            },
+            "the stream keeps running with a new input"
+        );
+
+        let mut moved = three_topics.clone();
+        moved.streams.insert(
+            "viewer".into(),
+            config::StreamConfig {
+                url: "http://localhost:9090".parse().expect("Invalid address"),
+                ..stream(&["left", "right", "center"])
+            },
+        );
+        let moved = parse_topology_config(&moved).expect("valid topology");
+        let plan = next.reconcile_plan(&moved);
+        let viewer = ComponentID::GRPCSink("viewer".into());
+        assert_eq!(plan.stopped, BTreeSet::from([viewer.clone()]));
+        assert_eq!(plan.started, BTreeSet::from([viewer]));
+        assert_eq!(plan.topics.len(), 3, "the topics feed the new stream");
+        assert!(plan.changes_sinks());
+    }
+
+    #[test]
+    fn reconcile_restarts_for_mcap_passthrough() {
+        let source = |topic: &str| config::TopicSource {
+            topic: topic.into(),
+            ros_type: Some("sensor_msgs/Imu".into()),
+            archetype: "Scalars".into(),
+            ..Default::default()
+        };
+        let passthrough = |topics: &[&str]| {
+            Some(config::McapPassthroughConfig {
+                data_dir: std::env::temp_dir(),
+                topics: topics.iter().map(|topic| (*topic).into()).collect(),
+            })
+        };
+        let cfg = config::Config {
+            topics: HashMap::from([
+                ("left".into(), source("/left/imu")),
+                ("right".into(), source("/right/imu")),
+            ]),
+            mcap_passthrough: passthrough(&["left"]),
+            ..Default::default()
+        };
+        let current = parse_topology_config(&cfg).expect("valid topology");
+
+        let mut added = cfg.clone();
+        added.topics.insert("center".into(), source("/center/imu"));
+        let added = parse_topology_config(&added).expect("valid topology");
+        assert!(
+            current.same_options(&added),
+            "the passthrough keeps recording"
+        );
+
+        for mcap_passthrough in [passthrough(&["left", "right"]), None] {
+            let changed = config::Config {
+                mcap_passthrough,
+                ..cfg.clone()
+            };
+            let changed = parse_topology_config(&changed).expect("valid topology");
+            assert!(
+                !current.same_options(&changed),
+                "the subscriptions restart with the new passthrough"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn reconcile_keeps_running_outputs() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_reconcile");
+        std::fs::create_dir_all(&data_dir).expect("temporary directory");
+        let transform = |rate| config::TransformConfig {
+            rate: Some(rate),
+            ..Default::default()
+        };
+        let cfg = config::Config {
+            transforms: HashMap::from([("slow".into(), transform(1.0))]),
+            db: config::DBConfig {
+                enabled: Some(false),
+                data_dir,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let current = parse_topology_config(&cfg).expect("valid topology");
+        let mut state = TopologyState::default();
+        let (_trigger, shutdown) = Tripwire::new();
+        let mut rx_map = state.apply_edges(&current);
+        assert!(state
+            .apply_outputs(&current, &mut rx_map, &shutdown)
+            .is_empty());
+        let (slow, fast) = (
+            ComponentID::Transform("slow".into()),
+            ComponentID::Transform("fast".into()),
+        );
+        let slow_tx = state.edges[&slow].channel.tx[0].tx.clone();
+
+        let mut added = cfg.clone();
+        added.transforms.insert("fast".into(), transform(10.0));
+        let added = parse_topology_config(&added).expect("valid topology");
+        let plan = current.reconcile_plan(&added);
+        assert_eq!(plan.started, BTreeSet::from([fast.clone()]));
+        let failures = state
+            .reconcile_outputs(&current, &added, &plan, &shutdown)
+            .await;
+        assert!(failures.is_empty(), "{failures:?}");
+        assert!(state.transforms.contains_key(&slow) && state.transforms.contains_key(&fast));
+        assert!(
+            state.edges[&slow].channel.tx[0].tx.same_channel(&slow_tx),
+            "the running transform keeps its channel"
+        );
+
+        let mut removed = cfg.clone();
+        removed.transforms = HashMap::from([("fast".into(), transform(10.0))]);
+        let removed = parse_topology_config(&removed).expect("valid topology");
+        let plan = added.reconcile_plan(&removed);
+        assert_eq!(plan.stopped, BTreeSet::from([slow.clone()]));
+        let failures = state
+            .reconcile_outputs(&added, &removed, &plan, &shutdown)
+            .await;
+        assert!(failures.is_empty(), "{failures:?}");
+        assert!(!state.transforms.contains_key(&slow) && !state.edges.contains_key(&slow));
+        assert!(state.transforms.contains_key(&fast));
+    }
+
+    #[tokio::test]
+    async fn recording_starts_and_stops() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_recording_control");
+        std::fs::create_dir_all(&data_dir).expect("temporary directory");
+        let cfg = config::Config {
+            db: config::DBConfig {
+                data_dir: data_dir.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        let mut state = TopologyState::default();
+        let (_trigger, shutdown) = Tripwire::new();
+        let mut rx_map = state.apply_edges(&topology);
+        let failures = state.apply_outputs(&topology, &mut rx_map, &shutdown);
+        assert!(failures.is_empty(), "{failures:?}");
+        let first = state
+            .recording_file()
+            .expect("recording starts with the bridge");
+        assert_eq!(state.stop_recording().await, Some(first.clone()));
+        assert_eq!(state.stop_recording().await, None);
+
+        let file = state.start_recording().expect("recording starts");
+        assert!(file.starts_with(&data_dir));
+        assert_ne!(file, first, "each recording gets a fresh file");
+        assert_eq!(state.start_recording().expect("still recording"), file);
+        assert_eq!(state.stop_recording().await, Some(file.clone()));
+        assert!(file.is_file(), "recording is written");
+        assert_eq!(state.recording_file(), None);
+        for file in [first, file] {
+            std::fs::remove_file(file).expect("recording file");
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_db_has_no_sink() {
+        let cfg = config::Config {
+            topics: HashMap::from([(
+                "comp1".into(),
+                config::TopicSource {
+                    topic: "example_topic".into(),
+                    ros_type: Some("std_msgs/String".into()),
+                    archetype: "TextLog".into(),
+                    ..Default::default()
+                },
+            )]),
+            db: config::DBConfig {
+                enabled: Some(false),
+                data_dir: "/nonexistent/ros_rerun".into(),
+                inputs: vec!["comp1".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        assert!(!topology.edges.contains_key(&ComponentID::DBSink));
+
+        let mut state = TopologyState::default();
+        let (_trigger, shutdown) = Tripwire::new();
+        let mut rx_map = state.apply_edges(&topology);
+        let failures = state.apply_outputs(&topology, &mut rx_map, &shutdown);
+        assert!(failures.is_empty(), "{failures:?}");
+        assert!(state.db_sink.is_none());
+        assert!(matches!(
+            state.start_recording(),
+            Err(TopologyConfigError::NotRunning(ComponentID::DBSink))
+        ));
+    }
+
+    #[tokio::test]
+    async fn failing_component_does_not_stop_others() {
+        let cfg = config::Config {
+            topics: HashMap::from([(
+                "comp1".into(),
+                config::TopicSource {
+                    topic: "example_topic".into(),
+                    ros_type: Some("std_msgs/String".into()),
+                    archetype: "TextLog".into(),
+                    ..Default::default()
+                },
+            )]),
+            transforms: HashMap::from([(
+                "decimated".into(),
+                config::TransformConfig {
+                    inputs: vec!["comp1".into()],
+                    decimate: Some(2),
+                    ..Default::default()
+                },
+            )]),
+            db: config::DBConfig {
+                data_dir: "/nonexistent/ros_rerun".into(),
+                inputs: vec!["decimated".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        let mut state = TopologyState::default();
+        let (_trigger, shutdown) = Tripwire::new();
+        let mut rx_map = state.apply_edges(&topology);
+        let failures = state.apply_outputs(&topology, &mut rx_map, &shutdown);
+        assert_eq!(failures.len(), 1, "the database cannot be created");
+        assert!(
+            matches!(
+                &failures[0],
+                TopologyConfigError::RerunInitializationError(ComponentID::DBSink, _)
+            ),
+            "unexpected failure {:?}",
+            failures[0]
+        );
+        assert!(
+            state
+                .transforms
+                .contains_key(&ComponentID::Transform("decimated".into())),
+            "the transform still runs"
+        );
+    }
+
+    #[test]
+    fn grpc_sink_reports_inputs() {
+        let cfg = config::Config {
+            topics: HashMap::from([(
+                "comp1".into(),
+                config::TopicSource {
+                    topic: "example_topic".into(),
+                    ros_type: Some("std_msgs/String".into()),
+                    archetype: "TextLog".into(),
+                    ..Default::default()
+                },
+            )]),
+            streams: HashMap::from([(
+                "stream1".into(),
+                config::StreamConfig {
+                    url: "http://localhost:8080".parse().expect("Invalid address"),
+                    inputs: vec!["comp1".into(), "missing".into()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg).expect("valid topology");
+        let id = ComponentID::GRPCSink("stream1".into());
+        let stream = topology.grpc_sinks.get(&id).expect("stream is configured");
+        let worker = GRPCSinkWorker::with_recording(
+            stream,
+            topology.inputs(&id),
+            rerun::RecordingStream::disabled(),
+        );
+        assert_eq!(
+            worker.inputs(),
+            [ComponentID::TopicSubscriber("comp1".into())],
+            "only connected components are inputs"
+        );
+    }
+
+    #[test]
+    fn invalid_transform_cycle() {
+        let transform = |input: &str| config::TransformConfig {
+            inputs: vec![input.into()],
+            ..Default::default()
+        };
+        let cfg = config::Config {
+            transforms: HashMap::from([
+                ("t1".into(), transform("t2")),
+                ("t2".into(), transform("t1")),
+            ]),
+            ..Default::default()
+        };
+        let topology = parse_topology_config(&cfg);
+        assert!(matches!(topology, Err(TopologyConfigError::Cycle(_))));
+    }
+
     #[test]
     fn invalid_self_referencing() {
         let cfg = config::Config {
@@ -354,6 +2071,7 @@ mod tests {
                     config::StreamConfig {
                         url: "http://localhost:8080".parse().expect("Invalid address"),
                         inputs: vec!["stream1".into(), "comp1".into()],
+                        ..Default::default()
                     },
                 ),
                 (
@@ -361,6 +2079,7 @@ mod tests {
                     config::StreamConfig {
                         url: "http://localhost:8080".parse().expect("Invalid address"),
                         inputs: vec!["stream1".into(), "comp1".into()],
+                        ..Default::default()
                     },
                 ),
             ]),
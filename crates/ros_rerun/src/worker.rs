@@ -1,81 +1,426 @@
-use std::sync::Arc;
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use log::{debug, error};
-use rclrs::DynamicSubscription;
+use ahash::{HashMap, HashMapExt as _, HashSet};
+use log::{debug, error, warn};
+use parking_lot::{Mutex, RwLock};
+use rclrs::{DynamicMessageError, DynamicSubscription, IntoPrimitiveOptions as _, RclrsError};
+use rerun::{
+    external::{
+        arrow::array::Array as _,
+        re_log::{error_once, warn_once},
+    },
+    AsComponents as _, ComponentBatch as _,
+};
 use ros_rerun_types::{
-    converter::{Converter, ConverterBuilder, ConverterRegistry, ConverterSettings},
+    converter::{
+        ColumnData, Converter, ConverterBuilder, ConverterError, ConverterRegistry,
+        ConverterSettings, Header, ROS_TIMELINE,
+    },
+    definitions::std_msgs,
+    dynamic_message::{serialize_cdr, simple_to_i64, with_path},
     ROSTypeName, RerunName,
 };
 use stream_cancel::Tripwire;
+use thiserror::Error;
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 
 use crate::{
-    channel::{ArchetypeReceiver, ArchetypeSender, LogComponents, LogData},
-    config::{DBConfig, StreamConfig, TopicSource},
+    channel::{ArchetypeReceiver, ArchetypeSender, LogColumns, LogComponents, LogData, SinkSender},
+    config::{
+        defs::{expand_entity_path, DEFAULT_APPLICATION_ID},
+        Config, DBConfig, Priority, StreamConfig, TimeDedup, TimeDedupConfig, TopicSource,
+        TransformConfig, DEFAULT_DEDUP_EPSILON_NS,
+    },
+    metrics::{MetricsRates, SinkStats, SinkStatsSnapshot, METRICS},
+    passthrough::RawMessage,
+    topology::ComponentID,
 };
 
+/// Errors occurring while setting up a worker.
+///
+/// Wraps the errors of the underlying libraries so the topology
+/// only has to deal with a single error type per component.
+#[derive(Error, Debug)]
+pub enum WorkerError {
+    #[error(transparent)]
+    Converter(#[from] ConverterError),
+
+    #[error("invalid ROS type: {0}")]
+    ROSType(#[from] DynamicMessageError),
+
+    #[error("failed to create subscription: {0}")]
+    Subscription(#[from] RclrsError),
+
+    #[error(transparent)]
+    Rerun(#[from] rerun::RecordingStreamError),
+
+    #[error("invalid entity hints: {0}")]
+    Hints(#[source] anyhow::Error),
+
+    #[error("failed to create {}: {1}", .0.display())]
+    CreateFile(PathBuf, #[source] std::io::Error),
+
+    #[error("failed to write MCAP file: {0}")]
+    Mcap(#[from] mcap::McapError),
+}
+
 pub struct SubscriptionWorker {
     topic: String,
+    ros_type: ROSTypeName,
+    rerun_names: Vec<RerunName>,
+    /// Quality of service the topic is subscribed with.
+    qos: rclrs::QoSProfile,
     _subscription: DynamicSubscription,
-    _converter: Arc<Box<dyn Converter>>,
+}
+
+/// Interval between checks of the QoS of the publishers of the subscribed
+/// topics, see `SubscriptionWorker::qos_mismatches`.
+pub const QOS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A publisher whose QoS policy does not satisfy the subscription to its
+/// topic. DDS does not connect them, so the subscription receives nothing
+/// from the publisher.
+///
+/// `rclrs` does not expose the QoS incompatibility events of DDS, so the
+/// policies are compared with the publishers from the ROS graph instead.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QosMismatch {
+    /// Fully qualified name of the publishing node.
+    pub publisher: String,
+    pub policy: &'static str,
+    pub offered: String,
+    pub requested: String,
+}
+
+impl std::fmt::Display for QosMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} offers {} {}, but the subscription requests {}",
+            self.publisher, self.policy, self.offered, self.requested
+        )
+    }
+}
+
+/// The first policy in which the QoS `offered` by a publisher is weaker
+/// than the QoS `requested` by a subscription, following the DDS
+/// request-offered rules, with the offered and requested values.
+///
+/// System defaults are left to the middleware and never reported.
+fn incompatible_qos_policy(
+    offered: &rclrs::QoSProfile,
+    requested: &rclrs::QoSProfile,
+) -> Option<(&'static str, String, String)> {
+    use rclrs::{QoSDurabilityPolicy, QoSLivelinessPolicy, QoSReliabilityPolicy};
+
+    let mismatch = |policy, offered: &str, requested: &str| {
+        Some((policy, offered.to_owned(), requested.to_owned()))
+    };
+    if matches!(offered.reliability, QoSReliabilityPolicy::BestEffort)
+        && matches!(requested.reliability, QoSReliabilityPolicy::Reliable)
+    {
+        return mismatch("reliability", "best effort", "reliable");
+    }
+    if matches!(offered.durability, QoSDurabilityPolicy::Volatile)
+        && matches!(requested.durability, QoSDurabilityPolicy::TransientLocal)
+    {
+        return mismatch("durability", "volatile", "transient local");
+    }
+    if let Some((offered, requested)) = longer_period(offered.deadline, requested.deadline) {
+        return Some(("deadline", offered, requested));
+    }
+    if matches!(offered.liveliness, QoSLivelinessPolicy::Automatic)
+        && matches!(requested.liveliness, QoSLivelinessPolicy::ManualByTopic)
+    {
+        return mismatch("liveliness", "automatic", "manual by topic");
+    }
+    longer_period(
+        offered.liveliness_lease_duration,
+        requested.liveliness_lease_duration,
+    )
+    .map(|(offered, requested)| ("liveliness lease duration", offered, requested))
+}
+
+/// The offered and requested periods, when a period `offered` by a
+/// publisher is longer than the one `requested` by a subscription.
+fn longer_period(
+    offered: rclrs::QoSDuration,
+    requested: rclrs::QoSDuration,
+) -> Option<(String, String)> {
+    let rclrs::QoSDuration::Custom(requested) = requested else {
+        return None;
+    };
+    match offered {
+        rclrs::QoSDuration::Custom(offered) if offered <= requested => None,
+        rclrs::QoSDuration::Custom(offered) => {
+            Some((format!("{offered:?}"), format!("{requested:?}")))
+        }
+        rclrs::QoSDuration::Infinite => Some(("infinite".to_owned(), format!("{requested:?}"))),
+        _ => None,
+    }
+}
+
+/// A converter fed by a subscription, with the channel of its topic entry.
+struct SubscriptionOutput {
+    converter: Box<dyn Converter>,
+    channel: ArchetypeSender,
+    priority: Priority,
+    /// Added to the logged timestamps, see `TopicSource::time_offset`.
+    time_offset: i64,
+    stale: Option<StaleChildren>,
+    gaps: Option<SequenceGaps>,
+    /// Entity the output logs to, see `TopicSource::entity_path`.
+    entity_path: Arc<String>,
+    /// Template of `entity_path` when it depends on the frame of each message.
+    frame_template: Option<String>,
+    /// Static components of the entity, when it depends on the frame.
+    frame_statics: Option<FrameStatics>,
+    /// Drops messages before conversion, see `TopicSource::max_rate_hz`.
+    throttle: Option<Mutex<Throttle>>,
+}
+
+/// Child entity of a topic that messages lost upstream are logged to,
+/// see `TopicSource::sequence_field`.
+pub const DROPPED_ENTITY: &str = "dropped";
+
+/// Detects messages lost before they reached the bridge from jumps in
+/// their sequence numbers, see `TopicSource::sequence_field`.
+struct SequenceGaps {
+    field: String,
+    last: Mutex<Option<i64>>,
+}
+
+impl SequenceGaps {
+    fn new(field: String) -> Self {
+        Self {
+            field,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Remember the sequence number of a message, returning the number of
+    /// messages missing since the previous one. A sequence that goes back,
+    /// e.g. because the publisher restarted, starts over without a gap.
+    fn update(&self, sequence: i64) -> Option<u64> {
+        let last = self.last.lock().replace(sequence)?;
+        let missing = sequence.checked_sub(last)?.checked_sub(1)?;
+        u64::try_from(missing).ok().filter(|missing| *missing > 0)
+    }
+
+    /// Check the sequence number of `msg`, if it has one.
+    fn check(&self, msg: &rclrs::DynamicMessage) -> Option<u64> {
+        let sequence = with_path(&msg.view(), &self.field, |value| match value {
+            rclrs::Value::Simple(simple) => simple_to_i64(simple),
+            _ => None,
+        })??;
+        self.update(sequence)
+    }
+}
+
+/// Static components of a topic whose entity path depends on the frame of
+/// each message, sent to each expanded path the first time it is logged to.
+#[derive(Default)]
+struct FrameStatics {
+    /// Components with what they are, for the error when sending them.
+    components: Vec<(Vec<rerun::SerializedComponentBatch>, &'static str)>,
+    sent: Mutex<HashSet<Arc<String>>>,
+}
+
+impl FrameStatics {
+    /// The components to send to `entity_path`, empty once sent to it.
+    fn unsent(
+        &self,
+        entity_path: &Arc<String>,
+    ) -> &[(Vec<rerun::SerializedComponentBatch>, &'static str)] {
+        if self.components.is_empty() || !self.sent.lock().insert(entity_path.clone()) {
+            return &[];
+        }
+        &self.components
+    }
+}
+
+/// The child entities logged by the last message of a topic, to clear
+/// those the next message no longer logs, see `TopicSource::clear_stale`.
+#[derive(Default)]
+struct StaleChildren {
+    /// See `TopicSource::clear_recursive`.
+    recursive: Option<bool>,
+    last: Mutex<HashSet<Arc<String>>>,
+}
+
+impl StaleChildren {
+    /// Remember the `children` logged below `topic` by a message, returning
+    /// the earlier children it no longer logs and whether to clear them
+    /// recursively, sorted by path.
+    fn update(
+        &self,
+        topic: &str,
+        children: impl Iterator<Item = Arc<String>>,
+    ) -> Vec<(Arc<String>, bool)> {
+        let children: HashSet<_> = children.filter(|child| **child != topic).collect();
+        let mut last = self.last.lock();
+        let has_descendants = |path: &str| {
+            last.iter().any(|other| {
+                other
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+        };
+        let mut stale: Vec<_> = last
+            .difference(&children)
+            .map(|path| {
+                let recursive = self.recursive.unwrap_or_else(|| has_descendants(path));
+                (path.clone(), recursive)
+            })
+            .collect();
+        stale.sort();
+        *last = children;
+        stale
+    }
 }
 
 impl SubscriptionWorker {
     /// Create a new subscription worker.
     ///
-    /// This will create a new subscription to the specified ROS topic and
-    /// set up the necessary message transformation.
+    /// This will create a single subscription to the ROS topic of `sources`
+    /// with `ros_type`, and convert every message with the converter of each
+    /// source, sending the result to the channel of that source. All sources
+    /// must have the same topic.
+    ///
+    /// Sources with the `*` ROS type use the generic converter of their
+    /// archetype, see `ConverterRegistry::find_converter`. Other sources use
+    /// the converter for `ros_type` and fall back to the generic one.
     ///
     /// # Errors
     ///
-    /// Returns an error if the subscription cannot be created.
+    /// Returns a `WorkerError` if the ROS type is invalid, no converter
+    /// is available, or the subscription cannot be created.
+    ///
+    /// With `log_converter_info`, the converter of each source is logged once
+    /// as static components of its entity, see `converter_info`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sources` is empty.
     pub fn new(
         node: &rclrs::Node,
-        config: &TopicSource,
+        ros_type: &str,
+        sources: Vec<(&TopicSource, ArchetypeSender)>,
         registry: &ConverterRegistry,
-        channel: ArchetypeSender,
-    ) -> anyhow::Result<Self> {
-        let rerun_name =
-            RerunName::RerunArchetype(rerun::ArchetypeName::from(config.archetype.as_str()));
-        // TODO: Handle message type auto-discovery
-        let valid_ros_type = config
-            .ros_type
-            .as_ref()
-            .expect("ROS type auto-discovery is not yet implemented");
-        let ros_type: ROSTypeName = valid_ros_type.as_str().try_into()?;
-
-        let converter = ConverterBuilder::new_with_registry(registry)
-            .topic(&config.topic)
-            .ros_type(ros_type.clone())
-            .rerun_name(rerun_name.clone())
-            .config(ConverterSettings(config.converter.clone()))
-            .build()?;
-        let converter = Arc::new(converter);
-        let cb_converter = converter.clone();
+        raw: Option<UnboundedSender<RawMessage>>,
+        log_converter_info: bool,
+    ) -> Result<Self, WorkerError> {
+        let config = sources.first().expect("No sources for subscription").0;
+        let ros_type = registry.resolve_type(ros_type)?;
         let topic = Arc::new(config.topic.clone());
-        debug!(
-            "Creating subscription to topic '{}' with ROS type '{}' and archetype '{}'",
-            config.topic, ros_type, rerun_name,
-        );
+        let schema_name = Arc::new(ros_type.schema_name());
+
+        let mut outputs = Vec::with_capacity(sources.len());
+        for (source, channel) in sources {
+            let rerun_name =
+                RerunName::RerunArchetype(rerun::ArchetypeName::from(source.archetype.as_str()));
+            let entity_path = Arc::new(source.resolve_entity_path(None));
+            let mut builder = ConverterBuilder::new_with_registry(registry)
+                .topic(&source.topic)
+                .entity_path(&entity_path)
+                .rerun_name(rerun_name.clone())
+                .config(ConverterSettings(source.converter.clone()));
+            if !source.is_any_ros_type() {
+                builder = builder.ros_type(ros_type.clone());
+            }
+            let converter = builder.build()?;
+            debug!(
+                "Creating subscription to topic '{}' with ROS type '{}' and archetype '{}'",
+                source.topic, ros_type, rerun_name,
+            );
 
+            let mut statics = Vec::new();
+            if let Some(hints) = entity_hints(source, &converter.rerun_name())? {
+                statics.push((hints, "entity hints"));
+            }
+            if log_converter_info {
+                let info = converter_info(converter.as_ref(), source, &ros_type);
+                statics.push((info, "converter info"));
+            }
+            let frame_template = source
+                .entity_path_uses_frame()
+                .then(|| source.entity_path.clone())
+                .flatten();
+            // The entity of a frame template is only known once a message arrives
+            let frame_statics = if frame_template.is_some() {
+                Some(FrameStatics {
+                    components: statics,
+                    ..Default::default()
+                })
+            } else {
+                for (batches, what) in statics {
+                    send_static(&channel, &entity_path, batches, what);
+                }
+                None
+            };
+            outputs.push(SubscriptionOutput {
+                converter,
+                channel,
+                priority: source.priority,
+                time_offset: source.time_offset_nanos(),
+                stale: source.clear_stale.then(|| StaleChildren {
+                    recursive: source.clear_recursive,
+                    ..Default::default()
+                }),
+                gaps: source.sequence_field.clone().map(SequenceGaps::new),
+                entity_path,
+                frame_template,
+                frame_statics,
+                throttle: topic_throttle(source).map(Mutex::new),
+            });
+        }
+        let rerun_names = outputs
+            .iter()
+            .map(|output| output.converter.rerun_name())
+            .collect();
+        let outputs = Arc::new(outputs);
+
+        let qos = config.qos_profile();
         let sub = node.create_dynamic_subscription(
-            ros_type.into(),
-            config.topic.as_str(),
+            ros_type.clone().into(),
+            config.topic.as_str().qos(qos),
             move |msg: rclrs::DynamicMessage, _info: rclrs::MessageInfo| {
-                let instance = cb_converter.clone();
-                let channel = channel.clone();
+                let outputs = outputs.clone();
                 let topic = topic.clone();
-                tokio::spawn(async move {
-                    for tx in channel.tx {
-                        if let Ok(convert_data) = instance.convert_view(msg.view()).await {
-                            let arch_msg = LogData::Archetype(LogComponents {
-                                entity_path: topic.clone(),
-                                header: convert_data.header,
-                                components: convert_data.components,
-                            });
-                            if let Err(err) = tx.send(arch_msg) {
-                                error!("Failed to send archetype data: {err:?}");
-                            }
-                        }
+                let received = receive_stamp();
+                METRICS.message_received();
+                if let Some(raw) = &raw {
+                    record_raw(raw, &topic, &schema_name, received, &msg);
+                }
+                // Sequences are checked in the callback, which sees the messages in order
+                let dropped: Vec<_> = outputs
+                    .iter()
+                    .map(|output| output.gaps.as_ref().and_then(|gaps| gaps.check(&msg)))
+                    .collect();
+                // Throttled before conversion, but a message after lost ones is
+                // always forwarded, so that the loss is logged.
+                let now = Instant::now();
+                let admitted: Vec<_> = outputs
+                    .iter()
+                    .zip(&dropped)
+                    .map(|(output, dropped)| output.admit(&topic, now) || dropped.is_some())
+                    .collect();
+                if !admitted.contains(&true) {
+                    return;
+                }
+                spawn_conversion(async move {
+                    let admitted = outputs.iter().zip(dropped).zip(admitted);
+                    for ((output, dropped), _) in admitted.filter(|(_, admitted)| *admitted) {
+                        output.forward(&topic, &msg, received, dropped).await;
                     }
                 });
             },
@@ -83,147 +428,1320 @@ impl SubscriptionWorker {
 
         Ok(Self {
             topic: config.topic.clone(),
+            ros_type,
+            rerun_names,
+            qos,
             _subscription: sub,
-            _converter: converter,
         })
     }
 
     pub fn topic(&self) -> &str {
         &self.topic
     }
+
+    /// ROS type the topic is subscribed with.
+    pub fn ros_type(&self) -> &ROSTypeName {
+        &self.ros_type
+    }
+
+    /// Archetypes of the converters fed by the subscription,
+    /// in the order of the sources it was created with.
+    pub fn rerun_names(&self) -> &[RerunName] {
+        &self.rerun_names
+    }
+
+    /// Quality of service the topic is subscribed with.
+    pub fn qos_profile(&self) -> &rclrs::QoSProfile {
+        &self.qos
+    }
+
+    /// The `publishers` of the topic whose QoS is incompatible with the
+    /// subscription, by node name, with the policy that does not match.
+    pub fn qos_mismatches(&self, publishers: &[(String, rclrs::QoSProfile)]) -> Vec<QosMismatch> {
+        publishers
+            .iter()
+            .filter_map(|(publisher, offered)| {
+                let (policy, offered, requested) = incompatible_qos_policy(offered, &self.qos)?;
+                Some(QosMismatch {
+                    publisher: publisher.clone(),
+                    policy,
+                    offered,
+                    requested,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Runtime that messages are converted on, see `Config::converter_threads`.
+///
+/// Its size is fixed once started, as conversions in flight cannot move
+/// to another runtime. A changed `converter_threads` applies on restart.
+static CONVERSION_RUNTIME: LazyLock<RwLock<Option<tokio::runtime::Runtime>>> =
+    LazyLock::new(RwLock::default);
+
+/// Convert messages on a dedicated runtime with `threads` worker threads
+/// from now on, instead of on the runtime of the subscriptions.
+///
+/// # Errors
+///
+/// Returns an error if the runtime cannot be built or was already started.
+pub fn start_conversion_runtime(threads: NonZeroUsize) -> anyhow::Result<()> {
+    let mut conversion_runtime = CONVERSION_RUNTIME.write();
+    if conversion_runtime.is_some() {
+        anyhow::bail!("conversion runtime is already started");
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(threads.get())
+        .thread_name("ros_rerun-convert")
+        .enable_all()
+        .build()?;
+    *conversion_runtime = Some(runtime);
+    Ok(())
+}
+
+/// Stop the conversion runtime, if started, dropping the conversions in flight.
+///
+/// Call this after the sinks shut down, as the conversions have nowhere to
+/// send their data anymore. Must not be called from within a runtime.
+pub fn stop_conversion_runtime() {
+    if let Some(runtime) = CONVERSION_RUNTIME.write().take() {
+        runtime.shutdown_background();
+    }
+}
+
+/// Spawn the conversion of a message, on the conversion runtime if started.
+fn spawn_conversion(conversion: impl Future<Output = ()> + Send + 'static) {
+    match CONVERSION_RUNTIME.read().as_ref() {
+        Some(runtime) => drop(runtime.spawn(conversion)),
+        None => drop(tokio::spawn(conversion)),
+    }
+}
+
+impl SubscriptionOutput {
+    /// Whether a message on `topic` received at `now` passes the throttle.
+    fn admit(&self, topic: &Arc<String>, now: Instant) -> bool {
+        self.throttle
+            .as_ref()
+            .is_none_or(|throttle| throttle.lock().admit(topic, now))
+    }
+
+    /// Convert a message and send the result to the channel of the output,
+    /// after the number of messages `dropped` before it, if any.
+    async fn forward(
+        &self,
+        topic: &Arc<String>,
+        msg: &rclrs::DynamicMessage,
+        received: Option<i64>,
+        dropped: Option<u64>,
+    ) {
+        let stamped =
+            |header: Option<Header>| Arc::new(log_header(header, received, self.time_offset));
+        if let Some(dropped) = dropped {
+            // Reported before converting, so that the loss is logged
+            // even when the message fails to convert.
+            let header = std_msgs::Header::from_path(&msg.view(), "header")
+                .ok()
+                .flatten()
+                .map(|header| Header::from(&header));
+            let entity_path = self.entity_path(topic, header.as_ref());
+            self.send_dropped(&entity_path, &stamped(header), dropped);
+        }
+        let started = Instant::now();
+        let result = self.converter.convert_view(msg.view()).await;
+        METRICS.converted(started.elapsed());
+        let convert_data = match result {
+            Ok(convert_data) => convert_data,
+            Err(err) => {
+                METRICS.conversion_error();
+                debug!("Failed to convert message on '{topic}': {err}");
+                return;
+            }
+        };
+        if !convert_data.partial_errors.is_empty() {
+            METRICS.partial_conversion_errors(convert_data.partial_errors.len());
+            warn_once!("Skipped malformed parts of messages on '{topic}'");
+            for err in &convert_data.partial_errors {
+                debug!("Partially converted message on '{topic}': {err:#}");
+            }
+        }
+        let entity_path = match convert_data.entity_path {
+            Some(entity_path) => Arc::new(entity_path),
+            None => self.entity_path(topic, convert_data.header.as_ref()),
+        };
+        if let Some(statics) = &self.frame_statics {
+            for (batches, what) in statics.unsent(&entity_path) {
+                send_static(&self.channel, &entity_path, batches.clone(), what);
+            }
+        }
+        if let Some(static_components) = convert_data.static_components {
+            // Static data is only sent when it changes, so it must not be dropped
+            let static_msg = LogData::AnyComponents(LogComponents {
+                entity_path: entity_path.clone(),
+                header: None,
+                components: static_components,
+                priority: Priority::High,
+                is_static: true,
+            });
+            for tx in &self.channel.tx {
+                if let Err(err) = tx.send(static_msg.clone()) {
+                    error!("Failed to send static data: {err:?}");
+                }
+            }
+        }
+        let header = stamped(convert_data.header);
+        // Clears come first, so descendants logged again by the message are kept.
+        let clears: Vec<_> = match &self.stale {
+            Some(stale) => {
+                let children = convert_data
+                    .batch
+                    .iter()
+                    .map(|entity| &entity.entity_path)
+                    .chain(convert_data.columns.iter().map(|rows| &rows.entity_path))
+                    .map(|path| child_entity_path(&entity_path, path));
+                stale
+                    .update(&entity_path, children)
+                    .into_iter()
+                    .map(|(path, recursive)| LogComponents {
+                        entity_path: path,
+                        header: Some(header.clone()),
+                        components: Arc::new(rerun::Clear::new(recursive)),
+                        priority: self.priority,
+                        is_static: false,
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        let columns = self.log_columns(&entity_path, &header, convert_data.columns);
+        let arch_msg = if convert_data.batch.is_empty() && !columns.is_empty() {
+            // The columns replace the components, leaving only the clears
+            (!clears.is_empty()).then(|| LogData::ArchetypeArray(clears))
+        } else if convert_data.batch.is_empty() {
+            let archetype = LogComponents {
+                entity_path,
+                header: Some(header),
+                components: convert_data.components,
+                priority: self.priority,
+                is_static: false,
+            };
+            Some(if clears.is_empty() {
+                LogData::Archetype(archetype)
+            } else {
+                LogData::ArchetypeArray(clears.into_iter().chain([archetype]).collect())
+            })
+        } else {
+            Some(LogData::ArchetypeArray(
+                clears
+                    .into_iter()
+                    .chain(convert_data.batch.into_iter().map(|entity| LogComponents {
+                        entity_path: child_entity_path(&entity_path, &entity.entity_path),
+                        header: Some(stamped(entity.header)),
+                        components: entity.components,
+                        // Static data is only sent once, so it must not be dropped
+                        priority: if entity.is_static {
+                            Priority::High
+                        } else {
+                            self.priority
+                        },
+                        is_static: entity.is_static,
+                    }))
+                    .collect(),
+            ))
+        };
+        for tx in &self.channel.tx {
+            if let Some(arch_msg) = &arch_msg {
+                if let Err(err) = tx.send(arch_msg.clone()) {
+                    error!("Failed to send archetype data: {err:?}");
+                }
+            }
+            for rows in &columns {
+                if let Err(err) = tx.send(rows.clone()) {
+                    error!("Failed to send columns: {err:?}");
+                }
+            }
+        }
+    }
+
+    /// Entity of a message on `topic` with `header`, the configured entity
+    /// path with the frame of the header filled in if it uses one.
+    fn entity_path(&self, topic: &str, header: Option<&Header>) -> Arc<String> {
+        let frame = header.and_then(|header| header.frame.as_deref());
+        self.frame_template
+            .as_deref()
+            .and_then(|template| expand_entity_path(template, topic, frame))
+            .map_or_else(|| self.entity_path.clone(), Arc::new)
+    }
+
+    /// Place the rows of `columns` below the topic's `entity_path`, at their
+    /// offsets from the stamp of the message `header`.
+    fn log_columns(
+        &self,
+        entity_path: &Arc<String>,
+        header: &Header,
+        columns: Vec<ColumnData>,
+    ) -> Vec<LogData> {
+        let Some(stamp) = header.stamp else {
+            if !columns.is_empty() {
+                warn_once!("Dropping columns for '{entity_path}' without a timestamp");
+            }
+            return Vec::new();
+        };
+        columns
+            .into_iter()
+            .map(|rows| {
+                LogData::Columns(LogColumns {
+                    entity_path: child_entity_path(entity_path, &rows.entity_path),
+                    times: Arc::new(
+                        rows.offsets
+                            .iter()
+                            .map(|offset| stamp.saturating_add(*offset))
+                            .collect(),
+                    ),
+                    columns: Arc::new(rows.columns),
+                    priority: self.priority,
+                })
+            })
+            .collect()
+    }
+
+    /// Log the number of messages lost upstream as a scalar and a warning.
+    fn send_dropped(&self, entity_path: &Arc<String>, header: &Arc<Header>, dropped: u64) {
+        let text = format!("{dropped} messages missing from the sequence");
+        let mut components = rerun::Scalars::new([dropped as f64]).as_serialized_batches();
+        components.extend(
+            rerun::TextLog::new(text)
+                .with_level(rerun::TextLogLevel::WARN)
+                .as_serialized_batches(),
+        );
+        let msg = LogData::AnyComponents(LogComponents {
+            entity_path: child_entity_path(entity_path, DROPPED_ENTITY),
+            header: Some(header.clone()),
+            components: Arc::new(components),
+            priority: self.priority,
+            is_static: false,
+        });
+        for tx in &self.channel.tx {
+            if let Err(err) = tx.send(msg.clone()) {
+                error!("Failed to send dropped messages: {err:?}");
+            }
+        }
+    }
+}
+
+/// Header that data is logged with.
+///
+/// Data without a stamp is logged at the time its message was `received`,
+/// and the `time_offset` in nanoseconds is applied to either.
+fn log_header(header: Option<Header>, received: Option<i64>, time_offset: i64) -> Header {
+    let mut header = header.unwrap_or_default();
+    header.stamp = header
+        .stamp
+        .or(received)
+        .map(|stamp| stamp.saturating_add(time_offset));
+    header
+}
+
+/// Throttle of a topic's messages before conversion, unless it has no
+/// `max_rate_hz` or `decimate` configured.
+fn topic_throttle(source: &TopicSource) -> Option<Throttle> {
+    (source.max_rate_hz.is_some() || source.decimate.is_some())
+        .then(|| Throttle::with_limits(source.max_rate_hz, source.decimate))
+}
+
+/// Entity path of a batch entry, relative to the entity of its topic.
+fn child_entity_path(topic: &Arc<String>, relative: &str) -> Arc<String> {
+    let relative = relative.trim_start_matches('/');
+    if relative.is_empty() {
+        return topic.clone();
+    }
+    Arc::new(format!("{}/{relative}", topic.trim_end_matches('/')))
+}
+
+/// Entity that the start and the shutdown of gRPC streams are logged to.
+pub const STATUS_ENTITY: &str = "_bridge/status";
+
+/// Lifecycle of a gRPC stream, as logged to `STATUS_ENTITY`.
+///
+/// The Rerun SDK connects in the background and reconnects when the
+/// connection drops without reporting it, so the bridge does not know
+/// the state of the connection itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StreamStatus {
+    Started,
+    Stopped,
+}
+
+impl std::fmt::Display for StreamStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Started => write!(f, "started"),
+            Self::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// Rerun application and recording ID of the recordings of the sinks,
+/// see `Config::application_id` and `Config::recording_id`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordingIds {
+    pub application_id: String,
+    /// Random for each recording when unset.
+    pub recording_id: Option<String>,
+}
+
+impl Default for RecordingIds {
+    fn default() -> Self {
+        Self {
+            application_id: DEFAULT_APPLICATION_ID.to_owned(),
+            recording_id: None,
+        }
+    }
+}
+
+impl RecordingIds {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            application_id: config.application_id().to_owned(),
+            recording_id: config.recording_id.clone(),
+        }
+    }
+
+    /// Builder of a recording stream with these IDs.
+    fn builder(&self) -> rerun::RecordingStreamBuilder {
+        let builder = rerun::RecordingStreamBuilder::new(self.application_id.as_str());
+        match &self.recording_id {
+            Some(recording_id) => builder.recording_id(recording_id.as_str()),
+            None => builder,
+        }
+    }
+
+    /// Store ID of a new recording with these IDs.
+    fn store_id(&self) -> rerun::StoreId {
+        let kind = rerun::StoreKind::Recording;
+        match &self.recording_id {
+            Some(recording_id) => {
+                rerun::StoreId::new(kind, self.application_id.as_str(), recording_id.as_str())
+            }
+            None => rerun::StoreId::random(kind, self.application_id.as_str()),
+        }
+    }
 }
 
 pub struct GRPCSinkWorker {
-    address: String,
+    config: Arc<StreamConfig>,
+    inputs: Vec<ComponentID>,
     rec: rerun::RecordingStream,
+    stats: Arc<SinkStats>,
 }
 
 impl GRPCSinkWorker {
     /// Create a worker that sends data to a gRPC Rerun server.
     ///
+    /// `inputs` are the components connected to this sink in the topology.
+    ///
+    /// The Rerun SDK connects to the server in the background and keeps
+    /// reconnecting when the connection drops, buffering the data logged
+    /// meanwhile. A server that is not running yet does not fail the sink.
+    /// With `StreamConfig::spawn`, a local viewer is spawned instead.
+    ///
     /// # Errors
-    /// Returns an error if the connection to the gRPC server cannot be established.
-    pub fn new(config: &StreamConfig) -> anyhow::Result<Self> {
-        let rec = rerun::RecordingStreamBuilder::new("ros_rerun")
-            .connect_grpc_opts(config.url.clone())?;
+    /// Returns `WorkerError::Rerun` if the URL of the gRPC server is invalid.
+    pub fn new(
+        config: &StreamConfig,
+        inputs: Vec<ComponentID>,
+        ids: &RecordingIds,
+    ) -> Result<Self, WorkerError> {
+        if config.spawn {
+            let rec = spawn_viewer(config, ids, |var| std::env::var_os(var))?;
+            return Ok(Self::with_recording(config, inputs, rec));
+        }
+        let rec = ids.builder().connect_grpc_opts(config.url.clone())?;
 
-        Ok(Self {
-            address: config.url.clone(),
+        Ok(Self::with_recording(config, inputs, rec))
+    }
+
+    /// Create a worker logging to an existing recording stream.
+    pub(crate) fn with_recording(
+        config: &StreamConfig,
+        inputs: Vec<ComponentID>,
+        rec: rerun::RecordingStream,
+    ) -> Self {
+        Self {
+            config: Arc::new(config.clone()),
+            inputs,
             rec,
-        })
+            stats: Arc::default(),
+        }
+    }
+
+    /// Counters of the messages logged to the stream and of its flushes.
+    pub fn stats(&self) -> SinkStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    /// Flush the stream, blocking until the data is sent.
+    pub fn flush(&self) {
+        let result = self.rec.flush_blocking();
+        self.stats.flushed(result.is_ok());
+        if let Err(err) = result {
+            error!("Failed to flush gRPC recording stream: {err}");
+        }
+    }
+
+    /// Log the start or the shutdown of the stream to it, if enabled.
+    ///
+    /// The status is logged in-band, so it arrives in order with the data.
+    fn log_status(&self, status: StreamStatus) {
+        if !self.config.log_status {
+            return;
+        }
+        let text = format!("gRPC stream to {} {status}", self.config.url);
+        if let Err(err) = self.rec.log(
+            STATUS_ENTITY,
+            &rerun::TextLog::new(text).with_level(rerun::TextLogLevel::INFO),
+        ) {
+            error!("Failed to log stream status: {err}");
+        }
+    }
+
+    /// Clear everything logged to the stream from the viewer.
+    fn clear(&self) {
+        let result = self.rec.log("/", &rerun::Clear::recursive());
+        self.stats.message_logged(result.is_ok());
+        if let Err(err) = result {
+            error!("Failed to clear gRPC recording stream: {err}");
+        }
+    }
+
+    /// What to do when the sink shuts down, in order.
+    ///
+    /// The clear comes first so it does not remove the final status, and
+    /// everything is logged before the last flush so that it is sent.
+    fn shutdown_steps(&self) -> Vec<ShutdownStep> {
+        let mut steps = Vec::with_capacity(3);
+        if self.config.clear_on_shutdown {
+            steps.push(ShutdownStep::Clear);
+        }
+        if self.config.log_status {
+            steps.push(ShutdownStep::LogStatus);
+        }
+        steps.push(ShutdownStep::Flush);
+        steps
+    }
+
+    /// Components sending data to this sink.
+    pub fn inputs(&self) -> &[ComponentID] {
+        &self.inputs
+    }
+
+    /// Replace the components sending data to this sink, after the topics
+    /// of the topology changed.
+    pub fn set_inputs(&mut self, inputs: Vec<ComponentID>) {
+        self.inputs = inputs;
     }
 
     pub fn run(&self, channel: ArchetypeReceiver, shutdown: Tripwire) {
+        self.log_status(StreamStatus::Started);
         let shared_rec = self.rec.clone();
-        tokio::spawn(run_grpc_sink_worker(shared_rec, channel, shutdown));
+        tokio::spawn(run_grpc_sink_worker(
+            shared_rec,
+            self.config.clone(),
+            self.stats.clone(),
+            channel,
+            shutdown,
+        ));
     }
 }
 
 impl Drop for GRPCSinkWorker {
     fn drop(&mut self) {
-        debug!("Shutting down gRPC sink to {}", self.address);
-        if let Err(err) = self.rec.flush_blocking() {
-            error!("Failed to flush gRPC recording stream: {err}");
+        debug!("Shutting down gRPC sink to {}", self.config.url);
+        for step in self.shutdown_steps() {
+            match step {
+                ShutdownStep::Clear => self.clear(),
+                ShutdownStep::LogStatus => self.log_status(StreamStatus::Stopped),
+                ShutdownStep::Flush => self.flush(),
+            }
         }
     }
 }
 
-fn send_log_comps(rec_stream: &rerun::RecordingStream, data: &LogComponents) {
-    if let Err(err) = rec_stream.log(
-        data.entity_path.as_str(),
-        &data.components.as_serialized_batches(),
-    ) {
+/// Steps of the graceful shutdown of a gRPC sink, see `GRPCSinkWorker::shutdown_steps`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShutdownStep {
+    Clear,
+    LogStatus,
+    Flush,
+}
+
+/// Spawn a local Rerun viewer for the stream, see `StreamConfig::spawn`.
+///
+/// The stream is saved to a file instead when no display is available,
+/// as the viewer would exit right away, or when spawning fails, e.g.
+/// because the `rerun` executable is not installed.
+fn spawn_viewer(
+    config: &StreamConfig,
+    ids: &RecordingIds,
+    env: impl Fn(&str) -> Option<std::ffi::OsString>,
+) -> Result<rerun::RecordingStream, WorkerError> {
+    let reason = if has_display(env) {
+        match ids.builder().spawn() {
+            Ok(rec) => return Ok(rec),
+            Err(err) => format!("failed to spawn a Rerun viewer: {err}"),
+        }
+    } else {
+        "no display is available to spawn a Rerun viewer".to_owned()
+    };
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let file = config
+        .spawn_fallback_dir
+        .clone()
+        .unwrap_or_default()
+        .join(format!("ros_rerun_stream_{secs}.rrd"));
+    warn!("Saving the stream to {} instead, {reason}", file.display());
+    Ok(ids.builder().save(file)?)
+}
+
+/// Whether a viewer window can be shown, from the environment variables
+/// of the display server on Linux.
+fn has_display(env: impl Fn(&str) -> Option<std::ffi::OsString>) -> bool {
+    !cfg!(target_os = "linux")
+        || ["DISPLAY", "WAYLAND_DISPLAY"]
+            .into_iter()
+            .any(|var| env(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// Send components once, as static data of a topic's entity.
+fn send_static(
+    channel: &ArchetypeSender,
+    topic: &Arc<String>,
+    batches: Vec<rerun::SerializedComponentBatch>,
+    what: &str,
+) {
+    let data = LogData::AnyComponents(LogComponents {
+        entity_path: topic.clone(),
+        header: None,
+        components: Arc::new(batches),
+        priority: Priority::High,
+        is_static: true,
+    });
+    for tx in &channel.tx {
+        if let Err(err) = tx.send(data.clone()) {
+            error!("Failed to send {what}: {err:?}");
+        }
+    }
+}
+
+/// Static components naming the converter of a topic's entity, its version,
+/// and the topic and ROS type it converts.
+fn converter_info(
+    converter: &dyn Converter,
+    source: &TopicSource,
+    ros_type: &ROSTypeName,
+) -> Vec<rerun::SerializedComponentBatch> {
+    [
+        ("converter", converter.id().to_owned()),
+        ("version", ros_rerun_types::VERSION.to_owned()),
+        ("topic", source.topic.clone()),
+        ("ros_type", ros_type.to_string()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        [rerun::datatypes::Utf8::from(value)].serialized(rerun::ComponentDescriptor::partial(
+            format!("ros_rerun.ConverterInfo:{name}"),
+        ))
+    })
+    .collect()
+}
+
+/// Static components logged once for a topic's entity, from the viewer hints
+/// in its config.
+///
+/// # Errors
+/// Returns `WorkerError::Hints` if the default color is malformed.
+fn entity_hints(
+    config: &TopicSource,
+    archetype: &RerunName,
+) -> Result<Option<Vec<rerun::SerializedComponentBatch>>, WorkerError> {
+    let mut batches = Vec::new();
+    for (name, hint) in [("visible", config.visible), ("expand", config.expand)] {
+        if let Some(hint) = hint {
+            batches.extend([rerun::datatypes::Bool(hint)].serialized(
+                rerun::ComponentDescriptor::partial(format!("ros_rerun.EntityHints:{name}")),
+            ));
+        }
+    }
+    if let Some(color) = &config.default_color {
+        let color = color.to_color().map_err(WorkerError::Hints)?;
+        match archetype_color(archetype, color) {
+            Some(color) => batches.extend(color),
+            None => warn!(
+                "'default_color' is not supported for {archetype} on topic '{}'",
+                config.topic
+            ),
+        }
+    }
+    Ok((!batches.is_empty()).then_some(batches))
+}
+
+/// The color component of an archetype, for archetypes that have one.
+fn archetype_color(
+    archetype: &RerunName,
+    color: rerun::Color,
+) -> Option<Vec<rerun::SerializedComponentBatch>> {
+    let RerunName::RerunArchetype(name) = archetype else {
+        return None;
+    };
+    let colors = [color];
+    let batches = match name.as_str() {
+        "rerun.archetypes.Arrows2D" => rerun::Arrows2D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.Arrows3D" => rerun::Arrows3D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.Boxes2D" => rerun::Boxes2D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.Boxes3D" => rerun::Boxes3D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.LineStrips2D" => rerun::LineStrips2D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.LineStrips3D" => rerun::LineStrips3D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.Points2D" => rerun::Points2D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.Points3D" => rerun::Points3D::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        // Scalars are colored by the series lines visualizer.
+        "rerun.archetypes.Scalars" => rerun::SeriesLines::update_fields()
+            .with_colors(colors)
+            .as_serialized_batches(),
+        "rerun.archetypes.TextLog" => rerun::TextLog::update_fields()
+            .with_color(color)
+            .as_serialized_batches(),
+        _ => return None,
+    };
+    Some(batches)
+}
+
+/// Serialize a message for MCAP passthrough.
+fn record_raw(
+    raw: &UnboundedSender<RawMessage>,
+    topic: &Arc<String>,
+    schema_name: &Arc<String>,
+    received: Option<i64>,
+    msg: &rclrs::DynamicMessage,
+) {
+    let data = match serialize_cdr(&msg.view()) {
+        Ok(data) => data,
+        Err(err) => {
+            debug!("Failed to serialize message on '{topic}' for MCAP: {err}");
+            return;
+        }
+    };
+    let raw_msg = RawMessage {
+        topic: topic.clone(),
+        schema_name: schema_name.clone(),
+        log_time: received
+            .and_then(|stamp| u64::try_from(stamp).ok())
+            .unwrap_or_default(),
+        data,
+    };
+    if raw.send(raw_msg).is_err() {
+        debug!("MCAP passthrough stopped, dropping message on '{topic}'");
+    }
+}
+
+/// Current time in nanoseconds since the Unix epoch, used for unstamped messages.
+fn receive_stamp() -> Option<i64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|elapsed| i64::try_from(elapsed.as_nanos()).ok())
+}
+
+/// Estimated size of serialized component batches in bytes,
+/// from the memory used by their Arrow arrays.
+fn batches_size(batches: &[rerun::SerializedComponentBatch]) -> usize {
+    batches
+        .iter()
+        .map(|batch| batch.array.get_array_memory_size())
+        .sum()
+}
+
+/// Timeline counting the messages of each entity, see `TimeDedup::Sequence`.
+pub const SEQUENCE_TIMELINE: &str = "ros_sequence";
+
+/// Separates messages with the same timestamp on an entity for a sink,
+/// see `TimeDedupConfig`. Does nothing by default.
+#[derive(Default)]
+struct TimeDeduplicator {
+    mode: Option<TimeDedup>,
+    epsilon: i64,
+    last: HashMap<Arc<String>, LastTime>,
+}
+
+/// The last message logged on an entity.
+#[derive(Clone, Copy)]
+struct LastTime {
+    stamp: i64,
+    /// Time the message was logged at, after nudging.
+    logged: i64,
+    sequence: i64,
+}
+
+impl TimeDeduplicator {
+    fn new(config: &TimeDedupConfig) -> Self {
+        let epsilon = config.dedup_epsilon_ns.unwrap_or(DEFAULT_DEDUP_EPSILON_NS);
+        Self {
+            mode: config.dedup_time,
+            epsilon: i64::try_from(epsilon).unwrap_or(i64::MAX),
+            last: HashMap::new(),
+        }
+    }
+
+    /// Time to log a message stamped `stamp` on `entity_path` at, and its
+    /// index on the `SEQUENCE_TIMELINE` if enabled.
+    fn time(&mut self, entity_path: &Arc<String>, stamp: i64) -> (i64, Option<i64>) {
+        let Some(mode) = self.mode else {
+            return (stamp, None);
+        };
+        let previous = self.last.get(entity_path).copied();
+        let logged = match previous {
+            Some(previous) if mode == TimeDedup::Nudge && previous.stamp == stamp => {
+                previous.logged.saturating_add(self.epsilon)
+            }
+            _ => stamp,
+        };
+        let sequence = previous.map_or(0, |previous| previous.sequence + 1);
+        self.last.insert(
+            entity_path.clone(),
+            LastTime {
+                stamp,
+                logged,
+                sequence,
+            },
+        );
+        (logged, (mode == TimeDedup::Sequence).then_some(sequence))
+    }
+}
+
+/// Log components to a recording stream.
+///
+/// Data larger than `max_bytes` is dropped with an error instead,
+/// see `StreamConfig::max_grpc_message_bytes`.
+fn send_log_comps(
+    rec_stream: &rerun::RecordingStream,
+    stats: &SinkStats,
+    data: &LogComponents,
+    max_bytes: Option<usize>,
+    dedup: &mut TimeDeduplicator,
+) {
+    let batches = data.components.as_serialized_batches();
+    if let Some(max_bytes) = max_bytes {
+        let size = batches_size(&batches);
+        if size > max_bytes {
+            stats.message_logged(false);
+            error_once!(
+                "Dropping data for '{}' larger than max_grpc_message_bytes ({max_bytes} bytes), \
+                 decimate the topic or reduce its size, e.g. with max_points",
+                data.entity_path
+            );
+            debug!("Dropped {size} bytes for '{}'", data.entity_path);
+            return;
+        }
+    }
+    let (time, sequence) = match data.header.as_ref().and_then(|header| header.stamp) {
+        Some(stamp) if !data.is_static => {
+            let (time, sequence) = dedup.time(&data.entity_path, stamp);
+            (Some(time), sequence)
+        }
+        stamp => (stamp, None),
+    };
+    match time {
+        Some(time) => rec_stream.set_time(
+            ROS_TIMELINE,
+            rerun::TimeCell::from_timestamp_nanos_since_epoch(time),
+        ),
+        None => rec_stream.disable_timeline(ROS_TIMELINE),
+    }
+    match sequence {
+        Some(sequence) => rec_stream.set_time_sequence(SEQUENCE_TIMELINE, sequence),
+        None if dedup.mode == Some(TimeDedup::Sequence) => {
+            rec_stream.disable_timeline(SEQUENCE_TIMELINE);
+        }
+        None => {}
+    }
+    let result = if data.is_static {
+        rec_stream.log_static(data.entity_path.as_str(), &batches)
+    } else {
+        rec_stream.log(data.entity_path.as_str(), &batches)
+    };
+    stats.message_logged(result.is_ok());
+    if let Err(err) = result {
         error!("Failed to send log components: {err}");
     }
 }
 
+/// Send rows of components at once with the column API.
+///
+/// Their times are taken as they are, `TimeDedupConfig` only applies to
+/// messages logged one at a time.
+fn send_log_columns(rec_stream: &rerun::RecordingStream, stats: &SinkStats, data: &LogColumns) {
+    let times = rerun::TimeColumn::new_timestamp_nanos_since_epoch(
+        ROS_TIMELINE,
+        data.times.iter().copied(),
+    );
+    let result = rec_stream.send_columns(
+        data.entity_path.as_str(),
+        [times],
+        data.columns.iter().cloned(),
+    );
+    stats.message_logged(result.is_ok());
+    if let Err(err) = result {
+        error!("Failed to send columns: {err}");
+    }
+}
+
 async fn run_grpc_sink_worker(
     rec_stream: rerun::RecordingStream,
+    config: Arc<StreamConfig>,
+    stats: Arc<SinkStats>,
     mut channel: ArchetypeReceiver,
     mut shutdown: Tripwire,
 ) {
+    let in_flight = channel.in_flight.clone();
+    let mut dedup = TimeDeduplicator::new(&config.time_dedup);
+    let mut send = |data: &LogComponents| {
+        if config.routes(&data.entity_path) {
+            send_log_comps(
+                &rec_stream,
+                &stats,
+                data,
+                config.max_grpc_message_bytes,
+                &mut dedup,
+            );
+        }
+    };
+    let mut log = |log_data: LogData| {
+        match log_data {
+            LogData::Archetype(arch) => {
+                send(&arch);
+            }
+            LogData::ArchetypeArray(archs) => {
+                for arch in archs {
+                    send(&arch);
+                }
+            }
+            LogData::AnyComponents(comps) => {
+                send(&comps);
+            }
+            LogData::AnyComponentsArray(comps_arr) => {
+                for comps in comps_arr {
+                    send(&comps);
+                }
+            }
+            LogData::Columns(columns) => {
+                if config.routes(&columns.entity_path) {
+                    send_log_columns(&rec_stream, &stats, &columns);
+                }
+            }
+        }
+        in_flight.release();
+    };
     loop {
         tokio::select! {
-            Some(log_data) = channel.rx.recv() => {
-                match log_data {
-                    LogData::Archetype(arch) => {
-                        send_log_comps(&rec_stream, &arch);
-                    }
-                    LogData::ArchetypeArray(archs) => {
-                        for arch in archs {
-                            send_log_comps(&rec_stream, &arch);
-                        }
-                    },
-                    LogData::AnyComponents(comps) => {
-                        send_log_comps(&rec_stream, &comps);
-                    },
-                    LogData::AnyComponentsArray(comps_arr) => {
-                        for comps in comps_arr {
-                            send_log_comps(&rec_stream, &comps);
-                        }
-                    },
-                }
+            log_data = channel.rx.recv() => {
+                // The inputs closed after the sink was removed from the topology
+                let Some(log_data) = log_data else {
+                    debug!("Inputs of gRPC sink worker closed");
+                    break;
+                };
+                log(log_data);
             }
             _ = &mut shutdown => {
                 debug!("Shutting down gRPC sink worker");
+                // Data queued before the shutdown is still sent, within limits
+                let deadline = Instant::now() + SINK_SHUTDOWN_DRAIN_TIMEOUT;
+                while let Ok(log_data) = channel.rx.try_recv() {
+                    log(log_data);
+                    if Instant::now() >= deadline {
+                        warn!(
+                            "Dropping {} queued message(s) of gRPC sink on shutdown",
+                            channel.in_flight.count()
+                        );
+                        break;
+                    }
+                }
+                let result = rec_stream.flush_blocking();
+                stats.flushed(result.is_ok());
+                if let Err(err) = result {
+                    error!("Failed to flush gRPC recording stream: {err}");
+                }
                 break;
             }
         }
     }
 }
 
+/// Longest time a gRPC sink keeps logging its queued data after the
+/// shutdown was triggered.
+pub const SINK_SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub struct DBSinkWorker {
+    data_dir: PathBuf,
+    ids: RecordingIds,
+    time_dedup: TimeDedupConfig,
+    rotation: Rotation,
+    /// The recording being written, `None` while recording is stopped.
+    recording: Arc<Mutex<Option<Recording>>>,
+    stats: Arc<SinkStats>,
+    stop_tx: UnboundedSender<StopRequest>,
+    /// Taken by the worker task when it runs.
+    stop_rx: Mutex<Option<UnboundedReceiver<StopRequest>>>,
+}
+
+/// Asks the database sink to stop recording, answered with the finished file.
+type StopRequest = oneshot::Sender<Option<PathBuf>>;
+
+/// A `.rrd` file written by the database sink.
+///
+/// A recording is split over several files when it is rotated, named
+/// with the recording ID and, after the first file, an incrementing index.
+/// The files share the recording ID, so the viewer merges them when they
+/// are opened together.
+struct Recording {
     rec: rerun::RecordingStream,
+    file: PathBuf,
+    data_dir: PathBuf,
+    store_id: rerun::StoreId,
+    index: usize,
+    started: Instant,
+    /// Estimated size of the data logged to the file, see `batches_size`.
+    bytes: u64,
+}
+
+impl Recording {
+    /// Start a fresh recording in `data_dir`.
+    fn new(data_dir: &Path, ids: &RecordingIds) -> Result<Self, WorkerError> {
+        Self::with_index(data_dir, ids.store_id(), 0)
+    }
+
+    /// Create the file with the lowest index from `index` that does not
+    /// exist yet, as a configured recording ID is reused by every recording.
+    fn with_index(
+        data_dir: &Path,
+        store_id: rerun::StoreId,
+        index: usize,
+    ) -> Result<Self, WorkerError> {
+        let recording_id = store_id.recording_id();
+        let file_at = |index| match index {
+            0 => data_dir.join(format!("ros_rerun_{}.rrd", recording_id.as_str())),
+            index => data_dir.join(format!("ros_rerun_{}_{index}.rrd", recording_id.as_str())),
+        };
+        let index = (index..)
+            .find(|index| !file_at(*index).exists())
+            .unwrap_or(index);
+        let file = file_at(index);
+        let rec = rerun::RecordingStreamBuilder::new(store_id.application_id().clone())
+            .recording_id(recording_id.clone())
+            .save(file.clone())?;
+        Ok(Self {
+            rec,
+            file,
+            data_dir: data_dir.to_owned(),
+            store_id,
+            index,
+            started: Instant::now(),
+            bytes: 0,
+        })
+    }
+
+    /// Continue the recording in the next file, returning the finished one.
+    fn rotate(&mut self) -> Result<Self, WorkerError> {
+        let next = Self::with_index(&self.data_dir, self.store_id.clone(), self.index + 1)?;
+        Ok(std::mem::replace(self, next))
+    }
+
+    /// Flush the recording to disk, blocking until it is written.
+    fn flush(&self, stats: &SinkStats) {
+        let result = self.rec.flush_blocking();
+        stats.flushed(result.is_ok());
+        if let Err(err) = result {
+            error!("Failed to flush DB recording stream: {err}");
+        }
+    }
 }
 
 impl DBSinkWorker {
     /// Create a worker that saves data to files in a local directory
     ///
+    /// Recording starts right away, see `start` and `stop`. No worker
+    /// is created when the database is disabled in the config.
+    ///
+    /// # Errors
+    /// Returns `WorkerError::Rerun` if the recording stream cannot be created.
+    pub fn new(config: &DBConfig, ids: &RecordingIds) -> Result<Self, WorkerError> {
+        let recording = Recording::new(&config.data_dir, ids)?;
+        let (stop_tx, stop_rx) = unbounded_channel();
+
+        Ok(Self {
+            data_dir: config.data_dir.clone(),
+            ids: ids.clone(),
+            time_dedup: config.time_dedup,
+            rotation: Rotation::new(config),
+            recording: Arc::new(Mutex::new(Some(recording))),
+            stats: Arc::default(),
+            stop_tx,
+            stop_rx: Mutex::new(Some(stop_rx)),
+        })
+    }
+
+    /// The file being recorded to, if recording.
+    pub fn recording_file(&self) -> Option<PathBuf> {
+        self.recording
+            .lock()
+            .as_ref()
+            .map(|recording| recording.file.clone())
+    }
+
+    /// Start recording to a fresh `.rrd` file, returning its path.
+    ///
+    /// Does nothing when already recording, and returns the current file.
+    ///
     /// # Errors
-    /// Returns an error if the recording stream cannot be created.
-    pub fn new(config: &DBConfig) -> anyhow::Result<Self> {
-        let store_id = rerun::StoreId::random(rerun::StoreKind::Recording, "ros_rerun");
-        let file_name = format!("{}_{}.rrd", "ros_rerun", store_id.recording_id().as_str());
-        let recording_file = config.data_dir.clone().join(file_name);
-        let rec = rerun::RecordingStreamBuilder::new("ros_rerun")
-            .recording_id(store_id.recording_id().clone())
-            .save(recording_file.clone())?;
+    /// Returns `WorkerError::Rerun` if the recording stream cannot be created.
+    pub fn start(&self) -> Result<PathBuf, WorkerError> {
+        let mut recording = self.recording.lock();
+        if let Some(recording) = recording.as_ref() {
+            return Ok(recording.file.clone());
+        }
+        let started = Recording::new(&self.data_dir, &self.ids)?;
+        debug!("Started DB recording to {}", started.file.display());
+        let file = started.file.clone();
+        *recording = Some(started);
+        Ok(file)
+    }
+
+    /// Stop recording, returning the path of the finished file.
+    ///
+    /// The messages already queued for the database are written and
+    /// flushed first. Messages received while stopped are discarded.
+    /// Returns `None` if not recording.
+    pub async fn stop(&self) -> Option<PathBuf> {
+        let (done_tx, done_rx) = oneshot::channel();
+        let running = self.stop_rx.lock().is_none() && self.stop_tx.send(done_tx).is_ok();
+        if running {
+            if let Ok(file) = done_rx.await {
+                return file;
+            }
+        }
+        // The worker is not running, so no messages are queued for it.
+        let recording = self.recording.lock().take()?;
+        recording.flush(&self.stats);
+        Some(recording.file)
+    }
 
-        Ok(Self { rec })
+    /// Counters of the messages logged to the database and of its flushes,
+    /// with the size of the recording file.
+    pub fn stats(&self) -> SinkStatsSnapshot {
+        SinkStatsSnapshot {
+            bytes_written: self
+                .recording_file()
+                .and_then(|file| std::fs::metadata(file).ok())
+                .map(|metadata| metadata.len()),
+            ..self.stats.snapshot()
+        }
     }
 
+    /// Flush the recording to disk, blocking until it is written.
+    pub fn flush(&self) {
+        if let Some(recording) = self.recording.lock().as_ref() {
+            recording.flush(&self.stats);
+        }
+    }
+
+    /// Run the worker, which can only be started once.
     pub fn run(&self, channel: ArchetypeReceiver, shutdown: Tripwire) {
-        let shared_rec = self.rec.clone();
-        tokio::spawn(run_db_sink_worker(shared_rec, channel, shutdown));
+        let Some(stop_rx) = self.stop_rx.lock().take() else {
+            error!("DB sink worker is already running");
+            return;
+        };
+        tokio::spawn(run_db_sink_worker(
+            self.recording.clone(),
+            self.rotation,
+            TimeDeduplicator::new(&self.time_dedup),
+            self.stats.clone(),
+            channel,
+            stop_rx,
+            shutdown,
+        ));
+    }
+}
+
+impl Drop for DBSinkWorker {
+    fn drop(&mut self) {
+        if let Some(file) = self.recording_file() {
+            debug!("Shutting down DB sink to {}", file.display());
+        }
+        self.flush();
+    }
+}
+
+/// When the database sink continues recording in a new file.
+#[derive(Clone, Copy, Default)]
+struct Rotation {
+    max_bytes: Option<u64>,
+    max_duration: Option<Duration>,
+}
+
+impl Rotation {
+    fn new(config: &DBConfig) -> Self {
+        Self {
+            max_bytes: config
+                .max_file_size_mb
+                .map(|size| size.saturating_mul(1_000_000)),
+            max_duration: config.max_duration_secs.map(Duration::from_secs),
+        }
+    }
+
+    /// Whether `recording` is rotated before logging `size` more bytes.
+    /// A file always gets some data, even when it is larger than the limit.
+    fn is_due(&self, recording: &Recording, size: u64) -> bool {
+        let full = self
+            .max_bytes
+            .is_some_and(|max_bytes| recording.bytes + size > max_bytes);
+        let expired = self
+            .max_duration
+            .is_some_and(|max_duration| recording.started.elapsed() >= max_duration);
+        recording.bytes > 0 && (full || expired)
+    }
+
+    /// Continue `recording` in a new file if it is due before logging
+    /// `size` more bytes, returning the finished file.
+    ///
+    /// When the new file cannot be created, the current file is kept
+    /// and rotating is only retried once it reaches a limit again.
+    fn apply(&self, recording: &mut Recording, size: u64) -> Option<Recording> {
+        if !self.is_due(recording, size) {
+            return None;
+        }
+        match recording.rotate() {
+            Ok(finished) => Some(finished),
+            Err(err) => {
+                error!(
+                    "Failed to continue DB recording in a new file, keeping {}: {err}",
+                    recording.file.display()
+                );
+                recording.started = Instant::now();
+                recording.bytes = 0;
+                None
+            }
+        }
+    }
+}
+
+/// Estimated size of the data in bytes, see `batches_size`.
+fn log_data_size(log_data: &LogData) -> usize {
+    let comps_size =
+        |comps: &LogComponents| batches_size(&comps.components.as_serialized_batches());
+    match log_data {
+        LogData::Archetype(comps) | LogData::AnyComponents(comps) => comps_size(comps),
+        LogData::ArchetypeArray(comps) | LogData::AnyComponentsArray(comps) => {
+            comps.iter().map(comps_size).sum()
+        }
+        LogData::Columns(columns) => columns
+            .columns
+            .iter()
+            .map(|column| column.list_array.get_array_memory_size())
+            .sum(),
     }
 }
 
 async fn run_db_sink_worker(
-    rec_stream: rerun::RecordingStream,
+    recording: Arc<Mutex<Option<Recording>>>,
+    rotation: Rotation,
+    mut dedup: TimeDeduplicator,
+    stats: Arc<SinkStats>,
     mut channel: ArchetypeReceiver,
+    mut stop_rx: UnboundedReceiver<StopRequest>,
     mut shutdown: Tripwire,
 ) {
+    let in_flight = channel.in_flight.clone();
+    let mut log = |log_data: LogData| {
+        let size = if rotation.max_bytes.is_some() {
+            log_data_size(&log_data) as u64
+        } else {
+            0
+        };
+        // Cloning the stream lets `start` swap the recording while logging.
+        let (rec_stream, finished) = match recording.lock().as_mut() {
+            Some(current) => {
+                let finished = rotation.apply(current, size);
+                current.bytes += size;
+                (Some(current.rec.clone()), finished)
+            }
+            None => (None, None),
+        };
+        if let Some(finished) = finished {
+            finished.flush(&stats);
+            debug!("Continuing DB recording after {}", finished.file.display());
+        }
+        if let Some(rec_stream) = rec_stream {
+            match log_data {
+                LogData::Archetype(arch) => {
+                    send_log_comps(&rec_stream, &stats, &arch, None, &mut dedup);
+                }
+                LogData::ArchetypeArray(archs) => {
+                    for arch in archs {
+                        send_log_comps(&rec_stream, &stats, &arch, None, &mut dedup);
+                    }
+                }
+                LogData::AnyComponents(comps) => {
+                    send_log_comps(&rec_stream, &stats, &comps, None, &mut dedup);
+                }
+                LogData::AnyComponentsArray(comps) => {
+                    for comps in comps {
+                        send_log_comps(&rec_stream, &stats, &comps, None, &mut dedup);
+                    }
+                }
+                LogData::Columns(columns) => {
+                    send_log_columns(&rec_stream, &stats, &columns);
+                }
+            }
+        }
+        in_flight.release();
+    };
     loop {
         tokio::select! {
-            Some(log_data) = channel.rx.recv() => {
-                match log_data {
-                    LogData::Archetype(arch) => {
-                        send_log_comps(&rec_stream, &arch);
-                    }
-                    LogData::ArchetypeArray(archs) => {
-                        for arch in archs {
-                            send_log_comps(&rec_stream, &arch);
-                        }
-                    },
-                    LogData::AnyComponents(comps) => {
-                        send_log_comps(&rec_stream, &comps);
-                    },
-                    LogData::AnyComponentsArray(comps) => {
-                        for comps in comps {
-                            send_log_comps(&rec_stream, &comps);
-                        }
-                    },
+            log_data = channel.rx.recv() => {
+                let Some(log_data) = log_data else {
+                    debug!("Inputs of DB sink worker closed");
+                    break;
+                };
+                log(log_data);
+            }
+            Some(done_tx) = stop_rx.recv() => {
+                while let Ok(log_data) = channel.rx.try_recv() {
+                    log(log_data);
                 }
+                let stopped = recording.lock().take();
+                let file = stopped.map(|recording| {
+                    recording.flush(&stats);
+                    debug!("Stopped DB recording to {}", recording.file.display());
+                    recording.file
+                });
+                let _ = done_tx.send(file);
             }
             _ = &mut shutdown => {
                 debug!("Shutting down DB sink worker");
@@ -232,3 +1750,857 @@ async fn run_db_sink_worker(
         }
     }
 }
+
+/// Decides which messages a transform or topic forwards.
+///
+/// State is tracked per entity path, so each input topic
+/// is rate limited and decimated independently.
+pub struct Throttle {
+    min_period: Option<Duration>,
+    decimate: usize,
+    state: HashMap<Arc<String>, ThrottleState>,
+}
+
+#[derive(Default)]
+struct ThrottleState {
+    seen: usize,
+    last_sent: Option<Instant>,
+}
+
+impl Throttle {
+    pub fn new(config: &TransformConfig) -> Self {
+        Self::with_limits(config.rate, config.decimate)
+    }
+
+    /// Forward at most `rate` messages per second and every `decimate`-th
+    /// message, either unlimited when unset.
+    pub fn with_limits(rate: Option<f64>, decimate: Option<usize>) -> Self {
+        Self {
+            min_period: rate.and_then(|rate| Duration::try_from_secs_f64(rate.recip()).ok()),
+            decimate: decimate.unwrap_or(1).max(1),
+            state: HashMap::new(),
+        }
+    }
+
+    /// Whether a message on `entity_path` received at `now` should be forwarded.
+    pub fn admit(&mut self, entity_path: &Arc<String>, now: Instant) -> bool {
+        let state = self.state.entry(entity_path.clone()).or_default();
+        let index = state.seen;
+        state.seen += 1;
+        if index % self.decimate != 0 {
+            return false;
+        }
+        if let (Some(period), Some(last_sent)) = (self.min_period, state.last_sent) {
+            if now.saturating_duration_since(last_sent) < period {
+                return false;
+            }
+        }
+        state.last_sent = Some(now);
+        true
+    }
+}
+
+pub struct TransformWorker {
+    config: TransformConfig,
+}
+
+impl TransformWorker {
+    /// Create a worker that forwards a throttled subset of its inputs.
+    pub fn new(config: &TransformConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    pub fn run(&self, channel: ArchetypeReceiver, output: ArchetypeSender, shutdown: Tripwire) {
+        tokio::spawn(run_transform_worker(
+            Throttle::new(&self.config),
+            channel,
+            output,
+            shutdown,
+        ));
+    }
+}
+
+async fn run_transform_worker(
+    mut throttle: Throttle,
+    mut channel: ArchetypeReceiver,
+    output: ArchetypeSender,
+    mut shutdown: Tripwire,
+) {
+    let in_flight = channel.in_flight.clone();
+    loop {
+        tokio::select! {
+            log_data = channel.rx.recv() => {
+                let Some(log_data) = log_data else {
+                    debug!("Inputs of transform worker closed");
+                    break;
+                };
+                // Static data is only logged once, so it is never throttled.
+                let forward = log_data.is_static()
+                    || log_data
+                        .entity_path()
+                        .is_none_or(|path| throttle.admit(path, Instant::now()));
+                if forward {
+                    for tx in &output.tx {
+                        if let Err(err) = tx.send(log_data.clone()) {
+                            error!("Failed to forward archetype data: {err:?}");
+                        }
+                    }
+                }
+                in_flight.release();
+            }
+            _ = &mut shutdown => {
+                debug!("Shutting down transform worker");
+                break;
+            }
+        }
+    }
+}
+
+/// Entity that the metrics of the bridge itself are logged under,
+/// see `Config::self_metrics`.
+pub const METRICS_ENTITY: &str = "_bridge/metrics";
+
+/// Logs the global metrics and the queue depth of every sink to the sinks,
+/// so the bridge can be observed in the viewer it feeds.
+pub struct SelfMetricsWorker {
+    interval: Duration,
+    sinks: Vec<(String, SinkSender)>,
+}
+
+impl SelfMetricsWorker {
+    /// Create a worker logging every `interval` to the named `sinks`.
+    pub fn new(interval: Duration, sinks: Vec<(String, SinkSender)>) -> Self {
+        Self { interval, sinks }
+    }
+
+    pub fn run(&self, shutdown: Tripwire) {
+        tokio::spawn(run_self_metrics_worker(
+            self.interval,
+            self.sinks.clone(),
+            shutdown,
+        ));
+    }
+}
+
+/// Values of the self metrics, keyed by their entity path below `METRICS_ENTITY`.
+fn self_metrics(rates: &MetricsRates, sinks: &[(String, SinkSender)]) -> Vec<(String, f64)> {
+    let mut metrics = vec![
+        ("messages_per_sec".to_owned(), rates.messages_per_sec),
+        ("dropped_per_sec".to_owned(), rates.dropped_per_sec),
+    ];
+    if let Some(latency) = rates.conversion_latency {
+        metrics.push((
+            "conversion_latency_ms".to_owned(),
+            latency.as_secs_f64() * 1000.0,
+        ));
+    }
+    metrics.extend(
+        sinks
+            .iter()
+            .map(|(name, sink)| (format!("queue_depth/{name}"), sink.in_flight.count() as f64)),
+    );
+    metrics
+}
+
+async fn run_self_metrics_worker(
+    interval: Duration,
+    sinks: Vec<(String, SinkSender)>,
+    mut shutdown: Tripwire,
+) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut last = (METRICS.snapshot(), Instant::now());
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let (snapshot, now) = (METRICS.snapshot(), Instant::now());
+                let rates = snapshot.rates_since(&last.0, now.duration_since(last.1));
+                last = (snapshot, now);
+                let header = Arc::new(Header {
+                    stamp: receive_stamp(),
+                    frame: None,
+                });
+                let metrics = self_metrics(&rates, &sinks)
+                    .into_iter()
+                    .map(|(path, value)| LogComponents {
+                        entity_path: Arc::new(format!("{METRICS_ENTITY}/{path}")),
+                        header: Some(header.clone()),
+                        components: Arc::new(rerun::Scalars::single(value)),
+                        priority: Priority::High,
+                        is_static: false,
+                    })
+                    .collect();
+                let data = LogData::AnyComponentsArray(metrics);
+                for (name, sink) in &sinks {
+                    if let Err(err) = sink.send(data.clone()) {
+                        error!("Failed to send self metrics to sink {name}: {err:?}");
+                    }
+                }
+            }
+            _ = &mut shutdown => {
+                debug!("Shutting down self metrics worker");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ros_rerun_types::color::ColorSetting;
+
+    use super::*;
+
+    #[test]
+    fn reports_incompatible_qos_policy() {
+        let sensor_data = rclrs::QOS_PROFILE_SENSOR_DATA;
+        let reliable = rclrs::QOS_PROFILE_DEFAULT;
+        assert_eq!(
+            incompatible_qos_policy(&sensor_data, &reliable),
+            Some((
+                "reliability",
+                "best effort".to_owned(),
+                "reliable".to_owned()
+            ))
+        );
+        assert_eq!(
+            incompatible_qos_policy(&reliable, &sensor_data),
+            None,
+            "best effort subscriptions accept reliable publishers"
+        );
+
+        let latched = rclrs::QoSProfile {
+            durability: rclrs::QoSDurabilityPolicy::TransientLocal,
+            ..reliable
+        };
+        let volatile = rclrs::QoSProfile {
+            durability: rclrs::QoSDurabilityPolicy::Volatile,
+            ..reliable
+        };
+        assert_eq!(
+            incompatible_qos_policy(&volatile, &latched).map(|(policy, ..)| policy),
+            Some("durability")
+        );
+        assert_eq!(incompatible_qos_policy(&latched, &volatile), None);
+
+        let deadline = |deadline| rclrs::QoSProfile {
+            deadline: rclrs::QoSDuration::Custom(deadline),
+            ..reliable
+        };
+        let fast = deadline(Duration::from_millis(100));
+        let slow = deadline(Duration::from_secs(1));
+        assert_eq!(
+            incompatible_qos_policy(&slow, &fast),
+            Some(("deadline", "1s".to_owned(), "100ms".to_owned()))
+        );
+        assert_eq!(incompatible_qos_policy(&fast, &slow), None);
+    }
+
+    #[test]
+    fn time_offset_shifts_stamps() {
+        let source = TopicSource {
+            time_offset: Some(-0.25),
+            ..Default::default()
+        };
+        let offset = source.time_offset_nanos();
+        assert_eq!(offset, -250_000_000);
+        let stamped = Header {
+            stamp: Some(1_000_000_000),
+            frame: Some("lidar".to_owned()),
+        };
+        let header = log_header(Some(stamped), Some(5_000_000_000), offset);
+        assert_eq!(header.stamp, Some(750_000_000), "message stamp wins");
+        assert_eq!(header.frame.as_deref(), Some("lidar"));
+        let header = log_header(None, Some(5_000_000_000), offset);
+        assert_eq!(header.stamp, Some(4_750_000_000), "offset receive time");
+        assert_eq!(log_header(None, None, offset).stamp, None);
+        assert_eq!(log_header(None, Some(1), 0).stamp, Some(1));
+    }
+
+    #[test]
+    fn clears_stale_children() {
+        let paths = |paths: &[&str]| {
+            paths
+                .iter()
+                .map(|path| Arc::new((*path).to_owned()))
+                .collect::<Vec<_>>()
+        };
+        let stale = |recursive, first: &[&str], next: &[&str]| {
+            let children = StaleChildren {
+                recursive,
+                ..Default::default()
+            };
+            children.update("/tf", paths(first).into_iter());
+            children
+                .update("/tf", paths(next).into_iter())
+                .into_iter()
+                .map(|(path, recursive)| ((*path).clone(), recursive))
+                .collect::<Vec<_>>()
+        };
+        let first = ["/tf", "/tf/map", "/tf/map/odom", "/tf/map/odom/base"];
+        assert_eq!(
+            stale(None, &first, &["/tf/map/odom/base"]),
+            [
+                ("/tf/map".to_owned(), true),
+                ("/tf/map/odom".to_owned(), true)
+            ],
+            "recursive with children, the topic itself is never stale"
+        );
+        assert_eq!(
+            stale(None, &first, &["/tf/map"]),
+            [
+                ("/tf/map/odom".to_owned(), true),
+                ("/tf/map/odom/base".to_owned(), false)
+            ]
+        );
+        assert_eq!(
+            stale(Some(false), &first, &[]),
+            [
+                ("/tf/map".to_owned(), false),
+                ("/tf/map/odom".to_owned(), false),
+                ("/tf/map/odom/base".to_owned(), false)
+            ],
+            "flat"
+        );
+        assert_eq!(
+            stale(Some(true), &["/tf/a", "/tf/b"], &["/tf/b"]),
+            [("/tf/a".to_owned(), true)],
+            "recursive"
+        );
+    }
+
+    #[test]
+    fn counts_sequence_gaps() {
+        let gaps = SequenceGaps::new("header.seq".to_owned());
+        assert_eq!(gaps.update(7), None, "first message");
+        assert_eq!(gaps.update(8), None);
+        assert_eq!(gaps.update(11), Some(2));
+        assert_eq!(gaps.update(11), None, "repeated");
+        assert_eq!(gaps.update(0), None, "publisher restarted");
+        assert_eq!(gaps.update(2), Some(1));
+        assert_eq!(gaps.update(i64::MIN), None);
+        assert_eq!(gaps.update(i64::MAX), None, "overflowing gap");
+    }
+
+    #[tokio::test]
+    async fn reports_gap_when_conversion_fails() {
+        use ros_rerun_types::{
+            converters::range::SensorRangeToArrows3D,
+            definitions::{sensor_msgs::Temperature, ROSMessage as _},
+        };
+
+        let (tx, mut rx) = unbounded_channel();
+        let output = SubscriptionOutput {
+            // A temperature cannot be read as a range
+            converter: Box::new(SensorRangeToArrows3D::default()),
+            channel: ArchetypeSender {
+                tx: vec![SinkSender {
+                    tx,
+                    in_flight: crate::channel::InFlight::new("db", 10),
+                }],
+            },
+            priority: Priority::High,
+            time_offset: 0,
+            stale: None,
+            gaps: None,
+            entity_path: Arc::new("/temperature".to_owned()),
+            frame_template: None,
+            frame_statics: None,
+            throttle: None,
+        };
+        let ros_type =
+            ROSTypeName::try_from(Temperature::ros_type()).expect("sensor_msgs is available");
+        let msg = rclrs::DynamicMessage::new(ros_type.into()).expect("valid message type");
+        let topic = Arc::new("/temperature".to_owned());
+        output.forward(&topic, &msg, Some(1), Some(2)).await;
+        let dropped = rx.try_recv().expect("gap is reported");
+        assert_eq!(
+            dropped.entity_path().map(|path| path.as_str()),
+            Some("/temperature/dropped")
+        );
+        assert!(rx.try_recv().is_err(), "nothing is converted");
+    }
+
+    #[test]
+    fn batch_entities_below_topic() {
+        let topic = Arc::new("/tf".to_owned());
+        assert_eq!(*child_entity_path(&topic, "map/odom"), "/tf/map/odom");
+        assert!(Arc::ptr_eq(&child_entity_path(&topic, ""), &topic));
+        let root = Arc::new("/".to_owned());
+        assert_eq!(*child_entity_path(&root, "/map"), "/map");
+    }
+
+    #[test]
+    fn sink_stats_count_logs_and_flushes() {
+        let worker = GRPCSinkWorker::with_recording(
+            &StreamConfig::default(),
+            Vec::new(),
+            rerun::RecordingStream::disabled(),
+        );
+        let data = LogComponents {
+            entity_path: Arc::new("/stats".to_owned()),
+            header: None,
+            components: Arc::new(rerun::Scalars::single(1.0)),
+            priority: Priority::default(),
+            is_static: false,
+        };
+        assert_eq!(worker.stats(), SinkStatsSnapshot::default());
+
+        let mut dedup = TimeDeduplicator::default();
+        send_log_comps(&worker.rec, &worker.stats, &data, None, &mut dedup);
+        send_log_comps(&worker.rec, &worker.stats, &data, None, &mut dedup);
+        worker.flush();
+
+        let stats = worker.stats();
+        assert_eq!(stats.messages_logged, 2);
+        assert_eq!(stats.log_errors, 0);
+        assert_eq!(stats.flush_errors, 0);
+        assert!(stats.last_flush.is_some());
+    }
+
+    #[test]
+    fn drops_oversized_batches() {
+        let stats = SinkStats::default();
+        let rec = rerun::RecordingStream::disabled();
+        let data = LogComponents {
+            entity_path: Arc::new("/cloud".to_owned()),
+            header: None,
+            components: Arc::new(rerun::Points3D::new(vec![[0.0_f32; 3]; 1000])),
+            priority: Priority::default(),
+            is_static: false,
+        };
+        assert!(batches_size(&data.components.as_serialized_batches()) > 1024);
+
+        let mut dedup = TimeDeduplicator::default();
+        send_log_comps(&rec, &stats, &data, Some(1024), &mut dedup);
+        assert_eq!(stats.snapshot().log_errors, 1, "over the limit");
+        send_log_comps(&rec, &stats, &data, Some(1 << 20), &mut dedup);
+        send_log_comps(&rec, &stats, &data, None, &mut dedup);
+        assert_eq!(stats.snapshot().messages_logged, 2);
+    }
+
+    #[test]
+    fn separates_duplicate_stamps() {
+        let entity = Arc::new("/imu".to_owned());
+        let other = Arc::new("/gps".to_owned());
+        let mut nudge = TimeDeduplicator::new(&TimeDedupConfig {
+            dedup_time: Some(TimeDedup::Nudge),
+            dedup_epsilon_ns: Some(10),
+        });
+        assert_eq!(nudge.time(&entity, 100), (100, None));
+        assert_eq!(nudge.time(&entity, 100), (110, None), "both are retained");
+        assert_eq!(nudge.time(&entity, 100), (120, None));
+        assert_eq!(nudge.time(&other, 100), (100, None), "per entity");
+        assert_eq!(nudge.time(&entity, 200), (200, None));
+
+        let mut sequence = TimeDeduplicator::new(&TimeDedupConfig {
+            dedup_time: Some(TimeDedup::Sequence),
+            ..Default::default()
+        });
+        assert_eq!(sequence.time(&entity, 100), (100, Some(0)));
+        assert_eq!(sequence.time(&entity, 100), (100, Some(1)));
+        assert_eq!(
+            TimeDeduplicator::default().time(&entity, 100),
+            (100, None),
+            "off by default"
+        );
+    }
+
+    #[test]
+    fn self_metrics_include_queue_depths() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let sink = SinkSender {
+            tx,
+            in_flight: crate::channel::InFlight::new("db", 10),
+        };
+        let data = LogData::AnyComponents(LogComponents {
+            entity_path: Arc::new("/queued".to_owned()),
+            header: None,
+            components: Arc::new(rerun::Scalars::single(1.0)),
+            priority: Priority::default(),
+            is_static: false,
+        });
+        assert!(sink.send(data).expect("receiver is alive"));
+        let rates = MetricsRates {
+            messages_per_sec: 5.0,
+            ..Default::default()
+        };
+
+        let metrics = self_metrics(&rates, &[("db".to_owned(), sink)]);
+        assert_eq!(
+            metrics,
+            [
+                ("messages_per_sec".to_owned(), 5.0),
+                ("dropped_per_sec".to_owned(), 0.0),
+                ("queue_depth/db".to_owned(), 1.0),
+            ],
+            "no latency without conversions"
+        );
+    }
+
+    #[tokio::test]
+    async fn grpc_sink_routes_component_arrays() {
+        let config = Arc::new(StreamConfig {
+            route: vec!["/_bridge/**".into()],
+            ..Default::default()
+        });
+        let stats = Arc::new(SinkStats::default());
+        let (tx, rx) = unbounded_channel();
+        let in_flight = crate::channel::InFlight::new("viewer", 10);
+        let sink = SinkSender {
+            tx,
+            in_flight: in_flight.clone(),
+        };
+        let (trigger, shutdown) = Tripwire::new();
+        let worker = tokio::spawn(run_grpc_sink_worker(
+            rerun::RecordingStream::disabled(),
+            config,
+            stats.clone(),
+            ArchetypeReceiver { rx, in_flight },
+            shutdown,
+        ));
+
+        let scalar = |path: &str| LogComponents {
+            entity_path: Arc::new(path.to_owned()),
+            header: None,
+            components: Arc::new(rerun::Scalars::single(1.0)),
+            priority: Priority::High,
+            is_static: false,
+        };
+        let data = LogData::AnyComponentsArray(vec![
+            scalar("/_bridge/metrics/messages_per_sec"),
+            scalar("/camera/image"),
+            scalar("/_bridge/metrics/dropped_per_sec"),
+        ]);
+        assert!(sink.send(data).expect("worker is running"));
+        while sink.in_flight.count() > 0 {
+            tokio::task::yield_now().await;
+        }
+        drop(trigger);
+        worker.await.expect("worker shuts down");
+        assert_eq!(
+            stats.snapshot().messages_logged,
+            2,
+            "each routed entity of the array is logged"
+        );
+    }
+
+    #[tokio::test]
+    async fn grpc_sink_drains_queue_on_shutdown() {
+        let stats = Arc::new(SinkStats::default());
+        let (tx, rx) = unbounded_channel();
+        let in_flight = crate::channel::InFlight::new("viewer", 100);
+        let sink = SinkSender {
+            tx,
+            in_flight: in_flight.clone(),
+        };
+        let (trigger, shutdown) = Tripwire::new();
+        let worker = tokio::spawn(run_grpc_sink_worker(
+            rerun::RecordingStream::disabled(),
+            Arc::new(StreamConfig::default()),
+            stats.clone(),
+            ArchetypeReceiver { rx, in_flight },
+            shutdown,
+        ));
+
+        const MESSAGES: usize = 20;
+        for i in 0..MESSAGES {
+            let data = LogData::AnyComponents(LogComponents {
+                entity_path: Arc::new(format!("/imu/{i}")),
+                header: None,
+                components: Arc::new(rerun::Scalars::single(i as f64)),
+                priority: Priority::High,
+                is_static: false,
+            });
+            assert!(sink.send(data).expect("worker is running"));
+        }
+        // Shut down before the worker got to run at all
+        drop(trigger);
+        worker.await.expect("worker shuts down");
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.messages_logged, MESSAGES as u64, "nothing is lost");
+        assert_eq!(sink.in_flight.count(), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn spawn_saves_to_file_without_display() {
+        assert!(has_display(
+            |var| (var == "WAYLAND_DISPLAY").then(|| "wayland-0".into())
+        ));
+        assert!(!has_display(|_| Some("".into())));
+
+        let dir = std::env::temp_dir().join("ros_rerun_spawn_fallback");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = StreamConfig {
+            spawn: true,
+            spawn_fallback_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        let rec = spawn_viewer(&config, &RecordingIds::default(), |_| None).unwrap();
+        rec.flush_blocking().unwrap();
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1, "saved to a file instead");
+    }
+
+    #[test]
+    fn grpc_sink_rejects_invalid_url() {
+        let config = StreamConfig {
+            url: "not a rerun url".into(),
+            ..Default::default()
+        };
+        assert!(GRPCSinkWorker::new(&config, Vec::new(), &RecordingIds::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn db_sink_keeps_files_of_fixed_recording_id() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_db_recording_id");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let config = DBConfig {
+            data_dir: data_dir.clone(),
+            ..Default::default()
+        };
+        let ids = RecordingIds {
+            application_id: "robot_a".to_owned(),
+            recording_id: Some("run_1".to_owned()),
+        };
+        let worker = DBSinkWorker::new(&config, &ids).unwrap();
+        let first = worker.stop().await.expect("was recording");
+        assert_eq!(first, data_dir.join("ros_rerun_run_1.rrd"));
+        let next = worker.start().unwrap();
+        assert_eq!(
+            next,
+            data_dir.join("ros_rerun_run_1_1.rrd"),
+            "does not overwrite"
+        );
+        assert!(first.is_file());
+    }
+
+    #[tokio::test]
+    async fn db_sink_rotates_files_by_size() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_db_rotation");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let config = DBConfig {
+            data_dir: data_dir.clone(),
+            ..Default::default()
+        };
+        let mut worker = DBSinkWorker::new(&config, &RecordingIds::default()).unwrap();
+        // Every message exceeds the limit, so each gets its own file
+        worker.rotation = Rotation {
+            max_bytes: Some(1),
+            max_duration: None,
+        };
+        let (tx, rx) = unbounded_channel();
+        let in_flight = crate::channel::InFlight::new("db", 100);
+        let sink = SinkSender {
+            tx,
+            in_flight: in_flight.clone(),
+        };
+        let (_trigger, shutdown) = Tripwire::new();
+        worker.run(ArchetypeReceiver { rx, in_flight }, shutdown);
+
+        for i in 0..3 {
+            let data = LogData::AnyComponents(LogComponents {
+                entity_path: Arc::new("/imu".to_owned()),
+                header: None,
+                components: Arc::new(rerun::Scalars::single(f64::from(i))),
+                priority: Priority::High,
+                is_static: false,
+            });
+            assert!(sink.send(data).expect("worker is running"));
+        }
+        let last = worker.stop().await.expect("was recording");
+        assert!(
+            last.to_string_lossy().ends_with("_2.rrd"),
+            "{}",
+            last.display()
+        );
+        let files = std::fs::read_dir(&data_dir)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .is_ok_and(|entry| entry.path().extension().is_some_and(|ext| ext == "rrd"))
+            })
+            .count();
+        assert_eq!(files, 3);
+        assert_eq!(worker.stats().messages_logged, 3);
+    }
+
+    #[test]
+    fn failed_rotation_waits_for_next_limit() {
+        let data_dir = std::env::temp_dir().join("ros_rerun_db_failed_rotation");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mut recording = Recording::new(&data_dir, &RecordingIds::default()).unwrap();
+        let rotation = Rotation {
+            max_bytes: Some(10),
+            max_duration: None,
+        };
+        recording.bytes = 8;
+        // The next file cannot be created
+        std::fs::remove_dir_all(&data_dir).unwrap();
+        assert!(rotation.apply(&mut recording, 5).is_none());
+        assert_eq!(recording.bytes, 0, "keeps logging to the current file");
+        recording.bytes = 5;
+        assert!(
+            !rotation.is_due(&recording, 5),
+            "not retried before the limit is reached again"
+        );
+    }
+
+    #[test]
+    fn clears_before_final_flush() {
+        let config = StreamConfig {
+            clear_on_shutdown: true,
+            log_status: true,
+            ..Default::default()
+        };
+        let worker =
+            GRPCSinkWorker::with_recording(&config, Vec::new(), rerun::RecordingStream::disabled());
+        assert_eq!(
+            worker.shutdown_steps(),
+            [
+                ShutdownStep::Clear,
+                ShutdownStep::LogStatus,
+                ShutdownStep::Flush
+            ]
+        );
+
+        let worker = GRPCSinkWorker::with_recording(
+            &StreamConfig::default(),
+            Vec::new(),
+            rerun::RecordingStream::disabled(),
+        );
+        assert_eq!(worker.shutdown_steps(), [ShutdownStep::Flush], "opt-in");
+    }
+
+    #[test]
+    fn throttle_decimates_per_topic() {
+        let mut throttle = Throttle::new(&TransformConfig {
+            decimate: Some(3),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let (a, b) = (Arc::new("a".to_owned()), Arc::new("b".to_owned()));
+        let forwarded = (0..6).filter(|_| throttle.admit(&a, now)).count();
+        assert_eq!(forwarded, 2);
+        assert!(throttle.admit(&b, now), "first message of a topic is kept");
+    }
+
+    #[test]
+    fn topic_throttle_limits_rate() {
+        assert!(topic_throttle(&TopicSource::default()).is_none());
+        let mut throttle = topic_throttle(&TopicSource {
+            max_rate_hz: Some(10.0),
+            ..Default::default()
+        })
+        .expect("rate limited");
+        let topic = Arc::new("/imu".to_owned());
+        let start = Instant::now();
+        // A message every 30 ms, at ~33 Hz
+        let forwarded: Vec<_> = (0..10)
+            .filter(|i| throttle.admit(&topic, start + Duration::from_millis(i * 30)))
+            .collect();
+        assert_eq!(forwarded, [0, 4, 8], "at most one per 100 ms");
+
+        let mut throttle = topic_throttle(&TopicSource {
+            max_rate_hz: Some(10.0),
+            decimate: Some(2),
+            ..Default::default()
+        })
+        .expect("rate limited");
+        let forwarded: Vec<_> = (0..10)
+            .filter(|i| throttle.admit(&topic, start + Duration::from_millis(i * 60)))
+            .collect();
+        assert_eq!(
+            forwarded,
+            [0, 2, 4, 6, 8],
+            "every second message passes both"
+        );
+    }
+
+    #[test]
+    fn entity_hints_from_config() {
+        let points =
+            RerunName::RerunArchetype(rerun::ArchetypeName::from("rerun.archetypes.Points3D"));
+        let config = TopicSource::default();
+        assert!(
+            entity_hints(&config, &points).expect("valid").is_none(),
+            "no hints configured"
+        );
+        let config = TopicSource {
+            visible: Some(false),
+            default_color: Some(ColorSetting::Hex("#ff0000".to_owned())),
+            ..Default::default()
+        };
+        let hints = entity_hints(&config, &points)
+            .expect("valid")
+            .expect("has hints");
+        assert_eq!(hints.len(), 2);
+    }
+
+    #[test]
+    fn frame_statics_sent_once_per_path() {
+        let hints = [rerun::datatypes::Bool(false)]
+            .serialized(rerun::ComponentDescriptor::partial(
+                "ros_rerun.EntityHints:visible",
+            ))
+            .into_iter()
+            .collect();
+        let statics = FrameStatics {
+            components: vec![(hints, "entity hints")],
+            ..Default::default()
+        };
+        let (base_link, camera) = (
+            Arc::new("/robot/base_link/scan".to_owned()),
+            Arc::new("/robot/camera/scan".to_owned()),
+        );
+        assert_eq!(statics.unsent(&base_link).len(), 1);
+        assert!(statics.unsent(&base_link).is_empty(), "already sent");
+        assert_eq!(statics.unsent(&camera).len(), 1, "new frame");
+        assert!(FrameStatics::default().unsent(&camera).is_empty());
+    }
+
+    #[test]
+    fn converter_info_names_converter() {
+        let converter = ros_rerun_types::converters::text::AnyToTextDocument::default();
+        let source = TopicSource {
+            topic: "/chatter".to_owned(),
+            ..Default::default()
+        };
+        let info = converter_info(&converter, &source, &ROSTypeName::new("std_msgs", "String"));
+        let names: Vec<_> = info
+            .iter()
+            .map(|batch| batch.descriptor.component.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "ros_rerun.ConverterInfo:converter",
+                "ros_rerun.ConverterInfo:version",
+                "ros_rerun.ConverterInfo:topic",
+                "ros_rerun.ConverterInfo:ros_type",
+            ]
+        );
+        assert!(converter.id().ends_with("AnyToTextDocument"));
+    }
+
+    #[test]
+    fn throttle_limits_rate() {
+        let mut throttle = Throttle::new(&TransformConfig {
+            rate: Some(10.0),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        let topic = Arc::new("a".to_owned());
+        assert!(throttle.admit(&topic, start), "first message is kept");
+        assert!(!throttle.admit(&topic, start + Duration::from_millis(50)));
+        assert!(throttle.admit(&topic, start + Duration::from_millis(100)));
+    }
+}
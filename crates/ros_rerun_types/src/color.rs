@@ -0,0 +1,240 @@
+//! Colors in converter configurations.
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// A color given in a converter config.
+///
+/// Either a hex string, `"#rrggbb"` or `"#rrggbbaa"`,
+/// or an array of 3 or 4 components in the 0-255 range.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ColorSetting {
+    Hex(String),
+    Components(Vec<u8>),
+}
+
+impl ColorSetting {
+    /// Parse the color.
+    ///
+    /// # Errors
+    /// Returns an error if the color is malformed.
+    pub fn to_color(&self) -> Result<rerun::Color> {
+        match self {
+            Self::Hex(hex) => parse_hex(hex),
+            Self::Components(components) => match components.as_slice() {
+                [r, g, b] => Ok(rerun::Color::from_rgb(*r, *g, *b)),
+                [r, g, b, a] => Ok(rerun::Color::from_unmultiplied_rgba(*r, *g, *b, *a)),
+                _ => bail!(
+                    "color must have 3 or 4 components, got {}",
+                    components.len()
+                ),
+            },
+        }
+    }
+}
+
+/// Parse a `#rrggbb` or `#rrggbbaa` hex color.
+///
+/// # Errors
+/// Returns an error if the string is not a valid hex color.
+pub fn parse_hex(hex: &str) -> Result<rerun::Color> {
+    let digits = hex
+        .strip_prefix('#')
+        .ok_or_else(|| anyhow!("color '{hex}' must start with '#'"))?;
+    if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+        bail!("color '{hex}' must have 6 or 8 hex digits");
+    }
+    let value =
+        u32::from_str_radix(digits, 16).map_err(|err| anyhow!("invalid color '{hex}': {err}"))?;
+    Ok(if digits.len() == 6 {
+        rerun::Color::from_u32((value << 8) | 0xff)
+    } else {
+        rerun::Color::from_u32(value)
+    })
+}
+
+/// Color scalar values with the Turbo colormap,
+/// normalized to the range of the values.
+///
+/// Non-finite values are colored like the minimum.
+pub fn colormap(values: &[f32]) -> Vec<rerun::Color> {
+    colormap_in_range(values, value_range(values))
+}
+
+/// Color scalar values with the Turbo colormap, normalized to `range`.
+///
+/// Values outside the range are clamped, and non-finite values, or all
+/// values without a range, are colored like the minimum.
+pub fn colormap_in_range(values: &[f32], range: Option<(f32, f32)>) -> Vec<rerun::Color> {
+    Colormap::Turbo.colors_in_range(values, range)
+}
+
+/// Colormap for scalar values given in a converter config.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Colormap {
+    /// Rainbow-like, with a high contrast between nearby values.
+    #[default]
+    Turbo,
+    /// Perceptually uniform from dark purple to yellow, also in grayscale.
+    Viridis,
+}
+
+impl Colormap {
+    /// Color scalar values normalized to `range`, see `colormap_in_range`.
+    pub fn colors_in_range(self, values: &[f32], range: Option<(f32, f32)>) -> Vec<rerun::Color> {
+        let (min, max) = range.unwrap_or((0.0, 0.0));
+        let range = max - min;
+        values
+            .iter()
+            .map(|v| {
+                let t = if range > 0.0 && v.is_finite() {
+                    (v - min) / range
+                } else {
+                    0.0
+                };
+                self.color(t)
+            })
+            .collect()
+    }
+
+    fn color(self, t: f32) -> rerun::Color {
+        match self {
+            Self::Turbo => turbo(t),
+            Self::Viridis => viridis(t),
+        }
+    }
+}
+
+/// Minimum and maximum of the finite values, in a single pass.
+pub fn value_range(values: &[f32]) -> Option<(f32, f32)> {
+    values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(None, |range, v| match range {
+            Some((min, max)) => Some((v.min(min), v.max(max))),
+            None => Some((*v, *v)),
+        })
+}
+
+/// Polynomial approximation of the Turbo colormap for `t` in `[0, 1]`.
+fn turbo(t: f32) -> rerun::Color {
+    let t = t.clamp(0.0, 1.0);
+    let poly = |c: [f32; 6]| c[0] + t * (c[1] + t * (c[2] + t * (c[3] + t * (c[4] + t * c[5]))));
+    let channel = |c: [f32; 6]| (poly(c).clamp(0.0, 1.0) * 255.0).round() as u8;
+    rerun::Color::from_rgb(
+        channel([
+            0.1357214, 4.615393, -42.66032, 132.1311, -152.9424, 59.28638,
+        ]),
+        channel([0.0914026, 2.194188, 4.842967, -14.18503, 4.277299, 2.829566]),
+        channel([
+            0.1066733, 12.64195, -60.58205, 110.3628, -89.90311, 27.34825,
+        ]),
+    )
+}
+
+/// Polynomial approximation of the Viridis colormap for `t` in `[0, 1]`.
+fn viridis(t: f32) -> rerun::Color {
+    let t = t.clamp(0.0, 1.0);
+    let poly = |c: [f32; 7]| {
+        c[0] + t * (c[1] + t * (c[2] + t * (c[3] + t * (c[4] + t * (c[5] + t * c[6])))))
+    };
+    let channel = |c: [f32; 7]| (poly(c).clamp(0.0, 1.0) * 255.0).round() as u8;
+    rerun::Color::from_rgb(
+        channel([
+            0.277_727_3,
+            0.105_093_04,
+            -0.330_861_8,
+            -4.634_230_5,
+            6.228_27,
+            4.776_385,
+            -5.435_456,
+        ]),
+        channel([
+            0.005_407_344_5,
+            1.404_613_5,
+            0.214_847_56,
+            -5.799_101,
+            14.179_933,
+            -13.745_145,
+            4.645_852_6,
+        ]),
+        channel([
+            0.334_099_8,
+            1.384_590_2,
+            0.095_095_16,
+            -19.332_441,
+            56.690_55,
+            -65.353_03,
+            26.312_435,
+        ]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(
+            parse_hex("#ff8000").ok(),
+            Some(rerun::Color::from_rgb(255, 128, 0))
+        );
+        assert_eq!(
+            parse_hex("#ff800080").ok(),
+            Some(rerun::Color::from_unmultiplied_rgba(255, 128, 0, 128))
+        );
+        assert!(parse_hex("ff8000").is_err(), "missing '#'");
+        assert!(parse_hex("#ff80").is_err(), "too short");
+        assert!(parse_hex("#gg8000").is_err(), "not hex");
+    }
+
+    #[test]
+    fn colormap_spans_range() {
+        let colors = colormap(&[0.0, 5.0, 10.0, f32::NAN]);
+        assert_eq!(colors.len(), 4);
+        assert_eq!(colors[0], turbo(0.0));
+        assert_eq!(colors[2], turbo(1.0));
+        assert_eq!(colors[3], colors[0], "non-finite uses the minimum");
+        assert_ne!(colors[0], colors[2]);
+    }
+
+    #[test]
+    fn fixed_range_clamps() {
+        assert_eq!(value_range(&[f32::NAN, 3.0, -1.0]), Some((-1.0, 3.0)));
+        assert_eq!(value_range(&[f32::NAN]), None);
+        let colors = colormap_in_range(&[-5.0, 5.0, 50.0], Some((0.0, 10.0)));
+        assert_eq!(colors, [turbo(0.0), turbo(0.5), turbo(1.0)]);
+    }
+
+    #[test]
+    fn viridis_runs_from_purple_to_yellow() {
+        let colors = Colormap::Viridis.colors_in_range(&[0.0, 1.0], Some((0.0, 1.0)));
+        let [r, g, b, _] = colors[0].to_array();
+        assert!(r < 100 && g < 20 && b > 60, "{:?}", colors[0]);
+        let [r, g, b, _] = colors[1].to_array();
+        assert!(r > 200 && g > 200 && b < 60, "{:?}", colors[1]);
+        assert_eq!(
+            Colormap::default().colors_in_range(&[0.5], Some((0.0, 1.0))),
+            colormap_in_range(&[0.5], Some((0.0, 1.0)))
+        );
+    }
+
+    #[test]
+    fn component_colors() {
+        let color: ColorSetting = toml::Value::Array(vec![
+            toml::Value::Integer(1),
+            toml::Value::Integer(2),
+            toml::Value::Integer(3),
+        ])
+        .try_into()
+        .expect("valid color");
+        assert_eq!(color.to_color().ok(), Some(rerun::Color::from_rgb(1, 2, 3)));
+        assert!(
+            ColorSetting::Components(vec![1, 2]).to_color().is_err(),
+            "too few components"
+        );
+    }
+}
@@ -3,14 +3,19 @@ use anyhow::Result;
 use async_trait::async_trait;
 use dyn_clone::DynClone;
 use log::debug;
+use parking_lot::Mutex;
 use rclrs::DynamicMessageError;
-use rerun::external::re_types_core::ArchetypeName;
-use serde::{Deserialize, Serialize};
+use rerun::external::{arrow::array::Array as _, re_types_core::ArchetypeName};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::fmt::Debug;
 use std::sync::Arc;
 use thiserror::Error;
 
-use crate::{register::register_converters, ROSTypeName, ROSTypeString, RerunName};
+use crate::{
+    overrides::{override_batches, OverrideConfig},
+    register::register_converters,
+    ROSTypeName, ROSTypeString, RerunName,
+};
 
 #[derive(Debug, Error)]
 pub enum ConverterError {
@@ -27,6 +32,37 @@ pub enum ConverterError {
     Conversion(RerunName, String, anyhow::Error),
 }
 
+impl ConverterError {
+    /// Create an `InvalidConfig` error attributed to the given converter.
+    pub fn invalid_config<C: Converter + ?Sized>(
+        converter: &C,
+        err: impl Into<anyhow::Error>,
+    ) -> Self {
+        Self::InvalidConfig(
+            converter.rerun_name(),
+            converter
+                .ros_type()
+                .cloned()
+                .unwrap_or_default()
+                .to_string(),
+            err.into(),
+        )
+    }
+
+    /// Create a `Conversion` error attributed to the given converter.
+    pub fn conversion<C: Converter + ?Sized>(converter: &C, err: impl Into<anyhow::Error>) -> Self {
+        Self::Conversion(
+            converter.rerun_name(),
+            converter
+                .ros_type()
+                .cloned()
+                .unwrap_or_default()
+                .to_string(),
+            err.into(),
+        )
+    }
+}
+
 /// Trait for configuring a message converter.
 ///
 /// All converters must implement this trait.
@@ -38,24 +74,252 @@ pub(super) trait ConverterCfg: Converter {
     /// # Errors
     /// Returns `ConfigParseError` if the configuration is invalid.
     fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError>;
+
+    /// Tell the converter which topic it converts messages of, and the
+    /// entity they are logged to. Called before `set_config`.
+    ///
+    /// Only needed by converters that share state between subscriptions.
+    fn set_topic(&mut self, _topic: &str, _entity_path: &str) {}
 }
 
 dyn_clone::clone_trait_object!(ConverterCfg);
 
+/// Name of the timeline that message timestamps are logged on.
+pub const ROS_TIMELINE: &str = "ros_time";
+
 /// Header information for messages
 ///
 /// Maps to the ROS `std_msgs/Header` definition
 /// and used to set the logged timepoint and
 /// the coordinate frame of reference.
-#[derive(Default)]
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct Header {
-    pub time: rerun::TimePoint,
+    /// Timestamp in nanoseconds since the Unix epoch.
+    ///
+    /// When `None`, the time the message was received is used.
+    pub stamp: Option<i64>,
     pub frame: Option<String>,
 }
 
 pub struct ConverterData {
-    pub header: Option<Arc<Header>>,
+    pub header: Option<Header>,
     pub components: Arc<dyn rerun::AsComponents + Send + Sync>,
+    /// Components logged as static on the same entity, e.g. metadata
+    /// that rarely changes and should not be repeated on the timeline.
+    pub static_components: Option<Arc<dyn rerun::AsComponents + Send + Sync>>,
+    /// Errors for the parts of the message that could not be converted,
+    /// e.g. malformed elements of an array. The rest is still logged.
+    pub partial_errors: Vec<anyhow::Error>,
+    /// Data for several entities below the topic's entity, e.g. one per
+    /// transform of a TF message. When not empty, it is logged instead
+    /// of `components` and `header`, see `ConverterData::batch`.
+    pub batch: Vec<EntityData>,
+    /// Data for several points in time at once, e.g. a planned trajectory,
+    /// logged with the Rerun column API. When not empty, it is logged
+    /// instead of `components`, see `ConverterData::columns`.
+    pub columns: Vec<ColumnData>,
+    /// Entity path the data is logged to instead of the topic's entity.
+    ///
+    /// The static components go to the same entity, and batch entries are
+    /// relative to it. The frame of the header does not affect the path:
+    /// it names the coordinate frame of the data, which Rerun places in
+    /// the world by the transforms logged on the entity and its parents.
+    pub entity_path: Option<String>,
+}
+
+/// Data logged on an entity below the topic's entity.
+pub struct EntityData {
+    /// Entity path relative to the topic's entity,
+    /// empty for the topic's entity itself.
+    pub entity_path: String,
+    pub header: Option<Header>,
+    pub components: Arc<dyn rerun::AsComponents + Send + Sync>,
+    /// Log the components as static data, which holds for all times.
+    pub is_static: bool,
+}
+
+/// Rows of components logged on an entity below the topic's entity, each at
+/// its own time.
+pub struct ColumnData {
+    /// Entity path relative to the topic's entity,
+    /// empty for the topic's entity itself.
+    pub entity_path: String,
+    /// Time of each row in nanoseconds, relative to the stamp of the header.
+    pub offsets: Vec<i64>,
+    /// Components with one entry per row.
+    pub columns: Vec<rerun::SerializedComponentColumn>,
+}
+
+impl EntityData {
+    pub fn new(
+        entity_path: impl Into<String>,
+        components: impl rerun::AsComponents + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            entity_path: entity_path.into(),
+            header: None,
+            components: Arc::new(components),
+            is_static: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Log the components as static data, see `is_static`.
+    #[must_use]
+    pub fn with_static(mut self, is_static: bool) -> Self {
+        self.is_static = is_static;
+        self
+    }
+}
+
+impl ConverterData {
+    pub fn new(components: impl rerun::AsComponents + Send + Sync + 'static) -> Self {
+        Self {
+            header: None,
+            components: Arc::new(components),
+            static_components: None,
+            partial_errors: Vec::new(),
+            batch: Vec::new(),
+            columns: Vec::new(),
+            entity_path: None,
+        }
+    }
+
+    /// Data logged at several points in time at once, relative to the stamp
+    /// of the header, or the time the message was received without one.
+    ///
+    /// Rows are sent in a single chunk per entity, which is much cheaper than
+    /// logging each row on its own, and show up in the viewer all at once.
+    pub fn columns(columns: Vec<ColumnData>) -> Self {
+        Self {
+            columns,
+            ..Self::static_only()
+        }
+    }
+
+    /// Data logged on several entities, each with its own header,
+    /// for messages that describe more than one entity, e.g. the transforms
+    /// of a TF message or the markers of a marker array.
+    ///
+    /// Converters producing a single entity should use `new`, which
+    /// does not allocate a batch.
+    pub fn batch(entities: Vec<EntityData>) -> Self {
+        Self {
+            batch: entities,
+            ..Self::static_only()
+        }
+    }
+
+    /// Data with nothing to log on the timeline, for converters that only
+    /// log static components, see `with_static`.
+    pub fn static_only() -> Self {
+        Self::new(Vec::<rerun::SerializedComponentBatch>::new())
+    }
+
+    /// Fill in the parts of the header that are missing from `self`.
+    #[must_use]
+    pub fn or_header(mut self, header: Header) -> Self {
+        let current = self.header.take().unwrap_or_default();
+        self.header = Some(Header {
+            stamp: current.stamp.or(header.stamp),
+            frame: current.frame.or(header.frame),
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_static(
+        mut self,
+        components: impl rerun::AsComponents + Send + Sync + 'static,
+    ) -> Self {
+        self.static_components = Some(Arc::new(components));
+        self
+    }
+
+    #[must_use]
+    pub fn with_partial_errors(mut self, errors: Vec<anyhow::Error>) -> Self {
+        self.partial_errors = errors;
+        self
+    }
+
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Log to `entity_path` instead of the topic's entity.
+    #[must_use]
+    pub fn with_entity_path(mut self, entity_path: impl Into<String>) -> Self {
+        self.entity_path = Some(entity_path.into());
+        self
+    }
+}
+
+/// How the elements of an array message, e.g. the detections of a
+/// detection array, are logged.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceMode {
+    /// As the instances of a single entity, the cheapest to log and query.
+    #[default]
+    Instanced,
+    /// Each element on its own child entity named by its index, e.g. to
+    /// toggle or plot a single element. Entities of elements missing from
+    /// the next message are kept, unless the topic clears stale children.
+    Split,
+}
+
+impl InstanceMode {
+    /// Arrange the components of a converter output by the mode.
+    ///
+    /// When split, every component with an instance per element is sliced
+    /// into the batch of per-element entities, while components with a
+    /// different number of instances, e.g. a single radius for all points,
+    /// are logged on every entity. Output already logged on several
+    /// entities or as columns, and output without instances, is kept.
+    #[must_use]
+    pub fn apply(self, mut data: ConverterData) -> ConverterData {
+        if self == Self::Instanced || !data.batch.is_empty() || !data.columns.is_empty() {
+            return data;
+        }
+        let batches = data.components.as_serialized_batches();
+        let instances = batches
+            .iter()
+            .map(|batch| batch.array.len())
+            .max()
+            .unwrap_or_default();
+        if instances == 0 {
+            return data;
+        }
+        data.batch = (0..instances)
+            .map(|index| {
+                let components: Vec<_> = batches
+                    .iter()
+                    .map(|batch| rerun::SerializedComponentBatch {
+                        array: if batch.array.len() == instances {
+                            batch.array.slice(index, 1)
+                        } else {
+                            batch.array.clone()
+                        },
+                        descriptor: batch.descriptor.clone(),
+                    })
+                    .collect();
+                let entity = EntityData::new(index.to_string(), components);
+                match &data.header {
+                    Some(header) => entity.with_header(header.clone()),
+                    None => entity,
+                }
+            })
+            .collect();
+        data.components = Arc::new(Vec::<rerun::SerializedComponentBatch>::new());
+        data
+    }
 }
 
 /// Trait for converting ROS messages into Rerun archetypes/components.
@@ -74,6 +338,10 @@ pub trait Converter: DynClone + Send + Sync {
         &self,
         msg: rclrs::DynamicMessageView<'a>,
     ) -> Result<ConverterData, ConverterError>;
+
+    /// Stable identifier of the converter, the name of its type,
+    /// e.g. to tell which converter produced an entity.
+    fn id(&self) -> &'static str;
 }
 
 dyn_clone::clone_trait_object!(Converter);
@@ -81,6 +349,79 @@ dyn_clone::clone_trait_object!(Converter);
 #[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
 pub struct ConverterSettings(pub toml::Table);
 
+impl ConverterSettings {
+    /// Setting with the dotted path of the message header, used by every converter.
+    pub const HEADER_FIELD: &'static str = "header_field";
+
+    /// Setting with constant component values, see `OverrideConfig`.
+    pub const OVERRIDES: &'static str = "overrides";
+
+    /// Setting with the `InstanceMode` of array messages.
+    pub const INSTANCE_MODE: &'static str = "instance_mode";
+
+    /// Setting with the `Converter::id` of the generic converter to use,
+    /// for archetypes with several generic converters.
+    pub const CONVERTER: &'static str = "converter";
+
+    /// Remove the `header_field` setting, which is applied by the
+    /// `ConverterBuilder` rather than by each converter.
+    ///
+    /// # Errors
+    /// Returns an error if the setting is not a string.
+    pub fn take_header_field(&mut self) -> anyhow::Result<Option<String>> {
+        match self.0.remove(Self::HEADER_FIELD) {
+            Some(toml::Value::String(field)) => Ok(Some(field)),
+            Some(_) => anyhow::bail!("'{}' must be a string", Self::HEADER_FIELD),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the `overrides` table, which is applied by the
+    /// `ConverterBuilder` to the output of every converter.
+    ///
+    /// # Errors
+    /// Returns an error if the table does not match `OverrideConfig`.
+    pub fn take_overrides(&mut self) -> Result<Option<OverrideConfig>, toml::de::Error> {
+        self.0
+            .remove(Self::OVERRIDES)
+            .map(toml::Value::try_into)
+            .transpose()
+    }
+
+    /// Remove the `instance_mode` setting, which is applied by the
+    /// `ConverterBuilder` to the output of every converter.
+    ///
+    /// # Errors
+    /// Returns an error if the setting is not an `InstanceMode`.
+    pub fn take_instance_mode(&mut self) -> Result<Option<InstanceMode>, toml::de::Error> {
+        self.0
+            .remove(Self::INSTANCE_MODE)
+            .map(toml::Value::try_into)
+            .transpose()
+    }
+
+    /// Remove the `converter` setting, which selects the converter the
+    /// remaining settings are for.
+    ///
+    /// # Errors
+    /// Returns an error if the setting is not a string.
+    pub fn take_converter(&mut self) -> anyhow::Result<Option<String>> {
+        match self.0.remove(Self::CONVERTER) {
+            Some(toml::Value::String(id)) => Ok(Some(id)),
+            Some(_) => anyhow::bail!("'{}' must be a string", Self::CONVERTER),
+            None => Ok(None),
+        }
+    }
+
+    /// Deserialize the settings into a converter specific config.
+    ///
+    /// # Errors
+    /// Returns a `toml::de::Error` if the settings do not match the config.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, toml::de::Error> {
+        toml::Value::Table(self.0.clone()).try_into()
+    }
+}
+
 /// Builder for configuring archetype converters.
 ///
 /// It abstracts over finding the correct converter from the registry
@@ -88,6 +429,7 @@ pub struct ConverterSettings(pub toml::Table);
 pub struct ConverterBuilder<'a> {
     registry: &'a ConverterRegistry,
     topic: String,
+    entity_path: Option<String>,
     ros_type: Option<ROSTypeName>,
     rerun_name: Option<RerunName>,
     config: Option<ConverterSettings>,
@@ -98,6 +440,7 @@ impl<'a> ConverterBuilder<'a> {
         Self {
             registry,
             topic: String::new(),
+            entity_path: None,
             ros_type: None,
             rerun_name: None,
             config: None,
@@ -109,6 +452,12 @@ impl<'a> ConverterBuilder<'a> {
         self
     }
 
+    /// Entity the messages are logged to, defaults to the topic.
+    pub fn entity_path(mut self, entity_path: &str) -> Self {
+        self.entity_path = Some(entity_path.to_owned());
+        self
+    }
+
     pub fn ros_type(mut self, ros_type: ROSTypeName) -> Self {
         self.ros_type = Some(ros_type);
         self
@@ -132,13 +481,161 @@ impl<'a> ConverterBuilder<'a> {
         let mut converter = self
             .registry
             .find_converter(self.ros_type.as_ref(), self.rerun_name.as_ref())?;
-        if let Some(config) = self.config {
-            converter.set_config(config)?;
+        let entity_path = self.entity_path.as_deref().unwrap_or(&self.topic);
+        if let Some(mut config) = self.config {
+            let header_field = config
+                .take_header_field()
+                .map_err(|err| ConverterError::invalid_config(converter.as_ref(), err))?;
+            let overrides = config
+                .take_overrides()
+                .map_err(|err| ConverterError::invalid_config(converter.as_ref(), err))?;
+            let instance_mode = config
+                .take_instance_mode()
+                .map_err(|err| ConverterError::invalid_config(converter.as_ref(), err))?;
+            let converter = self
+                .registry
+                .configure(converter, config, &self.topic, entity_path)?;
+            let overrides = overrides
+                .map(|overrides| override_batches(&converter.rerun_name(), &overrides))
+                .transpose()
+                .map_err(|err| ConverterError::invalid_config(converter.as_ref(), err))?;
+            let mut converter: Box<dyn Converter> = match header_field {
+                Some(field) => Box::new(HeaderField {
+                    inner: converter,
+                    field,
+                }),
+                None => converter,
+            };
+            if let Some(batches) = overrides {
+                converter = Box::new(Overrides {
+                    inner: converter,
+                    batches: Arc::new(batches),
+                });
+            }
+            // Split last, so overridden components end up on every element
+            if instance_mode == Some(InstanceMode::Split) {
+                converter = Box::new(SplitInstances { inner: converter });
+            }
+            return Ok(converter);
         }
+        converter.set_topic(&self.topic, entity_path);
         Ok(converter)
     }
 }
 
+/// Reads the header from the configured `header_field` of a message,
+/// for messages that do not keep their `std_msgs/Header` in `header`.
+///
+/// The header found at the field takes precedence over the one
+/// provided by the wrapped converter.
+#[derive(Clone)]
+struct HeaderField {
+    inner: Box<dyn ConverterCfg>,
+    field: String,
+}
+
+#[async_trait]
+impl Converter for HeaderField {
+    fn rerun_name(&self) -> RerunName {
+        self.inner.rerun_name()
+    }
+
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        self.inner.ros_type()
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let header = crate::definitions::std_msgs::Header::from_path(&msg, &self.field)
+            .map_err(|err| ConverterError::conversion(self, err))?
+            .ok_or_else(|| {
+                ConverterError::conversion(
+                    self,
+                    anyhow::anyhow!("missing header field '{}'", self.field),
+                )
+            })?;
+        let mut data = self.inner.convert_view(msg).await?;
+        let fallback = data.header.take().unwrap_or_default();
+        Ok(data.with_header(Header::from(&header)).or_header(fallback))
+    }
+}
+
+/// Replaces components of the wrapped converter output with the
+/// constant values of the configured `overrides`.
+#[derive(Clone)]
+struct Overrides {
+    inner: Box<dyn Converter>,
+    batches: Arc<Vec<rerun::SerializedComponentBatch>>,
+}
+
+#[async_trait]
+impl Converter for Overrides {
+    fn rerun_name(&self) -> RerunName {
+        self.inner.rerun_name()
+    }
+
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        self.inner.ros_type()
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let mut data = self.inner.convert_view(msg).await?;
+        let mut batches = data.components.as_serialized_batches();
+        batches.retain(|batch| {
+            !self
+                .batches
+                .iter()
+                .any(|other| other.descriptor == batch.descriptor)
+        });
+        batches.extend(self.batches.iter().cloned());
+        data.components = Arc::new(batches);
+        Ok(data)
+    }
+}
+
+/// Logs the elements of the wrapped converter output on separate
+/// entities, see `InstanceMode::Split`.
+#[derive(Clone)]
+struct SplitInstances {
+    inner: Box<dyn Converter>,
+}
+
+#[async_trait]
+impl Converter for SplitInstances {
+    fn rerun_name(&self) -> RerunName {
+        self.inner.rerun_name()
+    }
+
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        self.inner.ros_type()
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let data = self.inner.convert_view(msg).await?;
+        Ok(InstanceMode::Split.apply(data))
+    }
+}
+
 /// Registry for message converters.
 ///
 /// A converter registers a single ROS type to Rerun archetype/components mapping.
@@ -158,9 +655,14 @@ pub struct ConverterRegistry {
     /// This essentially defines the default archetype for a given ROS type.
     converters_by_ros_type: HashMap<ROSTypeName, Box<dyn ConverterCfg>>,
     /// Tracks generic converters that can (attempt to) convert any ROS type to a Rerun archetype.
-    generic_converters: HashMap<RerunName, Box<dyn ConverterCfg>>,
+    ///
+    /// An archetype can have several generic converters, selected by their id with
+    /// the `converter` setting, see `configure`. The first one registered is the default.
+    generic_converters: HashMap<RerunName, Vec<Box<dyn ConverterCfg>>>,
     /// Tracks errors for ROS type definitions that could not be found in the current environment.
     error_types: HashMap<String, DynamicMessageError>,
+    /// Resolved ROS types keyed by package and type name, see `resolve_type`.
+    type_cache: Mutex<HashMap<(String, String), ROSTypeName>>,
 }
 
 impl ConverterRegistry {
@@ -170,6 +672,7 @@ impl ConverterRegistry {
             converters_by_ros_type: HashMap::new(),
             generic_converters: HashMap::new(),
             error_types: HashMap::new(),
+            type_cache: Mutex::new(HashMap::new()),
         };
 
         register_converters(&mut registry);
@@ -177,9 +680,40 @@ impl ConverterRegistry {
         registry
     }
 
+    /// Resolve a ROS type name, e.g. `std_msgs/String`, reusing earlier lookups.
+    ///
+    /// Loading a type definition is slow, and large configurations resolve
+    /// the same types for many topics. Only resolved types are cached, so
+    /// types that are missing can still resolve later.
+    ///
+    /// # Errors
+    /// Returns a `DynamicMessageError` if the type definition is not available.
+    pub fn resolve_type(&self, type_name: &str) -> Result<ROSTypeName, DynamicMessageError> {
+        let Some(key) = type_cache_key(type_name) else {
+            return ROSTypeName::try_from(type_name);
+        };
+        if let Some(ros_type) = self.type_cache.lock().get(&key) {
+            return Ok(ros_type.clone());
+        }
+        let ros_type = ROSTypeName::try_from(type_name)?;
+        self.type_cache.lock().insert(key, ros_type.clone());
+        Ok(ros_type)
+    }
+
+    /// Forget the resolved ROS types, e.g. after new type definitions were installed.
+    pub fn clear_type_cache(&self) {
+        self.type_cache.lock().clear();
+    }
+
     /// Find a converter for a ROS type and a Rerun name.
     /// If the Rerun name is not specified, it will pick the default converter for the ROS type, if any.
     ///
+    /// Without a ROS type, only the generic converter of the Rerun name is used.
+    /// This is how topics configured with the `*` ROS type are converted: their
+    /// subscription still uses the type discovered from the ROS graph, but the
+    /// converter does not depend on it. With both, a converter registered for
+    /// the pair takes precedence over the generic one.
+    ///
     /// # Errors
     /// Returns `ConverterError::UnsupportedConversion` if no suitable converter is found.
     pub(crate) fn find_converter(
@@ -210,6 +744,7 @@ impl ConverterRegistry {
             .or_else(|| {
                 self.generic_converters
                     .get(&rerun_name)
+                    .and_then(|converters| converters.first())
                     .map(|converter| Ok(converter.clone()))
             })
             .unwrap_or(Err(ConverterError::UnsupportedConversion {
@@ -222,6 +757,7 @@ impl ConverterRegistry {
         let rerun_name = fully_qualified_name(rerun_name);
         self.generic_converters
             .get(&rerun_name)
+            .and_then(|converters| converters.first())
             .map(|converter| Ok(converter.clone()))
             .unwrap_or(Err(ConverterError::UnsupportedConversion {
                 name: rerun_name,
@@ -240,6 +776,60 @@ impl ConverterRegistry {
         }
     }
 
+    /// Set the topic and the config of a converter found by `find_converter`.
+    ///
+    /// The `converter` setting selects another generic converter of the same
+    /// archetype by its `Converter::id`, e.g. `AnyRadarToPoints3D` instead of
+    /// the default `AnyTransformArrayToPoses` for `Points3D`.
+    ///
+    /// # Errors
+    /// Returns `ConverterError::InvalidConfig` if the setting names no generic
+    /// converter of the archetype, or if the converter rejects the config.
+    fn configure(
+        &self,
+        converter: Box<dyn ConverterCfg>,
+        mut config: ConverterSettings,
+        topic: &str,
+        entity_path: &str,
+    ) -> FindConverterResult {
+        let id = config
+            .take_converter()
+            .map_err(|err| ConverterError::invalid_config(converter.as_ref(), err))?;
+        let mut converter = match id {
+            Some(id) if id != converter.id() => {
+                self.find_generic_converter(converter.as_ref(), &id)?
+            }
+            _ => converter,
+        };
+        converter.set_topic(topic, entity_path);
+        converter.set_config(config)?;
+        Ok(converter)
+    }
+
+    /// Find the generic converter with the `id` for the archetype of `converter`.
+    fn find_generic_converter(
+        &self,
+        converter: &dyn ConverterCfg,
+        id: &str,
+    ) -> FindConverterResult {
+        let generic = self
+            .generic_converters
+            .get(&converter.rerun_name())
+            .map_or(&[][..], Vec::as_slice);
+        if let Some(found) = generic.iter().find(|generic| generic.id() == id) {
+            return Ok(found.clone());
+        }
+        let available: Vec<_> = generic.iter().map(|generic| generic.id()).collect();
+        Err(ConverterError::invalid_config(
+            converter,
+            anyhow::anyhow!(
+                "no generic converter '{id}' for {}, available: [{}]",
+                converter.rerun_name(),
+                available.join(", ")
+            ),
+        ))
+    }
+
     pub(crate) fn register<T>(&mut self, converter: &T)
     where
         T: ConverterCfg + Clone + 'static,
@@ -262,6 +852,13 @@ impl ConverterRegistry {
         match parsed_type {
             Ok(Some(ros_type)) => {
                 debug!("Registered converter for {rerun_name} with ROS type {ros_type}");
+                self.type_cache.get_mut().insert(
+                    (
+                        ros_type.0.package_name.clone(),
+                        ros_type.0.type_name.clone(),
+                    ),
+                    ros_type.clone(),
+                );
                 if !self.converters_by_ros_type.contains_key(&ros_type) {
                     self.converters_by_ros_type
                         .insert(ros_type.clone(), converter.clone());
@@ -272,7 +869,9 @@ impl ConverterRegistry {
             Ok(None) => {
                 debug!("Registered generic converter for {rerun_name}");
                 self.generic_converters
-                    .insert(rerun_name.clone(), converter);
+                    .entry(rerun_name.clone())
+                    .or_default()
+                    .push(converter);
             }
             Err(err) => {
                 if let Some(ros_type) = ros_type {
@@ -289,6 +888,16 @@ impl ConverterRegistry {
     }
 }
 
+/// Cache key of a type name, the same for every accepted form of the name.
+fn type_cache_key(type_name: &str) -> Option<(String, String)> {
+    ROSTypeString::parse(type_name).map(|parsed| {
+        (
+            parsed.package_name().to_owned(),
+            parsed.type_name().to_owned(),
+        )
+    })
+}
+
 pub(super) type FindConverterResult = Result<Box<dyn ConverterCfg>, ConverterError>;
 
 fn fully_qualified_name(name: &RerunName) -> RerunName {
@@ -305,3 +914,140 @@ fn fully_qualified_name(name: &RerunName) -> RerunName {
         _ => name.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_custom_header_field() {
+        let mut config = ConverterSettings(toml::toml! {
+            header_field = "meta.header"
+            radius = 0.1
+        });
+        assert_eq!(
+            config.take_header_field().expect("string"),
+            Some("meta.header".to_owned())
+        );
+        assert!(
+            !config.0.contains_key(ConverterSettings::HEADER_FIELD),
+            "not passed on to the converter"
+        );
+        assert!(config.0.contains_key("radius"));
+
+        let mut config = ConverterSettings(toml::toml! { header_field = 1 });
+        assert!(config.take_header_field().is_err(), "must be a string");
+    }
+
+    #[test]
+    fn selects_generic_converter_by_id() {
+        use crate::converters::{
+            pose_array::{AnyTransformArrayToPoses, PoseOutput},
+            radar::AnyRadarToPoints3D,
+        };
+        use rerun::Archetype as _;
+
+        let mut registry = ConverterRegistry {
+            converters: HashMap::new(),
+            converters_by_ros_type: HashMap::new(),
+            generic_converters: HashMap::new(),
+            error_types: HashMap::new(),
+            type_cache: Mutex::new(HashMap::new()),
+        };
+        registry.register(&AnyTransformArrayToPoses::new(PoseOutput::Points));
+        registry.register(&AnyRadarToPoints3D::default());
+        let points = RerunName::RerunArchetype(rerun::Points3D::name());
+        let find = || {
+            registry
+                .find_converter(None, Some(&points))
+                .expect("generic")
+        };
+
+        let radar = ConverterSettings(toml::toml! {
+            converter = "AnyRadarToPoints3D"
+            returns = "targets"
+        });
+        let configure = |config| registry.configure(find(), config, "/radar", "/radar");
+        let converter = configure(radar).expect("radar config");
+        assert_eq!(converter.id(), "AnyRadarToPoints3D");
+
+        let err = configure(ConverterSettings(toml::toml! { returns = "targets" }))
+            .err()
+            .expect("not a transform array config");
+        assert!(err.to_string().contains("returns"), "{err}");
+
+        let unknown = ConverterSettings(toml::toml! { converter = "AnyLidarToPoints3D" });
+        let err = configure(unknown).err().expect("unknown id");
+        assert!(err.to_string().contains("AnyRadarToPoints3D"), "{err}");
+    }
+
+    #[test]
+    fn takes_instance_mode() {
+        let mut config = ConverterSettings(toml::toml! {
+            instance_mode = "split"
+            radius = 0.1
+        });
+        assert_eq!(
+            config.take_instance_mode().expect("valid mode"),
+            Some(InstanceMode::Split)
+        );
+        assert!(!config.0.contains_key(ConverterSettings::INSTANCE_MODE));
+
+        let mut config = ConverterSettings(toml::toml! { instance_mode = "separate" });
+        assert!(config.take_instance_mode().is_err(), "unknown mode");
+    }
+
+    #[test]
+    fn type_cache_key_forms() {
+        let key = Some(("std_msgs".to_owned(), "String".to_owned()));
+        assert_eq!(type_cache_key("std_msgs/String"), key);
+        assert_eq!(type_cache_key("std_msgs/msg/String"), key);
+        assert_eq!(type_cache_key("String"), None);
+    }
+
+    #[test]
+    fn wrappers_keep_converter_id() {
+        let text = crate::converters::text::AnyToTextDocument::default();
+        assert_eq!(text.id(), "AnyToTextDocument");
+        let converter = Overrides {
+            inner: Box::new(HeaderField {
+                inner: Box::new(text.clone()),
+                field: "meta.header".to_owned(),
+            }),
+            batches: Arc::new(Vec::new()),
+        };
+        assert_eq!(
+            converter.id(),
+            text.id(),
+            "wrappers report the inner converter"
+        );
+    }
+
+    #[test]
+    fn entity_path_defaults_to_topic() {
+        let data = ConverterData::new(rerun::Clear::flat());
+        assert_eq!(data.entity_path, None, "logged to the topic's entity");
+        let data = data.with_entity_path("/robot/base_link");
+        assert_eq!(data.entity_path.as_deref(), Some("/robot/base_link"));
+    }
+
+    #[test]
+    fn header_field_takes_precedence() {
+        let data = ConverterData::new(rerun::Clear::flat())
+            .with_header(Header {
+                stamp: Some(2),
+                frame: None,
+            })
+            .or_header(Header {
+                stamp: Some(1),
+                frame: Some("map".to_owned()),
+            });
+        assert_eq!(
+            data.header,
+            Some(Header {
+                stamp: Some(2),
+                frame: Some("map".to_owned()),
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{sensor_msgs::BatteryState, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Child entity of the status log, see `BatteryConfig::log_status`.
+pub const STATUS_ENTITY: &str = "status";
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BatteryConfig {
+    /// Log the charging status and health as a `TextLog` on the child
+    /// entity `status` whenever they change.
+    #[serde(default)]
+    log_status: bool,
+}
+
+/// Converts a battery state into a series per measured value, logged as
+/// `Scalars` on the child entities `voltage`, `current`, `percentage`
+/// and `temperature`.
+///
+/// Values that are not measured are NaN in ROS, and are skipped rather
+/// than plotted, so a series only has the points the battery reported.
+#[derive(Clone, Debug, Default)]
+pub struct SensorBatteryStateToScalars {
+    config: BatteryConfig,
+    /// Last logged status and health, per subscription.
+    status: Arc<Mutex<Option<(u8, u8)>>>,
+}
+
+impl SensorBatteryStateToScalars {
+    fn convert(&self, msg: &BatteryState) -> ConverterData {
+        let header = Header::from(&msg.header);
+        let values = [
+            ("voltage", msg.voltage),
+            ("current", msg.current),
+            ("percentage", msg.percentage),
+            ("temperature", msg.temperature),
+        ];
+        let mut entities: Vec<_> = values
+            .into_iter()
+            .filter(|(_, value)| !value.is_nan())
+            .map(|(name, value)| {
+                EntityData::new(name, rerun::Scalars::new([f64::from(value)]))
+                    .with_header(header.clone())
+            })
+            .collect();
+        if let Some(status) = self.status_log(msg) {
+            entities.push(EntityData::new(STATUS_ENTITY, status).with_header(header.clone()));
+        }
+        ConverterData::batch(entities).with_header(header)
+    }
+
+    /// Text log of the status and health, when enabled and changed.
+    fn status_log(&self, msg: &BatteryState) -> Option<rerun::TextLog> {
+        if !self.config.log_status {
+            return None;
+        }
+        let status = (msg.power_supply_status, msg.power_supply_health);
+        let mut last = self.status.lock();
+        if *last == Some(status) {
+            return None;
+        }
+        *last = Some(status);
+        let level = match msg.power_supply_health {
+            BatteryState::POWER_SUPPLY_HEALTH_UNKNOWN | BatteryState::POWER_SUPPLY_HEALTH_GOOD => {
+                rerun::components::TextLogLevel::INFO
+            }
+            _ => rerun::components::TextLogLevel::WARN,
+        };
+        Some(
+            rerun::TextLog::new(format!(
+                "{}, health {}",
+                msg.status_name(),
+                msg.health_name()
+            ))
+            .with_level(level),
+        )
+    }
+}
+
+impl ConverterCfg for SensorBatteryStateToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.status = Arc::default();
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorBatteryStateToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorBatteryStateToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(BatteryState::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: BatteryState =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(data: &ConverterData) -> Vec<&str> {
+        data.batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn skips_unmeasured_values() {
+        let msg = BatteryState {
+            voltage: 24.1,
+            current: -1.5,
+            percentage: 0.8,
+            temperature: f32::NAN,
+            power_supply_status: BatteryState::POWER_SUPPLY_STATUS_DISCHARGING,
+            ..Default::default()
+        };
+        let mut converter = SensorBatteryStateToScalars::default();
+        assert_eq!(
+            paths(&converter.convert(&msg)),
+            ["voltage", "current", "percentage"]
+        );
+
+        converter
+            .set_config(ConverterSettings(toml::toml! { log_status = true }))
+            .expect("valid config");
+        assert_eq!(
+            paths(&converter.convert(&msg)),
+            ["voltage", "current", "percentage", "status"]
+        );
+        assert!(
+            !paths(&converter.convert(&msg)).contains(&STATUS_ENTITY),
+            "unchanged status is not logged again"
+        );
+        let overheated = BatteryState {
+            power_supply_health: BatteryState::POWER_SUPPLY_HEALTH_OVERHEAT,
+            ..msg
+        };
+        assert_eq!(overheated.health_name(), "OVERHEAT");
+        assert!(paths(&converter.convert(&overheated)).contains(&STATUS_ENTITY));
+    }
+}
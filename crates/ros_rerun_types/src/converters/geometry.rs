@@ -0,0 +1,412 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::ColorSetting,
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{
+        geometry_msgs::{Accel, AccelStamped, Twist, TwistStamped, Vector3},
+        sensor_msgs::Imu,
+        std_msgs, ROSMessage,
+    },
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct VectorConfig {
+    /// Coordinate frame used when the message has no header
+    /// or the header frame is empty.
+    frame_id: Option<String>,
+}
+
+impl VectorConfig {
+    fn header(&self, msg_header: Option<&std_msgs::Header>) -> Header {
+        let mut header = msg_header.map(Header::from).unwrap_or_default();
+        if header.frame.is_none() {
+            header.frame.clone_from(&self.frame_id);
+        }
+        header
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArrowsConfig {
+    /// Coordinate frame used when the message has no header
+    /// or the header frame is empty.
+    frame_id: Option<String>,
+
+    /// Color of the linear arrow, defaults to `LINEAR_COLOR`.
+    linear_color: Option<ColorSetting>,
+
+    /// Color of the angular arrow, defaults to `ANGULAR_COLOR`.
+    angular_color: Option<ColorSetting>,
+
+    /// What the arrows show, the linear and angular vectors by default.
+    #[serde(default)]
+    mode: ArrowsMode,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrowsMode {
+    /// An arrow for the linear and one for the angular vector.
+    #[default]
+    Vectors,
+
+    /// A unit arrow pointing up, away from gravity, in the linear color.
+    ///
+    /// Only for IMUs, whose accelerometer reads the reaction to gravity.
+    /// The estimate is only meaningful while the device accelerates little,
+    /// e.g. at rest, as any other acceleration tilts the arrow.
+    GravityArrow,
+}
+
+/// Default color of linear arrows.
+pub const LINEAR_COLOR: rerun::Color = rerun::Color::from_rgb(0x1f, 0x77, 0xb4);
+
+/// Default color of angular arrows.
+pub const ANGULAR_COLOR: rerun::Color = rerun::Color::from_rgb(0xff, 0x7f, 0x0e);
+
+/// ROS messages made up of a linear and an angular vector,
+/// i.e. velocities and accelerations with or without a header.
+pub trait LinearAngular: ROSMessage + Clone + std::fmt::Debug + Default {
+    fn header(&self) -> Option<&std_msgs::Header>;
+    fn linear(&self) -> &Vector3;
+    fn angular(&self) -> &Vector3;
+
+    /// Whether the linear vector includes gravity, see `ArrowsMode::GravityArrow`.
+    const MEASURES_GRAVITY: bool = false;
+}
+
+impl LinearAngular for Twist {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        None
+    }
+    fn linear(&self) -> &Vector3 {
+        &self.linear
+    }
+    fn angular(&self) -> &Vector3 {
+        &self.angular
+    }
+}
+
+impl LinearAngular for TwistStamped {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        Some(&self.header)
+    }
+    fn linear(&self) -> &Vector3 {
+        &self.twist.linear
+    }
+    fn angular(&self) -> &Vector3 {
+        &self.twist.angular
+    }
+}
+
+impl LinearAngular for Accel {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        None
+    }
+    fn linear(&self) -> &Vector3 {
+        &self.linear
+    }
+    fn angular(&self) -> &Vector3 {
+        &self.angular
+    }
+}
+
+impl LinearAngular for AccelStamped {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        Some(&self.header)
+    }
+    fn linear(&self) -> &Vector3 {
+        &self.accel.linear
+    }
+    fn angular(&self) -> &Vector3 {
+        &self.accel.angular
+    }
+}
+
+/// The linear acceleration and angular velocity measured by an IMU.
+impl LinearAngular for Imu {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        Some(&self.header)
+    }
+    fn linear(&self) -> &Vector3 {
+        &self.linear_acceleration
+    }
+    fn angular(&self) -> &Vector3 {
+        &self.angular_velocity
+    }
+
+    const MEASURES_GRAVITY: bool = true;
+}
+
+/// Arrows starting at the origin, one for each labelled and colored vector.
+pub fn vector_arrows(vectors: &[(&str, &Vector3, rerun::Color)]) -> rerun::Arrows3D {
+    rerun::Arrows3D::from_vectors(
+        vectors
+            .iter()
+            .map(|(_, v, _)| rerun::datatypes::Vec3D::from(*v)),
+    )
+    .with_labels(vectors.iter().map(|(label, _, _)| *label))
+    .with_colors(vectors.iter().map(|(_, _, color)| *color))
+}
+
+/// Unit vector along a measured specific force, i.e. up for a device at rest.
+fn up_vector(specific_force: &Vector3) -> Option<Vector3> {
+    let Vector3 { x, y, z } = *specific_force;
+    let norm = (x * x + y * y + z * z).sqrt();
+    (norm.is_finite() && norm > 0.0).then(|| Vector3 {
+        x: x / norm,
+        y: y / norm,
+        z: z / norm,
+    })
+}
+
+/// Converts velocities and accelerations to a linear and an angular arrow.
+#[derive(Clone, Debug)]
+pub struct LinearAngularToArrows3D<T: LinearAngular> {
+    config: VectorConfig,
+    /// Colors of the linear and the angular arrow.
+    colors: [rerun::Color; 2],
+    mode: ArrowsMode,
+    _msg: PhantomData<fn() -> T>,
+}
+
+impl<T: LinearAngular> Default for LinearAngularToArrows3D<T> {
+    fn default() -> Self {
+        Self {
+            config: VectorConfig::default(),
+            colors: [LINEAR_COLOR, ANGULAR_COLOR],
+            mode: ArrowsMode::default(),
+            _msg: PhantomData,
+        }
+    }
+}
+
+impl<T: LinearAngular> LinearAngularToArrows3D<T> {
+    fn arrows(&self, msg: &T) -> rerun::Arrows3D {
+        let [linear, angular] = self.colors;
+        if self.mode == ArrowsMode::GravityArrow {
+            return match up_vector(msg.linear()) {
+                Some(up) => vector_arrows(&[("up", &up, linear)]),
+                None => vector_arrows(&[]),
+            };
+        }
+        vector_arrows(&[
+            ("linear", msg.linear(), linear),
+            ("angular", msg.angular(), angular),
+        ])
+    }
+}
+
+impl<T: LinearAngular> ConverterCfg for LinearAngularToArrows3D<T> {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        let config: ArrowsConfig = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if config.mode == ArrowsMode::GravityArrow && !T::MEASURES_GRAVITY {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'gravity_arrow' mode is only supported for IMU messages"),
+            ));
+        }
+        self.mode = config.mode;
+        let color = |setting: &Option<ColorSetting>, default| {
+            setting
+                .as_ref()
+                .map_or(Ok(default), ColorSetting::to_color)
+                .map_err(|err| ConverterError::invalid_config(self, err))
+        };
+        self.colors = [
+            color(&config.linear_color, LINEAR_COLOR)?,
+            color(&config.angular_color, ANGULAR_COLOR)?,
+        ];
+        self.config = VectorConfig {
+            frame_id: config.frame_id,
+        };
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: LinearAngular> Converter for LinearAngularToArrows3D<T> {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Arrows3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "LinearAngularToArrows3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(T::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: T = deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(ConverterData::new(self.arrows(&msg)).with_header(self.config.header(msg.header())))
+    }
+}
+
+/// Converts velocities and accelerations to six scalars,
+/// the linear x/y/z components followed by the angular ones.
+#[derive(Clone, Debug, Default)]
+pub struct LinearAngularToScalars<T: LinearAngular> {
+    config: VectorConfig,
+    _msg: PhantomData<fn() -> T>,
+}
+
+impl<T: LinearAngular> ConverterCfg for LinearAngularToScalars<T> {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: LinearAngular> Converter for LinearAngularToScalars<T> {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "LinearAngularToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(T::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: T = deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        let (linear, angular) = (msg.linear(), msg.angular());
+        let scalars = rerun::Scalars::new([
+            linear.x, linear.y, linear.z, angular.x, angular.y, angular.z,
+        ]);
+        Ok(ConverterData::new(scalars).with_header(self.config.header(msg.header())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::builtin_interfaces::Time;
+
+    #[test]
+    fn unstamped_uses_config_frame() {
+        let config: VectorConfig = ConverterSettings(toml::toml! { frame_id = "base_link" })
+            .parse()
+            .expect("valid config");
+        let header = config.header(Twist::default().header());
+        assert_eq!(header.frame.as_deref(), Some("base_link"));
+        assert_eq!(header.stamp, None, "unstamped messages use receive time");
+    }
+
+    #[test]
+    fn stamped_prefers_header_frame() {
+        let config: VectorConfig = ConverterSettings(toml::toml! { frame_id = "base_link" })
+            .parse()
+            .expect("valid config");
+        let msg = TwistStamped {
+            header: std_msgs::Header {
+                stamp: Time { sec: 2, nanosec: 5 },
+                frame_id: "odom".to_owned(),
+            },
+            ..Default::default()
+        };
+        let header = config.header(msg.header());
+        assert_eq!(header.frame.as_deref(), Some("odom"));
+        assert_eq!(header.stamp, Some(2_000_000_005));
+    }
+
+    #[test]
+    fn arrows_match_twist() {
+        let mut converter = LinearAngularToArrows3D::<Twist>::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                linear_color = "#00ff00"
+                angular_color = [0, 0, 255]
+            }))
+            .expect("valid config");
+        let msg = Twist {
+            linear: Vector3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            },
+            angular: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: -0.5,
+            },
+        };
+        let arrows = converter.arrows(&msg);
+        let expected = rerun::Arrows3D::from_vectors([[1.0, 2.0, 3.0], [0.0, 0.0, -0.5]])
+            .with_labels(["linear", "angular"])
+            .with_colors([
+                rerun::Color::from_rgb(0, 255, 0),
+                rerun::Color::from_rgb(0, 0, 255),
+            ]);
+        assert_eq!(arrows.vectors, expected.vectors);
+        assert_eq!(arrows.colors, expected.colors);
+
+        let invalid = ConverterSettings(toml::toml! { linear_color = "green" });
+        assert!(converter.set_config(invalid).is_err());
+    }
+
+    #[test]
+    fn imu_gravity_arrow() {
+        let mut converter = LinearAngularToArrows3D::<Imu>::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { mode = "gravity_arrow" }))
+            .expect("valid config");
+        // Lying on its side at rest, the y axis points up
+        let imu = Imu {
+            linear_acceleration: Vector3 {
+                x: 0.0,
+                y: 9.81,
+                z: 0.0,
+            },
+            ..Default::default()
+        };
+        let expected = rerun::Arrows3D::from_vectors([[0.0, 1.0, 0.0]]).with_labels(["up"]);
+        let arrows = converter.arrows(&imu);
+        assert_eq!(arrows.vectors, expected.vectors);
+        assert_eq!(arrows.labels, expected.labels);
+        assert_eq!(up_vector(&Vector3::default()), None, "free fall");
+
+        let mut twist = LinearAngularToArrows3D::<Twist>::default();
+        assert!(
+            twist
+                .set_config(ConverterSettings(toml::toml! { mode = "gravity_arrow" }))
+                .is_err(),
+            "twists do not measure gravity"
+        );
+    }
+
+    #[test]
+    fn unknown_config_is_rejected() {
+        let config = ConverterSettings(toml::toml! { frame = "base_link" });
+        assert!(
+            config.parse::<VectorConfig>().is_err(),
+            "unknown keys must be rejected"
+        );
+    }
+}
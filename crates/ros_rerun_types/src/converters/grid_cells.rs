@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{nav_msgs::GridCells, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Archetype that grid cells are logged as.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CellOutput {
+    /// A point at each cell center.
+    #[default]
+    Points,
+    /// A flat box covering each cell.
+    Boxes,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GridCellsConfig {
+    /// Overrides the archetype the converter was selected for.
+    output: Option<CellOutput>,
+
+    /// Radius of the points, or of the box outlines.
+    radius: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NavGridCellsToPoints3D {
+    output: CellOutput,
+    radius: Option<f32>,
+}
+
+impl NavGridCellsToPoints3D {
+    pub fn new(output: CellOutput) -> Self {
+        Self {
+            output,
+            radius: None,
+        }
+    }
+
+    fn convert(&self, msg: &GridCells) -> ConverterData {
+        let centers = msg.cells.iter().map(rerun::datatypes::Vec3D::from);
+        let data = match self.output {
+            CellOutput::Points => {
+                let points = rerun::Points3D::new(centers);
+                ConverterData::new(match self.radius {
+                    Some(radius) => points.with_radii([radius]),
+                    None => points,
+                })
+            }
+            CellOutput::Boxes => {
+                let half_size = [msg.cell_width / 2.0, msg.cell_height / 2.0, 0.0];
+                let boxes = rerun::Boxes3D::from_centers_and_half_sizes(
+                    centers,
+                    std::iter::repeat_n(half_size, msg.cells.len()),
+                );
+                ConverterData::new(match self.radius {
+                    Some(radius) => boxes.with_radii([radius]),
+                    None => boxes,
+                })
+            }
+        };
+        data.with_header(Header::from(&msg.header))
+    }
+}
+
+impl ConverterCfg for NavGridCellsToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        let config: GridCellsConfig = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if let Some(output) = config.output {
+            self.output = output;
+        }
+        self.radius = config.radius;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for NavGridCellsToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        match self.output {
+            CellOutput::Points => RerunName::RerunArchetype(rerun::Points3D::name()),
+            CellOutput::Boxes => RerunName::RerunArchetype(rerun::Boxes3D::name()),
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        "NavGridCellsToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(GridCells::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: GridCells =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_from_config() {
+        let mut converter = NavGridCellsToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { output = "boxes" }))
+            .expect("valid config");
+        assert_eq!(
+            converter.rerun_name(),
+            RerunName::RerunArchetype(rerun::Boxes3D::name())
+        );
+        assert!(
+            converter
+                .set_config(ConverterSettings(toml::toml! { output = "lines" }))
+                .is_err(),
+            "unknown output"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use rerun::Archetype as _;
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{
+        std_msgs::{self, Empty},
+        ROSMessage as _,
+    },
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Logs a tick of `1.0` for every `std_msgs/Empty`, so heartbeat topics
+/// show up as presence markers on the timeline.
+///
+/// The message has no stamp, so the tick is logged at the receive time.
+#[derive(Clone, Debug, Default)]
+pub struct StdEmptyToScalars {}
+
+impl ConverterCfg for StdEmptyToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("StdEmptyToScalars does not accept any configuration"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for StdEmptyToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "StdEmptyToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Empty::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        _msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        Ok(ConverterData::new(rerun::Scalars::new([1.0])))
+    }
+}
+
+/// Logs the delay in seconds between the stamp of a `std_msgs/Header`
+/// and the time it was received, at the time of the stamp.
+///
+/// Heartbeats show up as points on the timeline, and growing delays point
+/// to clock drift or a congested publisher.
+#[derive(Clone, Debug, Default)]
+pub struct StdHeaderToScalars {}
+
+/// Seconds from the stamp of `header` to the `received` time in nanoseconds.
+fn delay_secs(header: &std_msgs::Header, received: i64) -> f64 {
+    (received - header.stamp.as_nanos()) as f64 / 1e9
+}
+
+impl ConverterCfg for StdHeaderToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("StdHeaderToScalars does not accept any configuration"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for StdHeaderToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "StdHeaderToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(std_msgs::Header::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let received = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|elapsed| i64::try_from(elapsed.as_nanos()).ok())
+            .unwrap_or_default();
+        let header: std_msgs::Header =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        if header.stamp.is_zero() {
+            return Err(ConverterError::conversion(
+                self,
+                anyhow::anyhow!("header in frame '{}' has no stamp", header.frame_id),
+            ));
+        }
+        Ok(
+            ConverterData::new(rerun::Scalars::new([delay_secs(&header, received)]))
+                .with_header(Header::from(&header)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::builtin_interfaces::Time;
+
+    #[test]
+    fn header_delay() {
+        let header = std_msgs::Header {
+            stamp: Time {
+                sec: 10,
+                nanosec: 0,
+            },
+            frame_id: "base_link".to_owned(),
+        };
+        assert!((delay_secs(&header, 10_250_000_000) - 0.25).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use rerun::{datatypes::ChannelDatatype, Archetype as _, ColorModel};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{sensor_msgs::Image, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Color model and channel type of a `sensor_msgs/Image` encoding.
+fn image_format(encoding: &str) -> Option<(ColorModel, ChannelDatatype)> {
+    let format = match encoding {
+        "rgb8" => (ColorModel::RGB, ChannelDatatype::U8),
+        "bgr8" => (ColorModel::BGR, ChannelDatatype::U8),
+        "rgba8" => (ColorModel::RGBA, ChannelDatatype::U8),
+        "mono8" => (ColorModel::L, ChannelDatatype::U8),
+        "mono16" => (ColorModel::L, ChannelDatatype::U16),
+        _ => return None,
+    };
+    Some(format)
+}
+
+/// The pixel data of an image without row padding, in little endian.
+fn image_bytes(msg: &Image, channels: usize, bytes_per_channel: usize) -> anyhow::Result<Vec<u8>> {
+    let row_len = msg.width as usize * channels * bytes_per_channel;
+    let step = msg.step as usize;
+    let height = msg.height as usize;
+    if step < row_len {
+        anyhow::bail!("row step {step} is shorter than a row of {row_len} bytes");
+    }
+    let needed = step * height.saturating_sub(1) + row_len;
+    if height > 0 && msg.data.len() < needed {
+        anyhow::bail!(
+            "image data has {} bytes, expected {needed} for {}x{} {}",
+            msg.data.len(),
+            msg.width,
+            msg.height,
+            msg.encoding
+        );
+    }
+    let mut bytes = if step == row_len {
+        msg.data[..row_len * height].to_vec()
+    } else {
+        msg.data
+            .chunks(step)
+            .take(height)
+            .flat_map(|row| &row[..row_len])
+            .copied()
+            .collect()
+    };
+    if msg.is_bigendian != 0 && bytes_per_channel > 1 {
+        for channel in bytes.chunks_exact_mut(bytes_per_channel) {
+            channel.reverse();
+        }
+    }
+    Ok(bytes)
+}
+
+/// Converts raw camera frames to an image.
+#[derive(Clone, Debug, Default)]
+pub struct SensorImageToImage {}
+
+impl SensorImageToImage {
+    fn convert(&self, msg: &Image) -> Result<ConverterData, ConverterError> {
+        let (color_model, datatype) = image_format(&msg.encoding).ok_or_else(|| {
+            ConverterError::conversion(
+                self,
+                anyhow::anyhow!("unsupported image encoding '{}'", msg.encoding),
+            )
+        })?;
+        let bytes = image_bytes(msg, color_model.num_components(), datatype.bits() / 8)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let image = rerun::Image::from_color_model_and_bytes(
+            bytes,
+            [msg.width, msg.height],
+            color_model,
+            datatype,
+        );
+        Ok(ConverterData::new(image).with_header(Header::from(&msg.header)))
+    }
+}
+
+impl ConverterCfg for SensorImageToImage {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("SensorImageToImage does not accept any configuration"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorImageToImage {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Image::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorImageToImage"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Image::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: Image =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(&msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(encoding: &str, width: u32, step: u32, data: Vec<u8>) -> Image {
+        Image {
+            height: 2,
+            width,
+            encoding: encoding.to_owned(),
+            step,
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strips_row_padding() {
+        let msg = image("mono8", 2, 4, vec![1, 2, 0, 0, 3, 4, 0, 0]);
+        assert_eq!(image_bytes(&msg, 1, 1).expect("valid"), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn swaps_big_endian() {
+        let msg = Image {
+            is_bigendian: 1,
+            ..image("mono16", 1, 2, vec![0x01, 0x02, 0x03, 0x04])
+        };
+        assert_eq!(
+            image_bytes(&msg, 1, 2).expect("valid"),
+            vec![0x02, 0x01, 0x04, 0x03]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_images() {
+        assert!(
+            image_bytes(&image("rgb8", 2, 4, vec![0; 8]), 3, 1).is_err(),
+            "short step"
+        );
+        assert!(
+            image_bytes(&image("mono8", 2, 2, vec![0; 3]), 1, 1).is_err(),
+            "short data"
+        );
+        let converter = SensorImageToImage::default();
+        assert!(converter
+            .convert(&image("yuv422", 2, 4, vec![0; 8]))
+            .is_err());
+        assert!(converter.convert(&image("bgr8", 2, 6, vec![0; 12])).is_ok());
+    }
+}
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use rerun::{Archetype as _, AsComponents as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    converters::transform::OrientationFormat,
+    definitions::{sensor_msgs::Imu, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ImuConfig {
+    /// Draw the linear acceleration as an arrow from the IMU origin.
+    #[serde(default)]
+    show_acceleration: bool,
+
+    /// Convention of the orientation fields, defaults to `quat_xyzw`.
+    #[serde(default)]
+    orientation_format: OrientationFormat,
+}
+
+/// Converts IMU data into the rotation of the IMU frame.
+///
+/// The acceleration is measured in the IMU frame, which is the frame of the
+/// entity after the rotation, so its arrow turns with the IMU. IMUs that do
+/// not estimate their orientation log no rotation, see `Imu::has_orientation`.
+#[derive(Clone, Debug, Default)]
+pub struct SensorImuToTransform3D {
+    config: ImuConfig,
+}
+
+impl SensorImuToTransform3D {
+    /// Orientation of the IMU, if it estimates one.
+    fn rotation(&self, imu: &Imu) -> Option<rerun::Quaternion> {
+        imu.has_orientation()
+            .then(|| self.config.orientation_format.rotation(&imu.orientation))
+    }
+
+    fn convert(&self, imu: &Imu) -> ConverterData {
+        let mut batches = Vec::new();
+        if let Some(rotation) = self.rotation(imu) {
+            batches.extend(rerun::Transform3D::from_rotation(rotation).as_serialized_batches());
+        }
+        if self.config.show_acceleration {
+            batches.extend(
+                rerun::Arrows3D::from_vectors([rerun::datatypes::Vec3D::from(
+                    &imu.linear_acceleration,
+                )])
+                .with_labels(["linear_acceleration"])
+                .as_serialized_batches(),
+            );
+        }
+        ConverterData::new(batches).with_header(Header::from(&imu.header))
+    }
+}
+
+impl ConverterCfg for SensorImuToTransform3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorImuToTransform3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Transform3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorImuToTransform3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Imu::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let imu: Imu =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&imu))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::geometry_msgs::{Quaternion, Vector3};
+
+    fn has_component(data: &ConverterData, descriptor: &rerun::ComponentDescriptor) -> bool {
+        data.components
+            .as_serialized_batches()
+            .iter()
+            .any(|batch| batch.descriptor == *descriptor)
+    }
+
+    fn imu(orientation_covariance: f64) -> Imu {
+        Imu {
+            orientation: Quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: std::f64::consts::FRAC_1_SQRT_2,
+                w: std::f64::consts::FRAC_1_SQRT_2,
+            },
+            orientation_covariance: vec![orientation_covariance; 9],
+            linear_acceleration: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 9.81,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn skips_unknown_orientation() {
+        let converter = SensorImuToTransform3D {
+            config: ImuConfig {
+                show_acceleration: true,
+                ..Default::default()
+            },
+        };
+        let rotation = rerun::Transform3D::descriptor_quaternion();
+        let acceleration = rerun::Arrows3D::descriptor_vectors();
+        let data = converter.convert(&imu(0.0));
+        assert!(has_component(&data, &rotation) && has_component(&data, &acceleration));
+
+        let data = converter.convert(&imu(-1.0));
+        assert!(!has_component(&data, &rotation), "no orientation estimate");
+        assert!(has_component(&data, &acceleration));
+    }
+
+    #[test]
+    fn reads_orientation_format() {
+        // A quarter turn about z, with the scalar first
+        let mut wxyz = imu(0.0);
+        wxyz.orientation = Quaternion {
+            x: std::f64::consts::FRAC_1_SQRT_2,
+            y: 0.0,
+            z: 0.0,
+            w: std::f64::consts::FRAC_1_SQRT_2,
+        };
+        let mut converter = SensorImuToTransform3D::default();
+        converter
+            .set_config(ConverterSettings(
+                toml::toml! { orientation_format = "quat_wxyz" },
+            ))
+            .expect("valid config");
+        let expected = SensorImuToTransform3D::default()
+            .rotation(&imu(0.0))
+            .expect("has orientation");
+        let rotation = converter.rotation(&wxyz).expect("has orientation");
+        for (value, expected) in rotation.0.iter().zip(expected.0) {
+            assert!((value - expected).abs() < 1e-6, "{rotation:?}");
+        }
+    }
+}
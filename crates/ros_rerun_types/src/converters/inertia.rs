@@ -0,0 +1,177 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{
+        geometry_msgs::{Inertia, InertiaStamped},
+        std_msgs, ROSMessage,
+    },
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct InertiaConfig {
+    /// Log the table as static data whenever it changes,
+    /// instead of on the timeline for every message.
+    #[serde(rename = "static", default)]
+    is_static: bool,
+}
+
+/// ROS messages carrying the mass properties of a body.
+pub trait HasInertia: ROSMessage + Clone + std::fmt::Debug + Default {
+    fn header(&self) -> Option<&std_msgs::Header>;
+    fn inertia(&self) -> &Inertia;
+}
+
+impl HasInertia for Inertia {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        None
+    }
+    fn inertia(&self) -> &Inertia {
+        self
+    }
+}
+
+impl HasInertia for InertiaStamped {
+    fn header(&self) -> Option<&std_msgs::Header> {
+        Some(&self.header)
+    }
+    fn inertia(&self) -> &Inertia {
+        &self.inertia
+    }
+}
+
+/// Markdown tables of the mass, center of mass and inertia tensor.
+pub fn inertia_table(inertia: &Inertia) -> String {
+    let com = &inertia.com;
+    let mut table = vec![
+        "| Property | Value |".to_owned(),
+        "| --- | --- |".to_owned(),
+        format!("| Mass | {} kg |", inertia.m),
+        format!("| Center of mass | ({}, {}, {}) m |", com.x, com.y, com.z),
+        String::new(),
+        "| Inertia (kg m²) | x | y | z |".to_owned(),
+        "| --- | --- | --- | --- |".to_owned(),
+    ];
+    for (axis, row) in ["x", "y", "z"].iter().zip(inertia.tensor()) {
+        table.push(format!("| {axis} | {} | {} | {} |", row[0], row[1], row[2]));
+    }
+    table.join("\n")
+}
+
+/// Converts mass properties to a markdown text document.
+///
+/// Model data like this rarely changes, so it can be logged as static with
+/// the `static` setting, which only logs the table when it changes.
+#[derive(Clone, Debug, Default)]
+pub struct InertiaToTextDocument<T: HasInertia> {
+    config: InertiaConfig,
+    /// Last logged static table, per subscription.
+    last: Arc<Mutex<Option<String>>>,
+    _msg: PhantomData<fn() -> T>,
+}
+
+impl<T: HasInertia> InertiaToTextDocument<T> {
+    fn convert(&self, msg: &T) -> ConverterData {
+        let table = inertia_table(msg.inertia());
+        let header = msg.header().map(Header::from).unwrap_or_default();
+        if !self.config.is_static {
+            return ConverterData::new(rerun::TextDocument::from_markdown(table))
+                .with_header(header);
+        }
+        let data = ConverterData::static_only().with_header(header);
+        let mut last = self.last.lock();
+        if last.as_deref() == Some(table.as_str()) {
+            return data;
+        }
+        *last = Some(table.clone());
+        data.with_static(rerun::TextDocument::from_markdown(table))
+    }
+}
+
+impl<T: HasInertia> ConverterCfg for InertiaToTextDocument<T> {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.last = Arc::default();
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: HasInertia> Converter for InertiaToTextDocument<T> {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::TextDocument::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "InertiaToTextDocument"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(T::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: T = deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::geometry_msgs::Vector3;
+
+    fn inertia() -> Inertia {
+        Inertia {
+            m: 1.5,
+            com: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.1,
+            },
+            ixx: 0.2,
+            ixy: 0.01,
+            iyy: 0.3,
+            izz: 0.4,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn formats_table() {
+        let table = inertia_table(&inertia());
+        assert!(table.contains("| Mass | 1.5 kg |"));
+        assert!(table.contains("| Center of mass | (0, 0, 0.1) m |"));
+        assert!(table.contains("| x | 0.2 | 0.01 | 0 |"));
+        assert!(
+            table.contains("| y | 0.01 | 0.3 | 0 |"),
+            "tensor is symmetric"
+        );
+    }
+
+    #[test]
+    fn static_table_logged_on_change() {
+        let mut converter = InertiaToTextDocument::<Inertia>::default();
+        converter.config.is_static = true;
+        let msg = inertia();
+        assert!(converter.convert(&msg).static_components.is_some());
+        assert!(converter.convert(&msg).static_components.is_none());
+        let changed = Inertia { m: 2.0, ..msg };
+        assert!(converter.convert(&changed).static_components.is_some());
+    }
+}
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{sensor_msgs::LaserScan, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LaserScanConfig {
+    /// Readings beyond this range in meters are not shown,
+    /// in addition to those beyond the `range_max` of the scan.
+    range_limit: Option<f32>,
+
+    /// Radius of the logged points.
+    radius: Option<f32>,
+}
+
+/// Converts a planar laser scan into points in the plane of the scanner.
+#[derive(Clone, Debug, Default)]
+pub struct SensorLaserScanToPoints3D {
+    config: LaserScanConfig,
+}
+
+impl SensorLaserScanToPoints3D {
+    /// Project the valid readings of a scan into points in the scanner frame.
+    ///
+    /// Reading `i` is at the angle `angle_min + i * angle_increment` around
+    /// the z axis. Readings outside `[range_min, range_max]`, beyond the
+    /// `range_limit`, or not finite are skipped.
+    fn points(&self, scan: &LaserScan) -> Vec<[f32; 3]> {
+        let max = self
+            .config
+            .range_limit
+            .map_or(scan.range_max, |limit| limit.min(scan.range_max));
+        scan.ranges
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| range.is_finite() && **range >= scan.range_min && **range <= max)
+            .map(|(i, range)| {
+                let angle = scan.angle_min + i as f32 * scan.angle_increment;
+                [range * angle.cos(), range * angle.sin(), 0.0]
+            })
+            .collect()
+    }
+
+    fn convert(&self, scan: &LaserScan) -> ConverterData {
+        let points = rerun::Points3D::new(self.points(scan));
+        ConverterData::new(match self.config.radius {
+            Some(radius) => points.with_radii([radius]),
+            None => points,
+        })
+        .with_header(Header::from(&scan.header))
+    }
+}
+
+impl ConverterCfg for SensorLaserScanToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if self
+            .config
+            .range_limit
+            .is_some_and(|limit| !(limit.is_finite() && limit > 0.0))
+        {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'range_limit' must be a positive number"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorLaserScanToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Points3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorLaserScanToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(LaserScan::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let scan: LaserScan =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&scan))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn projects_ranges() {
+        let scan = LaserScan {
+            angle_min: -FRAC_PI_2,
+            angle_max: FRAC_PI_2,
+            angle_increment: FRAC_PI_2,
+            range_min: 0.1,
+            range_max: 10.0,
+            ranges: vec![1.0, 2.0, 3.0, f32::NAN, 0.05, 20.0],
+            ..Default::default()
+        };
+        let mut converter = SensorLaserScanToPoints3D::default();
+        let points = converter.points(&scan);
+        assert_eq!(
+            points.len(),
+            3,
+            "invalid and out of range readings are skipped"
+        );
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5);
+        assert!(close(points[0], [0.0, -1.0, 0.0]), "right");
+        assert!(close(points[1], [2.0, 0.0, 0.0]), "ahead");
+        assert!(close(points[2], [0.0, 3.0, 0.0]), "left");
+
+        converter
+            .set_config(ConverterSettings(toml::toml! { range_limit = 2.5 }))
+            .expect("valid config");
+        assert_eq!(converter.points(&scan).len(), 2);
+    }
+}
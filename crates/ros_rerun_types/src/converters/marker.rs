@@ -0,0 +1,256 @@
+use async_trait::async_trait;
+use rerun::{external::glam, Archetype as _, ArchetypeName, AsComponents as _};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{
+        geometry_msgs::{Point, Pose},
+        visualization_msgs::Marker,
+        ROSMessage as _,
+    },
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Converts RViz markers into the archetype matching their `type`.
+///
+/// Each marker is logged on the entity `<ns>/<id>` below the topic, so
+/// markers replace earlier ones with the same namespace and ID. `DELETE`
+/// clears the entity of a marker, `DELETEALL` the entities of all markers.
+///
+/// The converter is registered for every archetype markers are drawn as,
+/// so a marker topic can be configured with any of them. The archetype
+/// of each marker still follows its type.
+#[derive(Clone, Debug)]
+pub struct VisualizationMarkerToArchetypes {
+    archetype: ArchetypeName,
+}
+
+impl Default for VisualizationMarkerToArchetypes {
+    fn default() -> Self {
+        Self::new(rerun::Points3D::name())
+    }
+}
+
+impl VisualizationMarkerToArchetypes {
+    /// Converter registered for `archetype`.
+    pub fn new(archetype: ArchetypeName) -> Self {
+        Self { archetype }
+    }
+
+    fn convert(&self, marker: &Marker) -> Result<ConverterData, ConverterError> {
+        let header = Header::from(&marker.header);
+        let entity = match marker.action {
+            Marker::DELETE => EntityData::new(entity_path(marker), rerun::Clear::flat()),
+            Marker::DELETEALL => EntityData::new("", rerun::Clear::recursive()),
+            _ => EntityData::new(entity_path(marker), self.archetype_batches(marker)?),
+        };
+        Ok(ConverterData::batch(vec![entity.with_header(header.clone())]).with_header(header))
+    }
+
+    /// Components of the archetype that draws a marker of the given type.
+    fn archetype_batches(
+        &self,
+        marker: &Marker,
+    ) -> Result<Vec<rerun::SerializedComponentBatch>, ConverterError> {
+        let positions = marker
+            .points
+            .iter()
+            .map(|point| pose_point(&marker.pose, point));
+        let colors = marker_colors(marker);
+        // Points and lines are as wide as `scale.x`.
+        let radius = (marker.scale.x / 2.0) as f32;
+        let batches = match marker.marker_type {
+            Marker::POINTS => rerun::Points3D::new(positions)
+                .with_colors(colors)
+                .with_radii([radius])
+                .as_serialized_batches(),
+            Marker::LINE_STRIP => rerun::LineStrips3D::new([positions.collect::<Vec<_>>()])
+                .with_colors(colors.into_iter().take(1))
+                .with_radii([radius])
+                .as_serialized_batches(),
+            // TODO: CUBE and SPHERE as Boxes3D/Ellipsoids3D, LINE_LIST as LineStrips3D,
+            // and TEXT_VIEW_FACING as a label.
+            Marker::ARROW
+            | Marker::CUBE
+            | Marker::SPHERE
+            | Marker::CYLINDER
+            | Marker::LINE_LIST
+            | Marker::CUBE_LIST
+            | Marker::SPHERE_LIST
+            | Marker::TEXT_VIEW_FACING
+            | Marker::MESH_RESOURCE
+            | Marker::TRIANGLE_LIST => {
+                return Err(ConverterError::conversion(
+                    self,
+                    anyhow::anyhow!("marker type {} is not supported yet", marker.marker_type),
+                ))
+            }
+            other => {
+                return Err(ConverterError::conversion(
+                    self,
+                    anyhow::anyhow!("unknown marker type {other}"),
+                ))
+            }
+        };
+        Ok(batches)
+    }
+}
+
+/// Entity path of a marker relative to its topic, `<ns>/<id>`.
+fn entity_path(marker: &Marker) -> String {
+    let parts: Vec<_> = marker
+        .ns
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .map(rerun::EntityPathPart::new)
+        .chain([rerun::EntityPathPart::new(marker.id.to_string())])
+        .collect();
+    let path = rerun::EntityPath::from(parts).to_string();
+    path.trim_start_matches('/').to_owned()
+}
+
+/// A point of a marker in the frame of its header, placed by the marker's `pose`.
+fn pose_point(pose: &Pose, point: &Point) -> [f32; 3] {
+    let rotation = pose.orientation.rotation();
+    let translation = glam::DVec3::new(pose.position.x, pose.position.y, pose.position.z);
+    let point = rotation * glam::DVec3::new(point.x, point.y, point.z) + translation;
+    point.as_vec3().to_array()
+}
+
+/// Per point colors, if there is one for every point, or else the marker color.
+fn marker_colors(marker: &Marker) -> Vec<rerun::Color> {
+    if !marker.colors.is_empty() && marker.colors.len() == marker.points.len() {
+        marker.colors.iter().map(rerun::Color::from).collect()
+    } else {
+        vec![rerun::Color::from(&marker.color)]
+    }
+}
+
+impl ConverterCfg for VisualizationMarkerToArchetypes {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!(
+                    "VisualizationMarkerToArchetypes does not accept any configuration"
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for VisualizationMarkerToArchetypes {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(self.archetype)
+    }
+
+    fn id(&self) -> &'static str {
+        "VisualizationMarkerToArchetypes"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Marker::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let marker: Marker =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(&marker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{geometry_msgs::Quaternion, std_msgs::ColorRGBA};
+
+    fn marker(marker_type: i32) -> Marker {
+        Marker {
+            ns: "planner/path".to_owned(),
+            id: 3,
+            marker_type,
+            pose: Pose {
+                position: Point {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                // A quarter turn around z
+                orientation: Quaternion {
+                    x: 0.0,
+                    y: 0.0,
+                    z: std::f64::consts::FRAC_1_SQRT_2,
+                    w: std::f64::consts::FRAC_1_SQRT_2,
+                },
+            },
+            points: vec![
+                Point::default(),
+                Point {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+            ],
+            color: ColorRGBA {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_points_and_line_strips() {
+        let converter = VisualizationMarkerToArchetypes::default();
+        let points = marker(Marker::POINTS);
+        assert_eq!(entity_path(&points), "planner/path/3");
+        let moved = pose_point(&points.pose, &points.points[1]);
+        for (value, expected) in moved.iter().zip([1.0, 1.0, 0.0]) {
+            assert!((value - expected).abs() < 1e-6, "{moved:?}");
+        }
+        assert_eq!(
+            marker_colors(&points),
+            [rerun::Color::from_rgb(255, 0, 0)],
+            "marker color without per point colors"
+        );
+
+        let data = converter.convert(&points).expect("points are supported");
+        assert_eq!(data.batch.len(), 1);
+        assert_eq!(data.batch[0].entity_path, "planner/path/3");
+        let data = converter
+            .convert(&marker(Marker::LINE_STRIP))
+            .expect("line strips are supported");
+        assert_eq!(data.batch.len(), 1);
+        assert!(converter.convert(&marker(Marker::CUBE)).is_err());
+
+        let delete_all = Marker {
+            action: Marker::DELETEALL,
+            ..marker(Marker::CUBE)
+        };
+        let data = converter.convert(&delete_all).expect("clears all markers");
+        assert_eq!(data.batch[0].entity_path, "", "the topic's entity");
+    }
+
+    #[test]
+    fn zero_orientation_is_identity() {
+        let mut points = marker(Marker::POINTS);
+        points.pose.orientation = Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        assert_eq!(pose_point(&points.pose, &points.points[1]), [2.0, 0.0, 0.0]);
+    }
+}
@@ -1 +1,25 @@
+pub mod battery;
+pub mod geometry;
+pub mod grid_cells;
+pub mod heartbeat;
+pub mod image;
+pub mod imu;
+pub mod inertia;
+pub mod laser_scan;
+pub mod marker;
+pub mod occupancy_grid;
+pub mod odometry;
+pub mod path;
+pub mod point_cloud;
+pub mod pose_array;
+pub mod radar;
+pub mod range;
+pub mod roi;
+pub mod scalar_sensor;
 pub mod text;
+pub mod time_reference;
+pub mod trajectory;
+pub mod transform;
+#[cfg(feature = "video")]
+pub mod video;
+pub mod vision;
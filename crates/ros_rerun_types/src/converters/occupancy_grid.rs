@@ -0,0 +1,511 @@
+use std::sync::{Arc, LazyLock};
+
+use ahash::{HashMap, HashMapExt as _};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::{external::glam, Archetype as _, AsComponents as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{
+        builtin_interfaces::Time, geometry_msgs::Pose, map_msgs::OccupancyGridUpdate,
+        nav_msgs::OccupancyGrid, ROSMessage as _,
+    },
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Latest full grid received on each topic.
+///
+/// Grid updates only carry a patch, so the update converters
+/// need the full grid from another subscription to apply it to.
+/// Keyed by topic, as several grids often share the same frame.
+static GRIDS: LazyLock<Mutex<HashMap<String, Arc<OccupancyGrid>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Archetype that occupancy grids are logged as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridOutput {
+    /// Grayscale image, free cells are white and occupied cells black.
+    #[default]
+    Image,
+    /// Segmentation image with the occupancy (0-100) as class id,
+    /// unknown cells use class id 255.
+    SegmentationImage,
+}
+
+impl GridOutput {
+    fn rerun_name(self) -> RerunName {
+        match self {
+            Self::Image => RerunName::RerunArchetype(rerun::Image::name()),
+            Self::SegmentationImage => RerunName::RerunArchetype(rerun::SegmentationImage::name()),
+        }
+    }
+
+    /// Render the grid, flipping rows so that the image is a top down view.
+    fn render(self, grid: &OccupancyGrid) -> Result<ConverterData, anyhow::Error> {
+        let (width, height) = (grid.info.width, grid.info.height);
+        let cells = width as usize * height as usize;
+        if grid.data.len() != cells {
+            anyhow::bail!(
+                "grid data has {} cells, expected {width}x{height}",
+                grid.data.len()
+            );
+        }
+        let pixel = |value: i8| match (self, u8::try_from(value)) {
+            (Self::Image, Ok(value)) => 255 - (u16::from(value.min(100)) * 255 / 100) as u8,
+            (Self::Image, Err(_)) => 128,
+            (Self::SegmentationImage, Ok(value)) => value,
+            (Self::SegmentationImage, Err(_)) => 255,
+        };
+        let pixels = if width == 0 {
+            Vec::new()
+        } else {
+            grid.data
+                .chunks_exact(width as usize)
+                .rev()
+                .flat_map(|row| row.iter().map(|value| pixel(*value)))
+                .collect::<Vec<_>>()
+        };
+        let mut batches = match self {
+            Self::Image => {
+                rerun::Image::from_elements(&pixels, [width, height], rerun::ColorModel::L)
+                    .as_serialized_batches()
+            }
+            Self::SegmentationImage => rerun::SegmentationImage::new(
+                pixels,
+                rerun::datatypes::ImageFormat::segmentation(
+                    [width, height],
+                    rerun::datatypes::ChannelDatatype::U8,
+                ),
+            )
+            .as_serialized_batches(),
+        };
+        batches.extend(placement(grid));
+        Ok(ConverterData::new(batches).with_header(Header::from(&grid.header)))
+    }
+}
+
+/// The pose of a camera one meter above the grid origin, looking down.
+fn camera_pose(origin: &Pose) -> (glam::DVec3, glam::DQuat) {
+    let rotation = origin.orientation.rotation();
+    let position = glam::DVec3::new(origin.position.x, origin.position.y, origin.position.z);
+    // Looking down, the camera's right is the map's x and its down the map's -y
+    let camera_rotation = rotation * glam::DQuat::from_rotation_x(std::f64::consts::PI);
+    (position + rotation * glam::DVec3::Z, camera_rotation)
+}
+
+/// Pinhole and transform that place the rendered grid in 3D, with each cell
+/// `info.resolution` wide and the first cell at `info.origin`.
+///
+/// The image is projected by a camera looking down at the map from one meter
+/// above the origin, with a focal length that makes a pixel one cell wide.
+/// Empty grids and grids without a valid resolution are not placed.
+fn placement(grid: &OccupancyGrid) -> Vec<rerun::SerializedComponentBatch> {
+    let (width, height) = (grid.info.width, grid.info.height);
+    let resolution = grid.info.resolution;
+    if width == 0 || height == 0 || !(resolution.is_finite() && resolution > 0.0) {
+        return Vec::new();
+    }
+    let (camera_position, camera_rotation) = camera_pose(&grid.info.origin);
+    let focal_length = 1.0 / resolution;
+    let mut batches = rerun::Transform3D::from_translation_rotation(
+        camera_position.as_vec3().to_array(),
+        rerun::Quaternion::from_xyzw(camera_rotation.as_quat().to_array()),
+    )
+    .as_serialized_batches();
+    batches.extend(
+        rerun::Pinhole::from_focal_length_and_resolution(
+            [focal_length, focal_length],
+            [width as f32, height as f32],
+        )
+        // The bottom left of the top down image is at the origin
+        .with_principal_point([0.0, height as f32])
+        .with_image_plane_distance(1.0)
+        .as_serialized_batches(),
+    );
+    batches
+}
+
+/// Copy the cells of `update` into `grid`.
+///
+/// Parts of the patch that fall outside of the grid are skipped.
+/// Returns the number of cells written.
+///
+/// # Errors
+/// Returns an error if the patch size does not match its data.
+pub fn apply_update(
+    grid: &mut OccupancyGrid,
+    update: &OccupancyGridUpdate,
+) -> Result<usize, anyhow::Error> {
+    let (update_width, update_height) = (update.width as usize, update.height as usize);
+    if update.data.len() != update_width * update_height {
+        anyhow::bail!(
+            "update data has {} cells, expected {update_width}x{update_height}",
+            update.data.len()
+        );
+    }
+    let (grid_width, grid_height) = (i64::from(grid.info.width), i64::from(grid.info.height));
+    let mut written = 0;
+    for (row, cells) in update.data.chunks_exact(update_width.max(1)).enumerate() {
+        let grid_y = i64::from(update.y) + row as i64;
+        if !(0..grid_height).contains(&grid_y) {
+            continue;
+        }
+        for (col, value) in cells.iter().enumerate() {
+            let grid_x = i64::from(update.x) + col as i64;
+            if !(0..grid_width).contains(&grid_x) {
+                continue;
+            }
+            let index = usize::try_from(grid_y * grid_width + grid_x)?;
+            if let Some(cell) = grid.data.get_mut(index) {
+                *cell = *value;
+                written += 1;
+            }
+        }
+    }
+    Ok(written)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NavOccupancyGridToImage {
+    output: GridOutput,
+    topic: String,
+}
+
+impl NavOccupancyGridToImage {
+    pub fn new(output: GridOutput) -> Self {
+        Self {
+            output,
+            topic: String::new(),
+        }
+    }
+
+    /// Render the grid and keep it for the updates of this topic.
+    fn convert(&self, grid: OccupancyGrid) -> Result<ConverterData, anyhow::Error> {
+        let data = self.output.render(&grid)?;
+        GRIDS.lock().insert(self.topic.clone(), Arc::new(grid));
+        Ok(data)
+    }
+}
+
+impl ConverterCfg for NavOccupancyGridToImage {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if config.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("OccupancyGrid converters do not accept any configuration"),
+            ))
+        }
+    }
+
+    fn set_topic(&mut self, topic: &str, _entity_path: &str) {
+        topic.clone_into(&mut self.topic);
+    }
+}
+
+#[async_trait]
+impl Converter for NavOccupancyGridToImage {
+    fn rerun_name(&self) -> RerunName {
+        self.output.rerun_name()
+    }
+
+    fn id(&self) -> &'static str {
+        "NavOccupancyGridToImage"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(OccupancyGrid::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let grid: OccupancyGrid =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(grid)
+            .map_err(|err| ConverterError::conversion(self, err))
+    }
+}
+
+/// The full grid an update subscription applies its patches to.
+#[derive(Debug)]
+struct PatchedGrid {
+    /// Stamp of the full grid the patches were applied to.
+    base_stamp: Time,
+    grid: OccupancyGrid,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GridUpdateConfig {
+    /// Topic of the full grid the updates apply to.
+    ///
+    /// Defaults to the update topic without its `_updates` suffix,
+    /// e.g. `/costmap` for `/costmap_updates`.
+    #[serde(default)]
+    grid_topic: Option<String>,
+}
+
+/// Applies `OccupancyGridUpdate` patches to the last full grid of its grid topic.
+///
+/// The full grid must be subscribed to with a `nav_msgs/OccupancyGrid`
+/// converter, updates are dropped until it has been received.
+#[derive(Clone, Debug, Default)]
+pub struct MapOccupancyGridUpdateToImage {
+    output: GridOutput,
+    config: GridUpdateConfig,
+    topic: String,
+    state: Arc<Mutex<Option<PatchedGrid>>>,
+}
+
+impl MapOccupancyGridUpdateToImage {
+    pub fn new(output: GridOutput) -> Self {
+        Self {
+            output,
+            ..Default::default()
+        }
+    }
+
+    /// Topic of the full grid that the updates are applied to.
+    fn grid_topic(&self) -> &str {
+        self.config
+            .grid_topic
+            .as_deref()
+            .unwrap_or_else(|| self.topic.strip_suffix("_updates").unwrap_or(&self.topic))
+    }
+
+    fn convert(&self, update: &OccupancyGridUpdate) -> Result<ConverterData, anyhow::Error> {
+        let topic = self.grid_topic();
+        let full_grid = GRIDS.lock().get(topic).cloned().ok_or_else(|| {
+            anyhow::anyhow!("no full OccupancyGrid received on topic '{topic}' yet")
+        })?;
+
+        let mut state = self.state.lock();
+        // Start over from the full grid whenever a new one arrives.
+        if state
+            .as_ref()
+            .is_some_and(|patched| patched.base_stamp != full_grid.header.stamp)
+        {
+            *state = None;
+        }
+        let patched = state.get_or_insert_with(|| PatchedGrid {
+            base_stamp: full_grid.header.stamp,
+            grid: (*full_grid).clone(),
+        });
+        if apply_update(&mut patched.grid, update)? == 0 && !update.data.is_empty() {
+            anyhow::bail!(
+                "update at ({}, {}) is outside of the {}x{} grid",
+                update.x,
+                update.y,
+                patched.grid.info.width,
+                patched.grid.info.height
+            );
+        }
+        patched.grid.header.stamp = update.header.stamp;
+        self.output.render(&patched.grid)
+    }
+}
+
+impl ConverterCfg for MapOccupancyGridUpdateToImage {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        // Every subscription gets its own patched grid.
+        self.state = Arc::default();
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+
+    fn set_topic(&mut self, topic: &str, _entity_path: &str) {
+        topic.clone_into(&mut self.topic);
+    }
+}
+
+#[async_trait]
+impl Converter for MapOccupancyGridUpdateToImage {
+    fn rerun_name(&self) -> RerunName {
+        self.output.rerun_name()
+    }
+
+    fn id(&self) -> &'static str {
+        "MapOccupancyGridUpdateToImage"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(OccupancyGridUpdate::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let update: OccupancyGridUpdate =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(&update)
+            .map_err(|err| ConverterError::conversion(self, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::geometry_msgs::Quaternion;
+
+    fn grid(width: u32, height: u32) -> OccupancyGrid {
+        let mut grid = OccupancyGrid {
+            data: vec![0; (width * height) as usize],
+            ..Default::default()
+        };
+        grid.info.width = width;
+        grid.info.height = height;
+        grid
+    }
+
+    #[test]
+    fn grid_is_placed_at_origin() {
+        let mut placed = grid(4, 3);
+        placed.info.resolution = 0.05;
+        let components: Vec<_> = placement(&placed)
+            .iter()
+            .map(|batch| batch.descriptor.component.to_string())
+            .collect();
+        assert!(components.contains(&"Transform3D:translation".to_owned()));
+        assert!(components.contains(&"Pinhole:image_from_camera".to_owned()));
+        assert!(
+            placement(&grid(4, 3)).is_empty(),
+            "no resolution to place the grid with"
+        );
+
+        let data = GridOutput::Image.render(&placed).expect("valid grid");
+        assert!(data.batch.is_empty());
+    }
+
+    #[test]
+    fn zero_origin_orientation_is_identity() {
+        let mut placed = grid(4, 3);
+        placed.info.origin.orientation = Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        let (position, rotation) = camera_pose(&placed.info.origin);
+        assert_eq!(position, glam::DVec3::Z);
+        assert!(rotation.is_finite());
+    }
+
+    #[test]
+    fn update_is_applied() {
+        let mut grid = grid(4, 3);
+        let update = OccupancyGridUpdate {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2,
+            data: vec![10, 20, 30, 40],
+            ..Default::default()
+        };
+        assert_eq!(apply_update(&mut grid, &update).ok(), Some(4));
+        assert_eq!(
+            grid.data,
+            vec![0, 0, 0, 0, 0, 10, 20, 0, 0, 30, 40, 0],
+            "patch is copied row by row"
+        );
+    }
+
+    #[test]
+    fn update_is_clipped() {
+        let mut grid = grid(2, 2);
+        let update = OccupancyGridUpdate {
+            x: -1,
+            y: 1,
+            width: 2,
+            height: 2,
+            data: vec![1, 2, 3, 4],
+            ..Default::default()
+        };
+        assert_eq!(apply_update(&mut grid, &update).ok(), Some(1));
+        assert_eq!(grid.data, vec![0, 0, 2, 0]);
+    }
+
+    #[test]
+    fn updates_apply_to_grid_of_their_topic() {
+        let converter = |topic: &str| {
+            let mut converter = NavOccupancyGridToImage::new(GridOutput::Image);
+            converter.set_topic(topic, topic);
+            converter
+        };
+        let update_converter = |topic: &str| {
+            let mut converter = MapOccupancyGridUpdateToImage::new(GridOutput::Image);
+            converter.set_topic(topic, topic);
+            converter
+        };
+        // Both grids are in the same frame
+        let mut local = grid(2, 1);
+        local.header.frame_id = "map".to_owned();
+        let mut global = grid(3, 1);
+        global.header.frame_id = "map".to_owned();
+        converter("/local/costmap")
+            .convert(local)
+            .expect("valid grid");
+        converter("/global/costmap")
+            .convert(global)
+            .expect("valid grid");
+
+        let update = OccupancyGridUpdate {
+            width: 1,
+            height: 1,
+            data: vec![100],
+            ..Default::default()
+        };
+        let local_updates = update_converter("/local/costmap_updates");
+        let global_updates = update_converter("/global/costmap_updates");
+        local_updates.convert(&update).expect("local grid received");
+        global_updates
+            .convert(&update)
+            .expect("global grid received");
+        let patched = |converter: &MapOccupancyGridUpdateToImage| {
+            converter
+                .state
+                .lock()
+                .as_ref()
+                .map(|patched| patched.grid.data.clone())
+        };
+        assert_eq!(patched(&local_updates), Some(vec![100, 0]));
+        assert_eq!(patched(&global_updates), Some(vec![100, 0, 0]));
+
+        let mut configured = MapOccupancyGridUpdateToImage::new(GridOutput::Image);
+        configured
+            .set_config(ConverterSettings(
+                toml::toml! { grid_topic = "/global/costmap" },
+            ))
+            .expect("valid config");
+        configured.set_topic("/updates", "/updates");
+        assert_eq!(configured.grid_topic(), "/global/costmap");
+        assert!(
+            update_converter("/unknown_updates")
+                .convert(&update)
+                .is_err(),
+            "no grid received on /unknown"
+        );
+    }
+
+    #[test]
+    fn update_size_mismatch() {
+        let mut grid = grid(2, 2);
+        let update = OccupancyGridUpdate {
+            width: 2,
+            height: 2,
+            data: vec![1],
+            ..Default::default()
+        };
+        assert!(apply_update(&mut grid, &update).is_err(), "data too short");
+    }
+}
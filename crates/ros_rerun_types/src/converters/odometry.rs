@@ -0,0 +1,210 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::{
+    external::{glam, re_log::warn_once},
+    Archetype as _, AsComponents as _,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    converters::transform::OrientationFormat,
+    covariance::{ellipsoid, linear_block},
+    definitions::{geometry_msgs::Pose, nav_msgs::Odometry, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OdometryConfig {
+    /// Draw the linear velocity covariance as an ellipsoid at the robot origin.
+    ///
+    /// The ellipsoid has units of m/s drawn as meters, so it shows the shape
+    /// and orientation of the uncertainty rather than a region in space.
+    #[serde(default)]
+    show_twist_covariance: bool,
+
+    /// Standard deviations covered by the covariance ellipsoid, defaults to 1.
+    sigma: Option<f32>,
+
+    /// Draw the past positions of the robot as a breadcrumb trail of points.
+    #[serde(default)]
+    trail: bool,
+
+    /// Number of positions kept in the trail, defaults to `DEFAULT_TRAIL_LENGTH`.
+    trail_length: Option<usize>,
+
+    /// Convention of the orientation fields, defaults to `quat_xyzw`.
+    #[serde(default)]
+    orientation_format: OrientationFormat,
+}
+
+/// Default number of positions kept in an odometry trail.
+pub const DEFAULT_TRAIL_LENGTH: usize = 1000;
+
+/// Past positions of the robot in the odometry frame, oldest first.
+#[derive(Debug, Default)]
+struct Trail {
+    positions: VecDeque<glam::Vec3>,
+    orientation_format: OrientationFormat,
+}
+
+impl Trail {
+    /// Add the position of `pose` and return the trail in the frame of `pose`.
+    ///
+    /// The points are logged on the entity of the robot, after its transform,
+    /// so they are moved into the robot frame to stay in place in the world.
+    fn update(&mut self, pose: &Pose, max_len: usize) -> Vec<rerun::Position3D> {
+        let position = &pose.position;
+        let translation = glam::Vec3::new(position.x as f32, position.y as f32, position.z as f32);
+        let rotation = self
+            .orientation_format
+            .quaternion(&pose.orientation)
+            .as_quat()
+            .normalize();
+        if self.positions.len() >= max_len {
+            self.positions.pop_front();
+        }
+        self.positions.push_back(translation);
+        let to_robot = rotation.inverse();
+        self.positions
+            .iter()
+            .map(|p| to_robot * (*p - translation))
+            .filter(|p| p.is_finite())
+            .map(|p| rerun::Position3D::new(p.x, p.y, p.z))
+            .collect()
+    }
+}
+
+/// Converts odometry into the transform of the robot in the odometry frame.
+///
+/// The twist is given in the child frame, which is the frame of the entity
+/// after the transform, so the covariance ellipsoid moves with the robot.
+#[derive(Clone, Debug, Default)]
+pub struct NavOdometryToTransform3D {
+    config: OdometryConfig,
+    /// Trail of positions, per subscription.
+    trail: Arc<Mutex<Trail>>,
+}
+
+impl NavOdometryToTransform3D {
+    fn convert(&self, odometry: &Odometry) -> ConverterData {
+        let pose = &odometry.pose.pose;
+        let mut batches = rerun::Transform3D::from_translation_rotation(
+            rerun::datatypes::Vec3D::from(&pose.position),
+            self.config.orientation_format.rotation(&pose.orientation),
+        )
+        .as_serialized_batches();
+        if self.config.show_twist_covariance {
+            let sigma = self.config.sigma.unwrap_or(1.0);
+            match linear_block(&odometry.twist.covariance)
+                .and_then(|covariance| ellipsoid(covariance, sigma))
+            {
+                Some((half_sizes, rotation)) => batches.extend(
+                    rerun::Ellipsoids3D::from_half_sizes([half_sizes])
+                        .with_quaternions([rotation])
+                        .as_serialized_batches(),
+                ),
+                None => warn_once!(
+                    "Odometry in frame '{}' has an invalid twist covariance",
+                    odometry.header.frame_id
+                ),
+            }
+        }
+        if self.config.trail {
+            let max_len = self.config.trail_length.unwrap_or(DEFAULT_TRAIL_LENGTH);
+            let points = self.trail.lock().update(pose, max_len);
+            batches.extend(rerun::Points3D::new(points).as_serialized_batches());
+        }
+        ConverterData::new(batches).with_header(Header::from(&odometry.header))
+    }
+}
+
+impl ConverterCfg for NavOdometryToTransform3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        self.trail = Arc::new(Mutex::new(Trail {
+            orientation_format: self.config.orientation_format,
+            ..Default::default()
+        }));
+        if self
+            .config
+            .sigma
+            .is_some_and(|sigma| !(sigma.is_finite() && sigma > 0.0))
+        {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'sigma' must be a positive number"),
+            ));
+        }
+        if self.config.trail_length == Some(0) {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'trail_length' must be at least 1"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for NavOdometryToTransform3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Transform3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "NavOdometryToTransform3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Odometry::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let odometry: Odometry =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&odometry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::geometry_msgs::{Point, Quaternion};
+
+    fn pose(x: f64, yaw: f64) -> Pose {
+        Pose {
+            position: Point { x, y: 0.0, z: 0.0 },
+            orientation: Quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: (yaw / 2.0).sin(),
+                w: (yaw / 2.0).cos(),
+            },
+        }
+    }
+
+    #[test]
+    fn trail_stays_in_place() {
+        let mut trail = Trail::default();
+        trail.update(&pose(1.0, 0.0), 2);
+        trail.update(&pose(2.0, 0.0), 2);
+        // After turning left at x = 3, the start of the trail is on the left of the robot
+        let points = trail.update(&pose(3.0, std::f64::consts::FRAC_PI_2), 2);
+        assert_eq!(points.len(), 2, "trail is capped");
+        let first = points[0];
+        assert!(first.x().abs() < 1e-5 && (first.y() - 1.0).abs() < 1e-5);
+        assert!(points[1].x().abs() < 1e-6 && points[1].y().abs() < 1e-6);
+    }
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use rerun::{Archetype as _, AsComponents as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{nav_msgs::Path, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PathConfig {
+    /// Also log the waypoints of the path as points.
+    #[serde(default)]
+    with_points: bool,
+}
+
+/// Converts a path, e.g. a planned one, into a single line strip through
+/// the positions of its poses, in the frame of the path's header.
+///
+/// The headers of the individual poses are ignored. An empty path logs
+/// an empty strip, which hides the previous path.
+#[derive(Clone, Debug, Default)]
+pub struct NavPathToLineStrips3D {
+    config: PathConfig,
+}
+
+impl NavPathToLineStrips3D {
+    fn convert(&self, path: &Path) -> ConverterData {
+        let positions: Vec<_> = path
+            .poses
+            .iter()
+            .map(|pose| rerun::datatypes::Vec3D::from(&pose.pose.position))
+            .collect();
+        let mut batches = if self.config.with_points {
+            rerun::Points3D::new(positions.iter().copied()).as_serialized_batches()
+        } else {
+            Vec::new()
+        };
+        batches.extend(rerun::LineStrips3D::new([positions]).as_serialized_batches());
+        ConverterData::new(batches).with_header(Header::from(&path.header))
+    }
+}
+
+impl ConverterCfg for NavPathToLineStrips3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for NavPathToLineStrips3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::LineStrips3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "NavPathToLineStrips3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Path::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let path: Path =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::geometry_msgs::{Point, PoseStamped};
+
+    /// Components logged for a path, e.g. `LineStrips3D:strips`.
+    fn components(data: &ConverterData) -> Vec<String> {
+        data.components
+            .as_serialized_batches()
+            .iter()
+            .map(|batch| batch.descriptor.component.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn path_to_strip() {
+        let waypoint = |x| {
+            let mut pose = PoseStamped::default();
+            pose.pose.position = Point { x, y: 0.0, z: 0.0 };
+            pose
+        };
+        let path = Path {
+            poses: vec![waypoint(0.0), waypoint(1.0), waypoint(2.0)],
+            ..Default::default()
+        };
+        let mut converter = NavPathToLineStrips3D::default();
+        assert_eq!(
+            components(&converter.convert(&path)),
+            ["LineStrips3D:strips"]
+        );
+
+        converter
+            .set_config(ConverterSettings(toml::toml! { with_points = true }))
+            .expect("valid config");
+        assert_eq!(
+            components(&converter.convert(&path)),
+            ["Points3D:positions", "LineStrips3D:strips"]
+        );
+        assert!(
+            components(&converter.convert(&Path::default()))
+                .contains(&"LineStrips3D:strips".to_owned()),
+            "an empty strip"
+        );
+    }
+}
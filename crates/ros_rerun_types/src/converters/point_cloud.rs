@@ -0,0 +1,1059 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::{external::re_log::warn_once, Archetype as _, AsComponents as _, ComponentBatch as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::{colormap, value_range, Colormap},
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{
+        sensor_msgs::{PointCloud, PointCloud2},
+        ROSMessage as _,
+    },
+    dynamic_message::deserialize_view,
+    parsers::sensor_msgs::{
+        describe_fields, find_field, layout_error, unpack_color, ColoredPosition3DIter, FieldIter,
+        PackedColorIter, Position3DIter, Position3DValueIter, SoaPosition3DIter,
+    },
+    ROSTypeString, RerunName,
+};
+
+/// Default of `PointCloudConfig::max_splits`, the number of lasers of
+/// the largest common multi-beam lidars.
+pub const DEFAULT_MAX_SPLITS: usize = 128;
+
+/// Archetype that point clouds are logged as.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PointCloudOutput {
+    /// A point per point in the cloud.
+    #[default]
+    Points,
+    /// An axis aligned cube of `voxel_size` per point, e.g. for voxel maps.
+    ///
+    /// Boxes are considerably more expensive than points: every point
+    /// logs a center and a half size, and each cube is rendered as twelve
+    /// line segments. Prefer points for large clouds such as raw lidar scans.
+    Boxes,
+    /// A surface triangulated from the grid of an organized cloud, e.g. from
+    /// a depth camera. Triangles touching invalid points are skipped.
+    Mesh,
+}
+
+/// Memory layout of the point data.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PointCloudLayout {
+    /// Standard point-major layout, the fields of each point are interleaved.
+    #[default]
+    Aos,
+    /// Field-major layout used by some custom producers, see `SoaPosition3DIter`.
+    Soa,
+}
+
+/// How a cloud with more than `max_points` points is reduced.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PointCap {
+    /// Keep every n-th point, so the whole cloud stays covered.
+    #[default]
+    Subsample,
+    /// Keep the first `max_points` points, which is cheaper but may only
+    /// cover part of the scan.
+    Truncate,
+}
+
+/// Fields with a color packed into 4 bytes, see `PackedColorIter`.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackedColor {
+    /// The `rgb` field, the points are opaque.
+    Rgb,
+    /// The `rgba` field, including the alpha of each point.
+    Rgba,
+}
+
+impl PackedColor {
+    pub fn field_name(self) -> &'static str {
+        match self {
+            Self::Rgb => "rgb",
+            Self::Rgba => "rgba",
+        }
+    }
+}
+
+/// Automatic colormap ranges, see `IntensityRange`.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoRange {
+    /// The minimum and maximum of each cloud.
+    ///
+    /// Uses the full colormap for every cloud, but the color of a value
+    /// changes between clouds, so the colors flicker when the values of
+    /// consecutive scans span different ranges.
+    #[default]
+    AutoPerMessage,
+    /// The minimum and maximum of all clouds so far.
+    ///
+    /// Colors are stable over time, but an early outlier compresses the
+    /// colors of all later clouds.
+    AutoGlobal,
+}
+
+/// Range of the colored field values mapped onto the colormap.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum IntensityRange {
+    /// A fixed `[min, max]` range, values outside of it are clamped.
+    Fixed([f32; 2]),
+    Auto(AutoRange),
+}
+
+impl Default for IntensityRange {
+    fn default() -> Self {
+        Self::Auto(AutoRange::default())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PointCloudConfig {
+    /// Memory layout of `data`, defaults to the standard interleaved layout.
+    #[serde(default)]
+    layout: PointCloudLayout,
+
+    /// Overrides the archetype the converter was selected for.
+    output: Option<PointCloudOutput>,
+
+    /// Radius of the logged points, or of the box outlines.
+    radius: Option<f32>,
+
+    /// Edge length of the cubes when logging boxes.
+    voxel_size: Option<f32>,
+
+    /// Maximum number of points logged per message, to protect the viewer.
+    ///
+    /// Unlike decimation this applies within a message. Defaults to no cap.
+    max_points: Option<usize>,
+
+    /// How clouds above `max_points` are reduced.
+    #[serde(default)]
+    cap: PointCap,
+
+    /// Field whose values color the points with a colormap, e.g. `intensity`.
+    ///
+    /// Only supported for the standard interleaved layout.
+    color_field: Option<String>,
+
+    /// Packed color field that colors the points as is, `rgb` or `rgba`,
+    /// e.g. of the points of an RGBD camera. Clouds without the field fail
+    /// to convert. Only supported for the standard interleaved layout.
+    color: Option<PackedColor>,
+
+    /// Field that colors the points when neither `color_field` nor `color` is set,
+    /// e.g. `intensity` to color lidar clouds like RViz does.
+    ///
+    /// Unlike `color_field` it is optional: clouds without it are logged
+    /// without colors, so the viewer's uniform color applies. Ignored for
+    /// the soa layout.
+    intensity_field: Option<String>,
+
+    /// Colormap of the colored field values, defaults to `turbo`.
+    #[serde(default)]
+    colormap: Colormap,
+
+    /// Range of the colored field values, defaults to `auto_per_message`.
+    #[serde(default)]
+    intensity_range: IntensityRange,
+
+    /// Field whose values split the cloud into one entity per value,
+    /// logged as `<topic>/<field>/<value>`. E.g. `ring` splits the cloud of
+    /// a multi-beam lidar by laser, so each laser can be toggled on its own.
+    ///
+    /// Values are truncated to integers, points without a finite value are
+    /// skipped. Only supported for the standard interleaved layout.
+    split_by: Option<String>,
+
+    /// Maximum number of entities the cloud is split into, defaults to
+    /// `DEFAULT_MAX_SPLITS`. Points with further values are dropped.
+    max_splits: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SensorPointCloud2ToPoints3D {
+    output: PointCloudOutput,
+    config: PointCloudConfig,
+    /// Range of all colored values so far, for `AutoRange::AutoGlobal`.
+    global_range: Arc<Mutex<Option<(f32, f32)>>>,
+}
+
+impl SensorPointCloud2ToPoints3D {
+    pub fn new(output: PointCloudOutput) -> Self {
+        Self {
+            output,
+            ..Default::default()
+        }
+    }
+
+    /// Warn about a cloud without coordinates and return the error for it.
+    fn missing_coordinates(&self, cloud: &PointCloud2) -> ConverterError {
+        let fields = describe_fields(&cloud.fields);
+        warn_once!(
+            "PointCloud2 in frame '{}' has no x/y/z coordinate fields, available fields: {fields}",
+            cloud.header.frame_id
+        );
+        ConverterError::conversion(
+            self,
+            anyhow::anyhow!("missing x/y/z coordinate fields, available fields: {fields}"),
+        )
+    }
+
+    /// Field whose values color the points of `cloud`, if any.
+    ///
+    /// This is the `color_field` if set, or else the `intensity_field` if set
+    /// and the cloud has it, unless the points have a packed `color`.
+    fn color_field<'a>(&'a self, cloud: &PointCloud2) -> Option<&'a str> {
+        if let Some(field) = self.config.color_field.as_deref() {
+            return Some(field);
+        }
+        if self.config.layout == PointCloudLayout::Soa || self.config.color.is_some() {
+            return None;
+        }
+        let field = self.config.intensity_field.as_deref()?;
+        find_field(&cloud.fields, &[field])
+            .is_some()
+            .then_some(field)
+    }
+
+    /// The packed colors of the points, if configured.
+    ///
+    /// # Errors
+    /// Returns an error if the cloud has no such 4 byte field.
+    fn packed_colors<'a>(
+        &self,
+        cloud: &'a PointCloud2,
+    ) -> Result<Option<PackedColorIter<'a>>, ConverterError> {
+        let Some(color) = self.config.color else {
+            return Ok(None);
+        };
+        PackedColorIter::try_new(cloud, color.field_name(), color == PackedColor::Rgba)
+            .map(Some)
+            .ok_or_else(|| self.missing_packed_color(cloud, color))
+    }
+
+    fn missing_packed_color(&self, cloud: &PointCloud2, color: PackedColor) -> ConverterError {
+        ConverterError::conversion(
+            self,
+            anyhow::anyhow!(
+                "missing 4 byte packed color field '{}', available fields: {}",
+                color.field_name(),
+                describe_fields(&cloud.fields)
+            ),
+        )
+    }
+
+    /// The positions of the points, with their colors if any.
+    fn points(
+        &self,
+        cloud: &PointCloud2,
+    ) -> Result<(Vec<[f32; 3]>, Option<Vec<rerun::Color>>), ConverterError> {
+        let total = cloud.width as usize * cloud.height as usize;
+        if let Some(max_points) = self.config.max_points.filter(|max| total > *max) {
+            warn_once!(
+                "PointCloud2 in frame '{}' has {total} points, logging at most {max_points}",
+                cloud.header.frame_id
+            );
+        }
+        if let Some(color) = self.config.color {
+            let points = ColoredPosition3DIter::try_new(cloud, Some(color.field_name()))
+                .ok_or_else(|| match Position3DIter::try_new(cloud) {
+                    Some(_) => self.missing_packed_color(cloud, color),
+                    None => self.missing_coordinates(cloud),
+                })?
+                .filter(|(position, _)| position.iter().all(|v| v.is_finite()));
+            let alpha = color == PackedColor::Rgba;
+            let (positions, colors) = self
+                .capped(points, total)
+                .into_iter()
+                .map(|(position, bits)| {
+                    let color = bits.map_or([0; 4], |bits| unpack_color(bits, alpha));
+                    (position, rgba(color))
+                })
+                .unzip();
+            return Ok((positions, Some(colors)));
+        }
+        let Some(field) = self.color_field(cloud) else {
+            return Ok((self.positions(cloud)?, None));
+        };
+        let points = Position3DValueIter::try_new(cloud, field)
+            .ok_or_else(|| match find_field(&cloud.fields, &[field]) {
+                Some(_) => self.missing_coordinates(cloud),
+                None => self.missing_field(cloud, field),
+            })?
+            .filter(|(position, _)| position.iter().all(|v| v.is_finite()));
+        let (positions, values): (_, Vec<_>) = self.capped(points, total).into_iter().unzip();
+        Ok((positions, Some(self.colors(&values))))
+    }
+
+    fn field_values<'a>(
+        &self,
+        cloud: &'a PointCloud2,
+        field: &str,
+    ) -> Result<FieldIter<'a>, ConverterError> {
+        FieldIter::try_new(cloud, field).ok_or_else(|| self.missing_field(cloud, field))
+    }
+
+    fn missing_field(&self, cloud: &PointCloud2, field: &str) -> ConverterError {
+        ConverterError::conversion(
+            self,
+            anyhow::anyhow!(
+                "missing color field '{field}', available fields: {}",
+                describe_fields(&cloud.fields)
+            ),
+        )
+    }
+
+    /// Triangulate an organized cloud, see `organized_mesh`.
+    fn mesh(&self, cloud: &PointCloud2) -> Result<rerun::Mesh3D, ConverterError> {
+        if cloud.height < 2 {
+            return Err(ConverterError::conversion(
+                self,
+                anyhow::anyhow!("meshes require an organized cloud, got a height of 1"),
+            ));
+        }
+        let positions: Option<Vec<_>> = match self.config.layout {
+            PointCloudLayout::Aos => Position3DIter::try_new(cloud).map(Iterator::collect),
+            PointCloudLayout::Soa => SoaPosition3DIter::try_new(cloud).map(Iterator::collect),
+        };
+        let positions = positions.ok_or_else(|| self.missing_coordinates(cloud))?;
+        let (vertices, triangles, sources) = organized_mesh(&positions, cloud.width as usize);
+        let mut mesh = rerun::Mesh3D::new(vertices).with_triangle_indices(triangles);
+        if let Some(packed) = self.packed_colors(cloud)? {
+            let colors: Vec<_> = packed.collect();
+            mesh = mesh.with_vertex_colors(
+                sources
+                    .iter()
+                    .map(|source| rgba(colors.get(*source).copied().unwrap_or_default())),
+            );
+        } else if let Some(field) = self.color_field(cloud) {
+            let values: Vec<_> = self.field_values(cloud, field)?.collect();
+            let values: Vec<_> = sources
+                .iter()
+                .map(|source| values.get(*source).copied().unwrap_or(f32::NAN))
+                .collect();
+            mesh = mesh.with_vertex_colors(self.colors(&values));
+        }
+        Ok(mesh)
+    }
+
+    /// Color values with the configured `colormap` and `intensity_range`.
+    fn colors(&self, values: &[f32]) -> Vec<rerun::Color> {
+        let range = match self.config.intensity_range {
+            IntensityRange::Fixed([min, max]) => Some((min, max)),
+            IntensityRange::Auto(AutoRange::AutoPerMessage) => value_range(values),
+            IntensityRange::Auto(AutoRange::AutoGlobal) => {
+                let mut global = self.global_range.lock();
+                *global = match (*global, value_range(values)) {
+                    (Some((min, max)), Some((new_min, new_max))) => {
+                        Some((min.min(new_min), max.max(new_max)))
+                    }
+                    (current, new) => current.or(new),
+                };
+                *global
+            }
+        };
+        self.config.colormap.colors_in_range(values, range)
+    }
+
+    fn positions(&self, cloud: &PointCloud2) -> Result<Vec<[f32; 3]>, ConverterError> {
+        fn finite(position: &[f32; 3]) -> bool {
+            position.iter().all(|v| v.is_finite())
+        }
+
+        let total = cloud.width as usize * cloud.height as usize;
+        let positions: Option<Vec<_>> = match self.config.layout {
+            PointCloudLayout::Aos => {
+                Position3DIter::try_new(cloud).map(|iter| self.capped(iter.filter(finite), total))
+            }
+            PointCloudLayout::Soa => SoaPosition3DIter::try_new(cloud)
+                .map(|iter| self.capped(iter.filter(finite), total)),
+        };
+        positions.ok_or_else(|| self.missing_coordinates(cloud))
+    }
+
+    /// Collect at most `max_points` of the `total` points of a cloud.
+    fn capped<T>(&self, points: impl Iterator<Item = T>, total: usize) -> Vec<T> {
+        match self.config.max_points {
+            Some(max_points) if total > max_points => match self.config.cap {
+                PointCap::Subsample => points
+                    .step_by(total.div_ceil(max_points))
+                    .take(max_points)
+                    .collect(),
+                PointCap::Truncate => points.take(max_points).collect(),
+            },
+            _ => points.collect(),
+        }
+    }
+
+    /// Split the points by the values of `field`, one entity per value.
+    fn split(&self, cloud: &PointCloud2, field: &str) -> Result<ConverterData, ConverterError> {
+        let total = cloud.width as usize * cloud.height as usize;
+        let positions =
+            Position3DIter::try_new(cloud).ok_or_else(|| self.missing_coordinates(cloud))?;
+        let color_field = self.color_field(cloud);
+        let colored: Box<dyn Iterator<Item = f32>> = match color_field {
+            Some(color_field) => Box::new(self.field_values(cloud, color_field)?),
+            None => Box::new(std::iter::repeat(f32::NAN)),
+        };
+        let packed = self.packed_colors(cloud)?;
+        let has_packed = packed.is_some();
+        let packed: Box<dyn Iterator<Item = [u8; 4]>> = match packed {
+            Some(packed) => Box::new(packed),
+            None => Box::new(std::iter::repeat([0; 4])),
+        };
+        let points = positions
+            .zip(self.field_values(cloud, field)?)
+            .zip(colored)
+            .zip(packed)
+            .map(|(((position, key), value), packed)| (position, key, value, packed))
+            .filter(|(position, ..)| position.iter().all(|v| v.is_finite()));
+        let points = self.capped(points, total);
+
+        let keys: Vec<_> = points.iter().map(|(_, key, ..)| *key).collect();
+        let max_splits = self.config.max_splits.unwrap_or(DEFAULT_MAX_SPLITS);
+        let (groups, dropped) = group_by_value(&keys, max_splits);
+        if dropped {
+            warn_once!(
+                "PointCloud2 in frame '{}' has more than {max_splits} values of '{field}', \
+                 dropping the points of the others",
+                cloud.header.frame_id
+            );
+        }
+        let colors = if has_packed {
+            Some(points.iter().map(|(.., packed)| rgba(*packed)).collect())
+        } else {
+            color_field.map(|_| {
+                let values: Vec<_> = points.iter().map(|(_, _, value, _)| *value).collect();
+                self.colors(&values)
+            })
+        };
+        let header = Header::from(&cloud.header);
+        let entities = groups
+            .into_iter()
+            .map(|(key, indices)| {
+                let positions = indices.iter().map(|i| points[*i].0).collect();
+                let colors = colors
+                    .as_ref()
+                    .map(|colors| indices.iter().map(|i| colors[*i]).collect());
+                EntityData::new(
+                    format!("{field}/{key}"),
+                    self.points_or_boxes(positions, colors),
+                )
+                .with_header(header.clone())
+            })
+            .collect();
+        Ok(ConverterData::batch(entities).with_header(header))
+    }
+
+    fn convert(&self, cloud: &PointCloud2) -> Result<ConverterData, ConverterError> {
+        if self.config.layout == PointCloudLayout::Aos {
+            if let Some(err) = layout_error(cloud) {
+                return Err(ConverterError::conversion(
+                    self,
+                    anyhow::anyhow!("invalid PointCloud2 layout: {err}"),
+                ));
+            }
+        }
+        let data = match self.output {
+            PointCloudOutput::Mesh => ConverterData::new(self.mesh(cloud)?),
+            PointCloudOutput::Points | PointCloudOutput::Boxes => {
+                if let Some(field) = self.config.split_by.as_deref() {
+                    return self.split(cloud, field);
+                }
+                let (positions, colors) = self.points(cloud)?;
+                ConverterData::new(self.points_or_boxes(positions, colors))
+            }
+        };
+        Ok(data.with_header(Header::from(&cloud.header)))
+    }
+
+    fn points_or_boxes(
+        &self,
+        positions: Vec<[f32; 3]>,
+        colors: Option<Vec<rerun::Color>>,
+    ) -> Vec<rerun::SerializedComponentBatch> {
+        if self.output == PointCloudOutput::Boxes {
+            let half_size = self.config.voxel_size.unwrap_or_default() / 2.0;
+            let count = positions.len();
+            let mut boxes = rerun::Boxes3D::from_centers_and_half_sizes(
+                positions,
+                std::iter::repeat_n([half_size; 3], count),
+            );
+            if let Some(colors) = colors {
+                boxes = boxes.with_colors(colors);
+            }
+            return match self.config.radius {
+                Some(radius) => boxes.with_radii([radius]),
+                None => boxes,
+            }
+            .as_serialized_batches();
+        }
+        let mut points = rerun::Points3D::new(positions);
+        if let Some(colors) = colors {
+            points = points.with_colors(colors);
+        }
+        match self.config.radius {
+            Some(radius) => points.with_radii([radius]),
+            None => points,
+        }
+        .as_serialized_batches()
+    }
+}
+
+impl ConverterCfg for SensorPointCloud2ToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.global_range = Arc::default();
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if let Some(output) = self.config.output {
+            self.output = output;
+        }
+        if self.config.max_points == Some(0) {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'max_points' must be at least 1"),
+            ));
+        }
+        if self.config.color_field.is_some() && self.config.layout == PointCloudLayout::Soa {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'color_field' is not supported with the soa layout"),
+            ));
+        }
+        if self.config.color.is_some() {
+            if self.config.color_field.is_some() {
+                return Err(ConverterError::invalid_config(
+                    self,
+                    anyhow::anyhow!("'color' and 'color_field' are mutually exclusive"),
+                ));
+            }
+            if self.config.layout == PointCloudLayout::Soa {
+                return Err(ConverterError::invalid_config(
+                    self,
+                    anyhow::anyhow!("'color' is not supported with the soa layout"),
+                ));
+            }
+        }
+        if let IntensityRange::Fixed([min, max]) = self.config.intensity_range {
+            if !(min.is_finite() && max.is_finite() && min < max) {
+                return Err(ConverterError::invalid_config(
+                    self,
+                    anyhow::anyhow!("'intensity_range' must be [min, max] with min < max"),
+                ));
+            }
+        }
+        if self.config.split_by.is_some() {
+            let unsupported = if self.output == PointCloudOutput::Mesh {
+                Some("meshes")
+            } else if self.config.layout == PointCloudLayout::Soa {
+                Some("the soa layout")
+            } else {
+                None
+            };
+            if let Some(unsupported) = unsupported {
+                return Err(ConverterError::invalid_config(
+                    self,
+                    anyhow::anyhow!("'split_by' is not supported with {unsupported}"),
+                ));
+            }
+        }
+        if self.config.max_splits == Some(0) {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'max_splits' must be at least 1"),
+            ));
+        }
+        if self.output == PointCloudOutput::Mesh && self.config.max_points.is_some() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'max_points' is not supported for meshes"),
+            ));
+        }
+        if self.output == PointCloudOutput::Boxes
+            && !self.config.voxel_size.is_some_and(|size| size > 0.0)
+        {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'voxel_size' must be a positive number to log boxes"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorPointCloud2ToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        match self.output {
+            PointCloudOutput::Points => RerunName::RerunArchetype(rerun::Points3D::name()),
+            PointCloudOutput::Boxes => RerunName::RerunArchetype(rerun::Boxes3D::name()),
+            PointCloudOutput::Mesh => RerunName::RerunArchetype(rerun::Mesh3D::name()),
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorPointCloud2ToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(PointCloud2::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let cloud: PointCloud2 =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(&cloud)
+    }
+}
+
+/// Color of a point from its unpacked `[r, g, b, a]` bytes.
+fn rgba([r, g, b, a]: [u8; 4]) -> rerun::Color {
+    rerun::Color::from_unmultiplied_rgba(r, g, b, a)
+}
+
+/// Group the indices of `values` by their integer part in a single pass,
+/// keeping the groups of the first `max_splits` distinct values.
+///
+/// Returns the groups sorted by value, and whether values were dropped
+/// because of the cap. Values that are not finite are skipped.
+fn group_by_value(values: &[f32], max_splits: usize) -> (BTreeMap<i64, Vec<usize>>, bool) {
+    let mut groups: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+    let mut dropped = false;
+    for (i, value) in values.iter().enumerate() {
+        if !value.is_finite() {
+            continue;
+        }
+        let key = *value as i64;
+        match groups.get_mut(&key) {
+            Some(group) => group.push(i),
+            None if groups.len() < max_splits => {
+                groups.insert(key, vec![i]);
+            }
+            None => dropped = true,
+        }
+    }
+    (groups, dropped)
+}
+
+/// Triangulate the `width` columns of an organized cloud into a mesh.
+///
+/// Each grid cell of four neighboring points is split into two triangles,
+/// triangles touching an invalid point are skipped. Returns the vertices,
+/// the triangles, and the index in `positions` of each vertex.
+fn organized_mesh(
+    positions: &[[f32; 3]],
+    width: usize,
+) -> (Vec<[f32; 3]>, Vec<[u32; 3]>, Vec<usize>) {
+    let valid = |i: usize| {
+        positions
+            .get(i)
+            .is_some_and(|position| position.iter().all(|v| v.is_finite()))
+    };
+    let mut vertex_of = vec![None; positions.len()];
+    let (mut vertices, mut sources, mut triangles) = (Vec::new(), Vec::new(), Vec::new());
+    let mut vertex = |i: usize| {
+        *vertex_of[i].get_or_insert_with(|| {
+            vertices.push(positions[i]);
+            sources.push(i);
+            (vertices.len() - 1) as u32
+        })
+    };
+    let rows = positions.len().checked_div(width).unwrap_or_default();
+    for row in 1..rows {
+        for col in 1..width {
+            let (top, bottom) = ((row - 1) * width, row * width);
+            let [a, b, c, d] = [top + col - 1, top + col, bottom + col - 1, bottom + col];
+            for triangle in [[a, c, b], [b, c, d]] {
+                if triangle.iter().all(|i| valid(*i)) {
+                    triangles.push(triangle.map(&mut vertex));
+                }
+            }
+        }
+    }
+    (vertices, triangles, sources)
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LegacyPointCloudConfig {
+    /// Channel whose values color the points with a colormap, e.g. `intensity`.
+    channel: Option<String>,
+
+    /// Channel whose values are used as the point radii.
+    radius_channel: Option<String>,
+
+    /// Radius of the points when no radius channel is set.
+    radius: Option<f32>,
+
+    /// Channels logged as extra `ros_rerun.PointCloud:<name>` components.
+    #[serde(default)]
+    extra_channels: Vec<String>,
+}
+
+/// Converts the legacy `sensor_msgs/PointCloud` into points.
+///
+/// Channels are selected by name and must have one value per point,
+/// channels of a different length are skipped with a warning.
+#[derive(Clone, Debug, Default)]
+pub struct SensorPointCloudToPoints3D {
+    config: LegacyPointCloudConfig,
+}
+
+impl SensorPointCloudToPoints3D {
+    fn channel<'a>(cloud: &'a PointCloud, name: Option<&str>) -> Option<&'a [f32]> {
+        let name = name?;
+        let values = cloud.channel(name);
+        if values.is_none() {
+            warn_once!(
+                "PointCloud in frame '{}' has no channel '{name}' with one value per point, skipping it",
+                cloud.header.frame_id
+            );
+        }
+        values
+    }
+
+    fn convert(&self, cloud: &PointCloud) -> ConverterData {
+        let mut points =
+            rerun::Points3D::new(cloud.points.iter().map(rerun::datatypes::Vec3D::from));
+        if let Some(values) = Self::channel(cloud, self.config.channel.as_deref()) {
+            points = points.with_colors(colormap(values));
+        }
+        match Self::channel(cloud, self.config.radius_channel.as_deref()) {
+            Some(radii) => points = points.with_radii(radii.iter().copied()),
+            None => {
+                if let Some(radius) = self.config.radius {
+                    points = points.with_radii([radius]);
+                }
+            }
+        }
+        let mut batches = points.as_serialized_batches();
+        for name in &self.config.extra_channels {
+            if let Some(values) = Self::channel(cloud, Some(name)) {
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|value| rerun::datatypes::Float32(*value))
+                    .collect();
+                batches.extend(
+                    values.serialized(rerun::ComponentDescriptor::partial(format!(
+                        "ros_rerun.PointCloud:{name}"
+                    ))),
+                );
+            }
+        }
+        ConverterData::new(batches).with_header(Header::from(&cloud.header))
+    }
+}
+
+impl ConverterCfg for SensorPointCloudToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorPointCloudToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Points3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorPointCloudToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(PointCloud::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let cloud: PointCloud =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&cloud))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes_require_voxel_size() {
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        assert!(
+            converter
+                .set_config(ConverterSettings(toml::toml! { output = "boxes" }))
+                .is_err(),
+            "missing voxel size"
+        );
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                output = "boxes"
+                voxel_size = 0.05
+            }))
+            .expect("valid config");
+        assert_eq!(
+            converter.rerun_name(),
+            RerunName::RerunArchetype(rerun::Boxes3D::name())
+        );
+    }
+
+    #[test]
+    fn caps_points() {
+        let points = |count: u8| (0..count).map(|i| [f32::from(i), 0.0, 0.0]);
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { max_points = 4 }))
+            .expect("valid config");
+        let xs = |points: Vec<[f32; 3]>| points.iter().map(|p| p[0]).collect::<Vec<_>>();
+        assert_eq!(
+            xs(converter.capped(points(10), 10)),
+            [0.0, 3.0, 6.0, 9.0],
+            "uniform subsample"
+        );
+        assert_eq!(converter.capped(points(3), 3).len(), 3, "below the cap");
+
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                max_points = 4
+                cap = "truncate"
+            }))
+            .expect("valid config");
+        assert_eq!(xs(converter.capped(points(10), 10)), [0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn groups_points_by_ring() {
+        let rings = [0.0, 1.0, 0.0, 2.0, f32::NAN, 1.0, 3.0];
+        let (groups, dropped) = group_by_value(&rings, 16);
+        assert!(!dropped);
+        assert_eq!(
+            groups.into_iter().collect::<Vec<_>>(),
+            [(0, vec![0, 2]), (1, vec![1, 5]), (2, vec![3]), (3, vec![6])]
+        );
+
+        let (groups, dropped) = group_by_value(&rings, 2);
+        assert!(dropped, "rings 2 and 3 are over the cap");
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), [0, 1]);
+
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { split_by = "ring" }))
+            .expect("valid config");
+        for invalid in [
+            toml::toml! {
+                split_by = "ring"
+                layout = "soa"
+            },
+            toml::toml! {
+                split_by = "ring"
+                output = "mesh"
+            },
+            toml::toml! { max_splits = 0 },
+        ] {
+            assert!(converter.set_config(ConverterSettings(invalid)).is_err());
+        }
+    }
+
+    #[test]
+    fn triangulates_organized_grid() {
+        let mut positions: Vec<_> = (0..6).map(|i| [i as f32, 0.0, 0.0]).collect();
+        assert_eq!(organized_mesh(&positions, 3).1.len(), 4, "two cells");
+
+        // Drop the top right corner, which removes both triangles of its cell
+        positions[2][0] = f32::NAN;
+        let (vertices, triangles, sources) = organized_mesh(&positions, 3);
+        assert_eq!(triangles, [[0, 1, 2], [2, 1, 3]]);
+        assert_eq!(sources, [0, 3, 1, 4]);
+        assert_eq!(vertices.len(), 4);
+    }
+
+    #[test]
+    fn intensity_ranges() {
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                color_field = "intensity"
+                intensity_range = "auto_global"
+            }))
+            .expect("valid config");
+        converter.colors(&[0.0, 10.0]);
+        assert_eq!(
+            converter.colors(&[5.0]),
+            crate::color::colormap_in_range(&[5.0], Some((0.0, 10.0))),
+            "range of earlier clouds is kept"
+        );
+
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                color_field = "intensity"
+                intensity_range = [0.0, 100.0]
+            }))
+            .expect("valid config");
+        assert_eq!(
+            converter.config.intensity_range,
+            IntensityRange::Fixed([0.0, 100.0])
+        );
+        assert!(converter
+            .set_config(ConverterSettings(toml::toml! {
+                color_field = "intensity"
+                intensity_range = [1.0, 1.0]
+            }))
+            .is_err());
+        assert!(converter
+            .set_config(ConverterSettings(toml::toml! {
+                color_field = "intensity"
+                layout = "soa"
+            }))
+            .is_err());
+    }
+
+    #[test]
+    fn colors_by_intensity_if_present() {
+        use crate::definitions::sensor_msgs::PointField;
+
+        let field = |name: &str, offset| PointField {
+            name: name.to_owned(),
+            offset,
+            datatype: PointField::FLOAT32,
+            count: 1,
+        };
+        let data = [0.0_f32, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 3.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mut cloud = PointCloud2 {
+            height: 1,
+            width: 2,
+            fields: vec![
+                field("x", 0),
+                field("y", 4),
+                field("z", 8),
+                field("intensity", 12),
+            ],
+            point_step: 16,
+            row_step: 32,
+            data,
+            ..Default::default()
+        };
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        assert_eq!(converter.color_field(&cloud), None, "opt-in");
+
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                intensity_field = "intensity"
+                colormap = "viridis"
+            }))
+            .expect("valid config");
+        let (_, colors) = converter.points(&cloud).expect("valid cloud");
+        assert_eq!(colors, Some(converter.colors(&[1.0, 3.0])));
+        assert_eq!(
+            converter.colors(&[0.0, 1.0]),
+            Colormap::Viridis.colors_in_range(&[0.0, 1.0], Some((0.0, 1.0)))
+        );
+
+        cloud.fields[3].name = "reflectivity".to_owned();
+        let (positions, colors) = converter.points(&cloud).expect("uniform color");
+        assert_eq!((positions.len(), colors), (2, None));
+    }
+
+    #[test]
+    fn packed_rgb_colors() {
+        use crate::definitions::sensor_msgs::PointField;
+
+        let field = |name: &str, offset| PointField {
+            name: name.to_owned(),
+            offset,
+            datatype: PointField::FLOAT32,
+            count: 1,
+        };
+        let data = [0.0_f32, 0.0, 1.0, f32::from_bits(0x00_ff_00_80)]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let cloud = PointCloud2 {
+            height: 1,
+            width: 1,
+            fields: vec![
+                field("x", 0),
+                field("y", 4),
+                field("z", 8),
+                field("rgb", 12),
+            ],
+            point_step: 16,
+            row_step: 16,
+            data,
+            ..Default::default()
+        };
+        let mut converter = SensorPointCloud2ToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { color = "rgb" }))
+            .expect("valid config");
+        let (_, colors) = converter.points(&cloud).expect("has rgb");
+        assert_eq!(colors, Some(vec![rerun::Color::from_rgb(255, 0, 128)]));
+
+        converter
+            .set_config(ConverterSettings(toml::toml! { color = "rgba" }))
+            .expect("valid config");
+        let err = converter.points(&cloud).expect_err("no rgba field");
+        assert!(err.to_string().contains("rgba"), "{err}");
+        assert!(converter
+            .set_config(ConverterSettings(toml::toml! {
+                color = "rgb"
+                color_field = "intensity"
+            }))
+            .is_err());
+    }
+
+    #[test]
+    fn legacy_channels_must_match_points() {
+        use crate::definitions::{geometry_msgs::Point32, sensor_msgs::ChannelFloat32};
+
+        let cloud = PointCloud {
+            points: vec![Point32::default(); 2],
+            channels: vec![
+                ChannelFloat32 {
+                    name: "intensity".to_owned(),
+                    values: vec![0.5, 1.0],
+                },
+                ChannelFloat32 {
+                    name: "ring".to_owned(),
+                    values: vec![1.0],
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            SensorPointCloudToPoints3D::channel(&cloud, Some("intensity")),
+            Some([0.5, 1.0].as_slice())
+        );
+        assert_eq!(
+            SensorPointCloudToPoints3D::channel(&cloud, Some("ring")),
+            None,
+            "length mismatch"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{
+        geometry_msgs::{PoseArray, Transform},
+        std_msgs, ROSMessage as _,
+    },
+    dynamic_message::{deserialize_view, elements, with_path, Elements},
+    ROSTypeString, RerunName,
+};
+
+/// Archetype that an array of transforms is logged as.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PoseOutput {
+    /// Instanced poses, each transform becomes an instance of the entity.
+    ///
+    /// `rerun::Transform3D` describes a single transform, so the batch is
+    /// logged with `rerun::InstancePoses3D` instead.
+    #[default]
+    Poses,
+    /// The origins of the transforms.
+    Points,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PoseArrayConfig {
+    /// Dotted path to a `geometry_msgs/Transform[]` field, e.g. `skeleton.joints`.
+    field: Option<String>,
+
+    /// Overrides the archetype the converter was selected for.
+    output: Option<PoseOutput>,
+
+    /// Radius of the origins when logging points.
+    radius: Option<f32>,
+}
+
+/// Converts an array of transforms in any message into a batch of poses.
+///
+/// Useful for messages that carry several transforms without being a
+/// `tf2_msgs/TFMessage`, e.g. skeleton joints. An empty array clears the entity.
+#[derive(Clone, Debug, Default)]
+pub struct AnyTransformArrayToPoses {
+    output: PoseOutput,
+    config: PoseArrayConfig,
+}
+
+impl AnyTransformArrayToPoses {
+    pub fn new(output: PoseOutput) -> Self {
+        Self {
+            output,
+            config: PoseArrayConfig::default(),
+        }
+    }
+
+    /// Read the transforms, skipping malformed elements.
+    ///
+    /// Returns the valid transforms and an error per skipped element.
+    fn transforms(
+        &self,
+        msg: &rclrs::DynamicMessageView<'_>,
+    ) -> anyhow::Result<(Vec<Transform>, Vec<anyhow::Error>)> {
+        let field = self.config.field.as_deref().unwrap_or_default();
+        with_path(msg, field, |value| match elements(value) {
+            Some(Elements::Message(views)) => Ok(valid_transforms(
+                views
+                    .iter()
+                    .map(|view| deserialize_view(view).map_err(Into::into)),
+            )),
+            _ => Err(anyhow::anyhow!(
+                "'{field}' is not an array of geometry_msgs/Transform"
+            )),
+        })
+        .unwrap_or_else(|| Err(anyhow::anyhow!("missing field '{field}'")))
+    }
+
+    fn poses(&self, transforms: &[Transform]) -> ConverterData {
+        if transforms.is_empty() {
+            return ConverterData::new(rerun::Clear::flat());
+        }
+        let translations = transforms
+            .iter()
+            .map(|transform| rerun::datatypes::Vec3D::from(&transform.translation));
+        match self.output {
+            PoseOutput::Poses => ConverterData::new(
+                rerun::InstancePoses3D::new()
+                    .with_translations(translations)
+                    .with_quaternions(
+                        transforms
+                            .iter()
+                            .map(|transform| rerun::Quaternion::from(&transform.rotation)),
+                    ),
+            ),
+            PoseOutput::Points => {
+                let points = rerun::Points3D::new(translations);
+                ConverterData::new(match self.config.radius {
+                    Some(radius) => points.with_radii([radius]),
+                    None => points,
+                })
+            }
+        }
+    }
+}
+
+impl ConverterCfg for AnyTransformArrayToPoses {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if let Some(output) = self.config.output {
+            self.output = output;
+        }
+        if self.config.field.is_none() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'field' is required to locate the array of transforms"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Split elements into the valid transforms and an error per invalid element.
+fn valid_transforms(
+    elements: impl Iterator<Item = anyhow::Result<Transform>>,
+) -> (Vec<Transform>, Vec<anyhow::Error>) {
+    let mut transforms = Vec::new();
+    let mut errors = Vec::new();
+    for (index, element) in elements.enumerate() {
+        match element {
+            Ok(transform) if transform.is_valid() => transforms.push(transform),
+            Ok(_) => errors.push(anyhow::anyhow!(
+                "transform {index} has non-finite values or a zero rotation"
+            )),
+            Err(err) => errors.push(err.context(format!("transform {index}"))),
+        }
+    }
+    (transforms, errors)
+}
+
+#[async_trait]
+impl Converter for AnyTransformArrayToPoses {
+    fn rerun_name(&self) -> RerunName {
+        match self.output {
+            PoseOutput::Poses => RerunName::RerunArchetype(rerun::InstancePoses3D::name()),
+            PoseOutput::Points => RerunName::RerunArchetype(rerun::Points3D::name()),
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        "AnyTransformArrayToPoses"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        None
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let (transforms, errors) = self
+            .transforms(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let header = std_msgs::Header::from_view(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let data = self.poses(&transforms).with_partial_errors(errors);
+        Ok(match header {
+            Some(header) => data.with_header((&header).into()),
+            None => data,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GeometryPoseArrayConfig {
+    /// Log each pose as a `Transform3D` on its own entity, named by its
+    /// index, instead of the positions as points.
+    #[serde(default)]
+    as_transforms: bool,
+
+    /// Radius of the points.
+    radius: Option<f32>,
+}
+
+/// Converts a `geometry_msgs/PoseArray`, e.g. the particles of a particle
+/// filter, into the positions of its poses.
+///
+/// With `as_transforms`, each pose is logged as a transform on the child
+/// entity `<index>` instead, so its orientation is kept.
+#[derive(Clone, Debug, Default)]
+pub struct GeometryPoseArrayToPoints3D {
+    config: GeometryPoseArrayConfig,
+}
+
+impl GeometryPoseArrayToPoints3D {
+    fn convert(&self, msg: &PoseArray) -> ConverterData {
+        let header = Header::from(&msg.header);
+        let positions = msg
+            .poses
+            .iter()
+            .map(|pose| rerun::datatypes::Vec3D::from(&pose.position));
+        if self.config.as_transforms {
+            let entities = msg
+                .poses
+                .iter()
+                .zip(positions)
+                .enumerate()
+                .map(|(index, (pose, position))| {
+                    let transform = rerun::Transform3D::from_translation_rotation(
+                        position,
+                        rerun::Quaternion::from(&pose.orientation),
+                    );
+                    EntityData::new(index.to_string(), transform).with_header(header.clone())
+                })
+                .collect();
+            return ConverterData::batch(entities).with_header(header);
+        }
+        let points = rerun::Points3D::new(positions);
+        ConverterData::new(match self.config.radius {
+            Some(radius) => points.with_radii([radius]),
+            None => points,
+        })
+        .with_header(header)
+    }
+}
+
+impl ConverterCfg for GeometryPoseArrayToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for GeometryPoseArrayToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Points3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "GeometryPoseArrayToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(PoseArray::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: PoseArray =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_is_required() {
+        let mut converter = AnyTransformArrayToPoses::new(PoseOutput::Points);
+        assert!(
+            converter.set_config(ConverterSettings::default()).is_err(),
+            "missing field"
+        );
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                field = "skeleton.joints"
+                output = "poses"
+            }))
+            .expect("valid config");
+        assert_eq!(
+            converter.rerun_name(),
+            RerunName::RerunArchetype(rerun::InstancePoses3D::name())
+        );
+    }
+
+    #[test]
+    fn skips_malformed_transforms() {
+        use crate::definitions::geometry_msgs::Vector3;
+
+        let valid = Transform {
+            translation: Vector3 {
+                x: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let non_finite = Transform {
+            translation: Vector3 {
+                x: f64::NAN,
+                ..Default::default()
+            },
+            ..valid
+        };
+        let (transforms, errors) = valid_transforms(
+            [
+                Ok(valid),
+                Ok(non_finite),
+                Err(anyhow::anyhow!("not a transform")),
+                Ok(valid),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(transforms, vec![valid, valid]);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("transform 1"));
+    }
+
+    #[test]
+    fn pose_array_points_or_transforms() {
+        use crate::definitions::geometry_msgs::{Point, Pose};
+
+        let particles = PoseArray {
+            poses: vec![
+                Pose::default(),
+                Pose {
+                    position: Point {
+                        x: 1.0,
+                        y: 2.0,
+                        z: 0.0,
+                    },
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut converter = GeometryPoseArrayToPoints3D::default();
+        let data = converter.convert(&particles);
+        assert!(data.batch.is_empty(), "a single entity of points");
+        let empty = converter.convert(&PoseArray::default());
+        assert!(empty.batch.is_empty(), "empty points rather than an error");
+
+        converter
+            .set_config(ConverterSettings(toml::toml! { as_transforms = true }))
+            .expect("valid config");
+        let data = converter.convert(&particles);
+        let paths: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect();
+        assert_eq!(paths, ["0", "1"]);
+    }
+}
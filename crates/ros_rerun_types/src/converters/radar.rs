@@ -0,0 +1,378 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::{colormap_in_range, value_range},
+    converter::{Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings},
+    definitions::std_msgs,
+    dynamic_message::{elements, with_path, Elements, MessageVisitor as _},
+    ROSTypeString, RerunName,
+};
+
+/// Default radii of the returns with the lowest and the highest `rssi`.
+pub const DEFAULT_RADIUS_RANGE: [f32; 2] = [0.05, 0.3];
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RadarConfig {
+    /// Dotted path to the array of radar returns, e.g. `targets`.
+    returns: Option<String>,
+
+    /// Field of a return with its range in meters, defaults to `range`.
+    range: Option<String>,
+
+    /// Field of a return with its azimuth around the z axis, defaults to `azimuth`.
+    azimuth: Option<String>,
+
+    /// Field of a return with its elevation above the xy plane.
+    /// Without it, the returns are in the xy plane.
+    elevation: Option<String>,
+
+    /// Angles are given in degrees instead of radians.
+    #[serde(default)]
+    degrees: bool,
+
+    /// Field of a return whose values color the points, e.g. `doppler`.
+    doppler: Option<String>,
+
+    /// Range of the `doppler` values mapped onto the colormap.
+    /// Defaults to the range of the values of each message.
+    doppler_range: Option<[f32; 2]>,
+
+    /// Field of a return whose values scale the points, e.g. `rssi`.
+    rssi: Option<String>,
+
+    /// Radii of the returns with the lowest and the highest `rssi` in meters,
+    /// defaults to `DEFAULT_RADIUS_RANGE`.
+    radius_range: Option<[f32; 2]>,
+}
+
+/// A single radar return, read from the configured fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RadarReturn {
+    range: f64,
+    azimuth: f64,
+    elevation: f64,
+    doppler: Option<f64>,
+    rssi: Option<f64>,
+}
+
+impl RadarReturn {
+    /// Cartesian position of the return in the radar frame.
+    fn position(&self) -> [f32; 3] {
+        let horizontal = self.range * self.elevation.cos();
+        [
+            (horizontal * self.azimuth.cos()) as f32,
+            (horizontal * self.azimuth.sin()) as f32,
+            (self.range * self.elevation.sin()) as f32,
+        ]
+    }
+}
+
+/// Converts the polar returns of a radar, in any message, into points.
+///
+/// The fields of the returns are mapped through the config, so custom
+/// radar messages can be shown without a dedicated converter. It is
+/// selected with `converter = "AnyRadarToPoints3D"` in the topic config.
+#[derive(Clone, Debug, Default)]
+pub struct AnyRadarToPoints3D {
+    config: RadarConfig,
+}
+
+impl AnyRadarToPoints3D {
+    /// Read the returns, skipping malformed elements.
+    ///
+    /// Returns the valid returns and an error per skipped element.
+    fn returns(
+        &self,
+        msg: &rclrs::DynamicMessageView<'_>,
+    ) -> anyhow::Result<(Vec<RadarReturn>, Vec<anyhow::Error>)> {
+        let field = self.config.returns.as_deref().unwrap_or_default();
+        with_path(msg, field, |value| match elements(value) {
+            Some(Elements::Message(views)) => {
+                let mut returns = Vec::with_capacity(views.len());
+                let mut errors = Vec::new();
+                for (index, view) in views.iter().enumerate() {
+                    match self.radar_return(view) {
+                        Ok(radar_return) => returns.push(radar_return),
+                        Err(err) => errors.push(err.context(format!("return {index}"))),
+                    }
+                }
+                Ok((returns, errors))
+            }
+            _ => Err(anyhow::anyhow!("'{field}' is not an array of messages")),
+        })
+        .unwrap_or_else(|| Err(anyhow::anyhow!("missing field '{field}'")))
+    }
+
+    fn radar_return(&self, view: &rclrs::DynamicMessageView<'_>) -> anyhow::Result<RadarReturn> {
+        let number = |field: &str| {
+            view.get_f64(field)
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| anyhow::anyhow!("missing or invalid number '{field}'"))
+        };
+        let angle = |field: &str| {
+            number(field).map(|angle| {
+                if self.config.degrees {
+                    angle.to_radians()
+                } else {
+                    angle
+                }
+            })
+        };
+        Ok(RadarReturn {
+            range: number(self.config.range.as_deref().unwrap_or("range"))?,
+            azimuth: angle(self.config.azimuth.as_deref().unwrap_or("azimuth"))?,
+            elevation: self.config.elevation.as_deref().map_or(Ok(0.0), angle)?,
+            doppler: self.config.doppler.as_deref().map(number).transpose()?,
+            rssi: self.config.rssi.as_deref().map(number).transpose()?,
+        })
+    }
+
+    fn points(&self, returns: &[RadarReturn]) -> rerun::Points3D {
+        let mut points = rerun::Points3D::new(returns.iter().map(RadarReturn::position));
+        if self.config.doppler.is_some() {
+            let values = field_values(returns, |radar_return| radar_return.doppler);
+            let range = match self.config.doppler_range {
+                Some([min, max]) => Some((min, max)),
+                None => value_range(&values),
+            };
+            points = points.with_colors(colormap_in_range(&values, range));
+        }
+        if self.config.rssi.is_some() {
+            let values = field_values(returns, |radar_return| radar_return.rssi);
+            let radius_range = self.config.radius_range.unwrap_or(DEFAULT_RADIUS_RANGE);
+            points = points.with_radii(radii(&values, radius_range));
+        }
+        points
+    }
+}
+
+fn field_values(returns: &[RadarReturn], field: impl Fn(&RadarReturn) -> Option<f64>) -> Vec<f32> {
+    returns
+        .iter()
+        .map(|radar_return| field(radar_return).unwrap_or_default() as f32)
+        .collect()
+}
+
+/// Scale values linearly from their range onto `[min, max]` radii.
+///
+/// All values get the minimum radius when they are all the same.
+fn radii(values: &[f32], [min, max]: [f32; 2]) -> Vec<f32> {
+    let (low, high) = value_range(values).unwrap_or((0.0, 0.0));
+    let span = high - low;
+    values
+        .iter()
+        .map(|value| {
+            let t = if span > 0.0 {
+                (value - low) / span
+            } else {
+                0.0
+            };
+            min + t * (max - min)
+        })
+        .collect()
+}
+
+impl ConverterCfg for AnyRadarToPoints3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if self.config.returns.is_none() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'returns' is required to locate the array of radar returns"),
+            ));
+        }
+        let ordered = |range: Option<[f32; 2]>| {
+            range.is_none_or(|[min, max]| min.is_finite() && max.is_finite() && min < max)
+        };
+        if !ordered(self.config.doppler_range) {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'doppler_range' must be [min, max] with min < max"),
+            ));
+        }
+        if !ordered(self.config.radius_range)
+            || self.config.radius_range.is_some_and(|[min, _]| min < 0.0)
+        {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'radius_range' must be [min, max] with 0 <= min < max"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for AnyRadarToPoints3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Points3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "AnyRadarToPoints3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        None
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let (returns, errors) = self
+            .returns(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let header = std_msgs::Header::from_view(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let data = ConverterData::new(self.points(&returns)).with_partial_errors(errors);
+        Ok(match header {
+            Some(header) => data.with_header((&header).into()),
+            None => data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_synthetic_radar_returns() {
+        let mut converter = AnyRadarToPoints3D::default();
+        assert!(
+            converter.set_config(ConverterSettings::default()).is_err(),
+            "missing returns"
+        );
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                returns = "targets"
+                degrees = true
+                doppler = "doppler"
+                doppler_range = [-10.0, 10.0]
+                rssi = "rssi"
+                radius_range = [0.1, 0.5]
+            }))
+            .expect("valid config");
+        let returns = [
+            RadarReturn {
+                range: 2.0,
+                azimuth: 90_f64.to_radians(),
+                doppler: Some(-10.0),
+                rssi: Some(20.0),
+                ..Default::default()
+            },
+            RadarReturn {
+                range: 4.0,
+                elevation: 30_f64.to_radians(),
+                doppler: Some(10.0),
+                rssi: Some(40.0),
+                ..Default::default()
+            },
+        ];
+
+        let expected = [[0.0, 2.0, 0.0], [4.0 * 0.75_f32.sqrt(), 0.0, 2.0]];
+        for (radar_return, expected) in returns.iter().zip(expected) {
+            let position = radar_return.position();
+            for (value, expected) in position.iter().zip(expected) {
+                assert!((value - expected).abs() < 1e-5, "{position:?}");
+            }
+        }
+        let points = converter.points(&returns);
+        assert!(points.colors.is_some(), "colored by doppler");
+        assert!(points.radii.is_some(), "scaled by rssi");
+
+        let radii = radii(&[20.0, 30.0, 40.0], [0.1, 0.5]);
+        for (radius, expected) in radii.iter().zip([0.1, 0.3, 0.5]) {
+            assert!((radius - expected).abs() < 1e-6, "{radii:?}");
+        }
+    }
+
+    #[test]
+    fn reads_returns_from_dynamic_message() {
+        use crate::{
+            definitions::{nav_msgs::Path, sensor_msgs::Range, ROSMessage as _},
+            ROSTypeName,
+        };
+
+        let message = |ros_type: &ROSTypeString<'static>| {
+            let ros_type = ROSTypeName::try_from(ros_type).expect("message is available");
+            rclrs::DynamicMessage::new(ros_type.into()).expect("valid message type")
+        };
+        let mut converter = AnyRadarToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                returns = "poses"
+                range = "max_range"
+                azimuth = "field_of_view"
+                elevation = "min_range"
+                rssi = "radiation_type"
+            }))
+            .expect("valid config");
+
+        // The fields of a range reading stand in for those of a radar return
+        let range = message(Range::ros_type());
+        let radar_return = converter
+            .radar_return(&range.view())
+            .expect("numeric fields");
+        assert_eq!(
+            radar_return,
+            RadarReturn {
+                rssi: Some(0.0),
+                ..Default::default()
+            },
+            "integer fields are read as numbers"
+        );
+        let mut missing = converter.clone();
+        missing.config.doppler = Some("doppler".to_owned());
+        assert!(
+            missing.radar_return(&range.view()).is_err(),
+            "no doppler field"
+        );
+
+        let path = message(Path::ros_type());
+        let (returns, errors) = converter.returns(&path.view()).expect("array of messages");
+        assert!(returns.is_empty() && errors.is_empty());
+        missing.config.returns = Some("header".to_owned());
+        assert!(missing.returns(&path.view()).is_err(), "not an array");
+        missing.config.returns = Some("targets".to_owned());
+        assert!(missing.returns(&path.view()).is_err(), "missing field");
+    }
+
+    #[test]
+    fn split_radar_returns() {
+        use rerun::external::arrow::array::Array as _;
+
+        use crate::converter::InstanceMode;
+
+        let mut converter = AnyRadarToPoints3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                returns = "targets"
+                doppler = "doppler"
+            }))
+            .expect("valid config");
+        let returns = [RadarReturn::default(), RadarReturn::default()];
+        let data = InstanceMode::Split.apply(ConverterData::new(converter.points(&returns)));
+        assert_eq!(data.batch.len(), 2, "an entity per return");
+        assert!(
+            data.components.as_serialized_batches().is_empty(),
+            "nothing left on the topic's entity"
+        );
+        let colors = data.batch[1]
+            .components
+            .as_serialized_batches()
+            .into_iter()
+            .find(|batch| batch.descriptor.component.to_string() == "Points3D:colors")
+            .expect("colored by doppler");
+        assert_eq!(colors.array.len(), 1, "the color of the return");
+
+        let data = InstanceMode::Split.apply(ConverterData::new(converter.points(&[])));
+        assert!(data.batch.is_empty(), "no returns");
+    }
+}
@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use rerun::{Archetype as _, AsComponents as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{sensor_msgs::Range, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RangeConfig {
+    /// Only log the range as a scalar to plot, without drawing the beam.
+    #[serde(default)]
+    as_scalar: bool,
+}
+
+/// Converts the reading of a single beam range sensor, e.g. an ultrasonic
+/// or infrared one, into arrows along the beam and a scalar.
+///
+/// The beam is drawn along the x axis of the header frame, with an arrow
+/// on each edge of its `field_of_view`. Readings are clamped to
+/// `[min_range, max_range]`, so a sensor reporting infinity for "nothing
+/// detected" shows its maximum range.
+#[derive(Clone, Debug, Default)]
+pub struct SensorRangeToArrows3D {
+    config: RangeConfig,
+}
+
+impl SensorRangeToArrows3D {
+    fn convert(&self, range: &Range) -> ConverterData {
+        let distance = clamped_range(range);
+        let mut batches = rerun::Scalars::new([f64::from(distance)]).as_serialized_batches();
+        if !self.config.as_scalar {
+            batches.extend(beam_arrows(range, distance).as_serialized_batches());
+        }
+        ConverterData::new(batches).with_header(Header::from(&range.header))
+    }
+}
+
+/// The reading clamped to the limits of the sensor.
+///
+/// Readings that are not a number stay so, and limits that are not finite
+/// or out of order are ignored.
+fn clamped_range(range: &Range) -> f32 {
+    let mut distance = range.range;
+    if distance.is_nan() {
+        return distance;
+    }
+    if range.max_range.is_finite() {
+        distance = distance.min(range.max_range);
+    }
+    if range.min_range.is_finite() && range.min_range <= range.max_range {
+        distance = distance.max(range.min_range);
+    }
+    distance
+}
+
+/// Arrows of length `distance` along the center and the edges of the beam.
+fn beam_arrows(range: &Range, distance: f32) -> rerun::Arrows3D {
+    rerun::Arrows3D::from_vectors(beam_vectors(range, distance))
+}
+
+/// Vectors along the center and, for a beam with a width, the edges of the beam.
+fn beam_vectors(range: &Range, distance: f32) -> Vec<[f32; 3]> {
+    let half_fov = range.field_of_view / 2.0;
+    let angles: &[f32] = if half_fov > 0.0 {
+        &[-half_fov, 0.0, half_fov]
+    } else {
+        &[0.0]
+    };
+    angles
+        .iter()
+        .map(|angle| [distance * angle.cos(), distance * angle.sin(), 0.0])
+        .collect()
+}
+
+impl ConverterCfg for SensorRangeToArrows3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorRangeToArrows3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Arrows3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorRangeToArrows3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Range::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let range: Range =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn clamps_range_to_limits() {
+        let reading = |range| Range {
+            field_of_view: FRAC_PI_2,
+            min_range: 0.2,
+            max_range: 4.0,
+            range,
+            ..Default::default()
+        };
+        assert_eq!(clamped_range(&reading(1.5)), 1.5);
+        assert_eq!(clamped_range(&reading(0.05)), 0.2);
+        assert_eq!(clamped_range(&reading(f32::INFINITY)), 4.0, "no detection");
+        assert!(clamped_range(&reading(f32::NAN)).is_nan());
+
+        let vectors = beam_vectors(&reading(2.0), 2.0);
+        assert_eq!(vectors.len(), 3, "center and edges of the beam");
+        assert_eq!(vectors[1], [2.0, 0.0, 0.0]);
+        assert!(
+            (vectors[2][0] - vectors[2][1]).abs() < 1e-6,
+            "45 degrees left"
+        );
+        let narrow = Range {
+            field_of_view: 0.0,
+            ..reading(2.0)
+        };
+        assert_eq!(beam_vectors(&narrow, 2.0), [[2.0, 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn scalar_only_mode() {
+        let mut converter = SensorRangeToArrows3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! { as_scalar = true }))
+            .expect("valid config");
+        assert!(converter.config.as_scalar);
+        assert!(converter
+            .set_config(ConverterSettings(toml::toml! { cone = true }))
+            .is_err());
+    }
+}
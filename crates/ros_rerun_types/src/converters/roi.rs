@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings},
+    definitions::{sensor_msgs::RegionOfInterest, std_msgs, ROSMessage as _},
+    dynamic_message::{deserialize_view, with_path, DeserializeError},
+    ROSTypeString, RerunName,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RegionOfInterestConfig {
+    /// Dotted path to a `sensor_msgs/RegionOfInterest` field, e.g. `roi`
+    /// of a `sensor_msgs/CameraInfo`.
+    ///
+    /// Required for other message types, ignored for a `RegionOfInterest`.
+    field: Option<String>,
+}
+
+/// Converts a region of interest into a single 2D box in pixel coordinates.
+///
+/// The viewer overlays 2D boxes on an image when they are logged to the
+/// image entity or one of its children. Entity paths follow the topic
+/// names, so publish the region on a topic below the image topic, e.g.
+/// `/camera/image/roi` for `/camera/image`.
+///
+/// An unset region, which `CameraInfo` uses for the full image, clears the box.
+#[derive(Clone, Debug, Default)]
+pub struct SensorRegionOfInterestToBoxes2D {
+    /// Whether the converter reads the region from a field of any message.
+    any: bool,
+    config: RegionOfInterestConfig,
+}
+
+impl SensorRegionOfInterestToBoxes2D {
+    /// Converter reading the region at the configured `field` of any message.
+    pub fn any() -> Self {
+        Self {
+            any: true,
+            config: RegionOfInterestConfig::default(),
+        }
+    }
+
+    fn region(
+        &self,
+        msg: &rclrs::DynamicMessageView<'_>,
+    ) -> Result<RegionOfInterest, DeserializeError> {
+        let Some(field) = self.config.field.as_deref().filter(|_| self.any) else {
+            return deserialize_view(msg);
+        };
+        with_path(msg, field, |value| match value {
+            rclrs::Value::Simple(rclrs::SimpleValue::Message(roi)) => deserialize_view(roi),
+            _ => Err(DeserializeError::Custom(format!(
+                "'{field}' is not a sensor_msgs/RegionOfInterest"
+            ))),
+        })
+        .unwrap_or_else(|| Err(DeserializeError::Custom(format!("missing field '{field}'"))))
+    }
+}
+
+fn boxes(roi: &RegionOfInterest) -> ConverterData {
+    if roi.is_full_image() {
+        return ConverterData::new(rerun::Clear::flat());
+    }
+    ConverterData::new(rerun::Boxes2D::from_mins_and_sizes(
+        [(roi.x_offset as f32, roi.y_offset as f32)],
+        [(roi.width as f32, roi.height as f32)],
+    ))
+}
+
+impl ConverterCfg for SensorRegionOfInterestToBoxes2D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if self.any && self.config.field.is_none() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'field' is required to locate the region of interest"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorRegionOfInterestToBoxes2D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Boxes2D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorRegionOfInterestToBoxes2D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        (!self.any).then(RegionOfInterest::ros_type)
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let roi = self
+            .region(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let header = std_msgs::Header::from_view(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        let data = boxes(&roi);
+        Ok(match header {
+            Some(header) => data.with_header((&header).into()),
+            None => data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_requires_field() {
+        let mut converter = SensorRegionOfInterestToBoxes2D::any();
+        assert!(converter.set_config(ConverterSettings::default()).is_err());
+        converter
+            .set_config(ConverterSettings(toml::toml! { field = "roi" }))
+            .expect("valid config");
+        assert!(converter.ros_type().is_none(), "generic");
+        assert_eq!(
+            SensorRegionOfInterestToBoxes2D::default().ros_type(),
+            Some(RegionOfInterest::ros_type())
+        );
+    }
+}
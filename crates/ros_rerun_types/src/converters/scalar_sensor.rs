@@ -0,0 +1,299 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{sensor_msgs::MagneticField, std_msgs, ROSMessage as _},
+    dynamic_message::{deserialize_view, MessageVisitor as _},
+    ROSTypeString, RerunName,
+};
+
+/// Common `sensor_msgs` types made up of a header and a single reading,
+/// with the field holding the reading.
+pub static SENSOR_SCALARS: [(ROSTypeString<'static>, &str); 5] = [
+    (
+        ROSTypeString::new("sensor_msgs", "FluidPressure"),
+        "fluid_pressure",
+    ),
+    (
+        ROSTypeString::new("sensor_msgs", "Illuminance"),
+        "illuminance",
+    ),
+    (ROSTypeString::new("sensor_msgs", "Range"), "range"),
+    (
+        ROSTypeString::new("sensor_msgs", "RelativeHumidity"),
+        "relative_humidity",
+    ),
+    (
+        ROSTypeString::new("sensor_msgs", "Temperature"),
+        "temperature",
+    ),
+];
+
+/// Numeric `std_msgs` types, each holding a single value in `data`.
+///
+/// Integer values are widened to `f64`, like all readings.
+pub static STD_SCALARS: [ROSTypeString<'static>; 11] = ROSTypeString::in_package(
+    "std_msgs",
+    [
+        "Float32", "Float64", "Int8", "Int16", "Int32", "Int64", "UInt8", "UInt16", "UInt32",
+        "UInt64", "Byte",
+    ],
+);
+
+/// Field of the value of the `STD_SCALARS` types.
+pub const STD_SCALAR_FIELD: &str = "data";
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ScalarSensorConfig {
+    /// Numeric top-level field holding the reading.
+    ///
+    /// Required for the generic converter, optional for the registered
+    /// sensor types where it defaults to the field of the reading.
+    field: Option<String>,
+}
+
+/// Converts a single numeric field of a message to a `Scalars`.
+///
+/// The timestamp and frame are taken from the `header` field,
+/// if the message has one.
+#[derive(Clone, Debug, Default)]
+pub struct SingleScalarSensor {
+    ros_type: Option<&'static ROSTypeString<'static>>,
+    field: Option<&'static str>,
+    config: ScalarSensorConfig,
+}
+
+impl SingleScalarSensor {
+    /// Converter for a known ROS type, reading `field` by default.
+    pub fn new(ros_type: &'static ROSTypeString<'static>, field: &'static str) -> Self {
+        Self {
+            ros_type: Some(ros_type),
+            field: Some(field),
+            config: ScalarSensorConfig::default(),
+        }
+    }
+
+    fn field(&self) -> Option<&str> {
+        self.config.field.as_deref().or(self.field)
+    }
+}
+
+impl ConverterCfg for SingleScalarSensor {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if self.field().is_none() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("'field' is required to convert any ROS type to scalars"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SingleScalarSensor {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SingleScalarSensor"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        self.ros_type
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let field = self.field().unwrap_or_default();
+        let value = msg.get_f64(field).ok_or_else(|| {
+            ConverterError::conversion(
+                self,
+                anyhow::anyhow!("missing or non-numeric field '{field}'"),
+            )
+        })?;
+        let data = ConverterData::new(rerun::Scalars::new([value]));
+        let header = std_msgs::Header::from_view(&msg)
+            .map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(match header {
+            Some(header) => data.with_header((&header).into()),
+            None => data,
+        })
+    }
+}
+
+/// Converts a magnetometer reading into a series per axis, logged as
+/// `Scalars` on the child entities `x`, `y` and `z`.
+#[derive(Clone, Debug, Default)]
+pub struct SensorMagneticFieldToScalars;
+
+impl SensorMagneticFieldToScalars {
+    fn convert(&self, msg: &MagneticField) -> ConverterData {
+        let header = Header::from(&msg.header);
+        let field = &msg.magnetic_field;
+        let entities = [("x", field.x), ("y", field.y), ("z", field.z)]
+            .into_iter()
+            .map(|(axis, value)| {
+                EntityData::new(axis, rerun::Scalars::new([value])).with_header(header.clone())
+            })
+            .collect();
+        ConverterData::batch(entities).with_header(header)
+    }
+}
+
+impl ConverterCfg for SensorMagneticFieldToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("SensorMagneticFieldToScalars does not accept any configuration"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorMagneticFieldToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorMagneticFieldToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(MagneticField::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: MagneticField =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rclrs::SimpleValue;
+
+    use super::*;
+    use crate::dynamic_message::simple_to_f64;
+
+    #[test]
+    fn generic_requires_field() {
+        let mut converter = SingleScalarSensor::default();
+        assert!(
+            converter.set_config(ConverterSettings::default()).is_err(),
+            "generic converter needs a field"
+        );
+        converter
+            .set_config(ConverterSettings(toml::toml! { field = "voltage" }))
+            .expect("valid config");
+        assert_eq!(converter.field(), Some("voltage"));
+    }
+
+    #[test]
+    fn std_numeric_types_widen_data() {
+        for type_name in ["Float64", "Int32"] {
+            let ros_type = STD_SCALARS
+                .iter()
+                .find(|ros_type| ros_type.type_name() == type_name)
+                .expect("registered std_msgs type");
+            let mut converter = SingleScalarSensor::new(ros_type, STD_SCALAR_FIELD);
+            converter
+                .set_config(ConverterSettings::default())
+                .expect("field has a default");
+            assert_eq!(converter.field(), Some("data"));
+        }
+        assert_eq!(simple_to_f64(&SimpleValue::Double(&2.5)), Some(2.5));
+        assert_eq!(simple_to_f64(&SimpleValue::Int32(&-7)), Some(-7.0));
+        assert_eq!(
+            simple_to_f64(&SimpleValue::Uint64(&(1 << 40))),
+            Some(1_099_511_627_776.0)
+        );
+    }
+
+    #[test]
+    fn environmental_sensors_match_definitions() {
+        use crate::definitions::{
+            sensor_msgs::{FluidPressure, Illuminance, RelativeHumidity, Temperature},
+            ROSMessage,
+        };
+
+        /// The value field of `T`, as registered in `SENSOR_SCALARS`.
+        fn registered_field<T: ROSMessage + Serialize + Default>() -> &'static str {
+            let (_, field) = SENSOR_SCALARS
+                .iter()
+                .find(|(ros_type, _)| ros_type == T::ros_type())
+                .expect("registered sensor type");
+            let fields = toml::Table::try_from(T::default()).expect("serializable");
+            assert!(fields.contains_key(*field), "{field} is a field");
+            field
+        }
+
+        assert_eq!(registered_field::<RelativeHumidity>(), "relative_humidity");
+        assert_eq!(registered_field::<Illuminance>(), "illuminance");
+        assert_eq!(registered_field::<FluidPressure>(), "fluid_pressure");
+        assert_eq!(registered_field::<Temperature>(), "temperature");
+
+        // Plot the variance of the readings instead, e.g. for
+        // [topics.cpu_temperature.converter]
+        let (ros_type, field) = &SENSOR_SCALARS[4];
+        let mut converter = SingleScalarSensor::new(ros_type, field);
+        converter
+            .set_config(ConverterSettings(toml::toml! { field = "variance" }))
+            .expect("valid config");
+        assert_eq!(converter.field(), Some("variance"));
+    }
+
+    #[test]
+    fn sensor_types_default_field() {
+        let (ros_type, field) = &SENSOR_SCALARS[1];
+        let mut converter = SingleScalarSensor::new(ros_type, field);
+        converter
+            .set_config(ConverterSettings::default())
+            .expect("field has a default");
+        assert_eq!(converter.field(), Some("illuminance"));
+    }
+
+    #[test]
+    fn magnetic_field_per_axis() {
+        use crate::definitions::geometry_msgs::Vector3;
+
+        let msg = MagneticField {
+            magnetic_field: Vector3 {
+                x: 2e-5,
+                y: -1e-5,
+                z: 4e-5,
+            },
+            ..Default::default()
+        };
+        let data = SensorMagneticFieldToScalars.convert(&msg);
+        let paths: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect();
+        assert_eq!(paths, ["x", "y", "z"]);
+        assert!(SensorMagneticFieldToScalars
+            .set_config(ConverterSettings(toml::toml! { field = "x" }))
+            .is_err());
+    }
+}
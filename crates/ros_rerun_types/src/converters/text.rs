@@ -1,43 +1,84 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use rclrs::BaseType;
 use rerun::Archetype as _;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    color::ColorSetting,
     converter::{Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings},
-    dynamic_message::MessageVisitor as _,
+    dynamic_message::{DumpLimits, MessageVisitor as _},
     ROSTypeString, RerunName,
 };
 
-const STD_MSGS_STRING: ROSTypeString<'_> = ROSTypeString("std_msgs", "String");
+const STD_MSGS_STRING: ROSTypeString<'_> = ROSTypeString::new("std_msgs", "String");
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct TextDocumentConfig {
     /// The field in the ROS message to extract the text from.
     /// If `None`, it will output all text-like fields.
     field: Option<String>,
+
+    /// Render the whole message as an indented key/value tree in a
+    /// markdown code block, instead of only the text-like fields.
+    debug_dump: bool,
+
+    /// Maximum nesting depth rendered in `debug_dump` mode.
+    max_depth: Option<usize>,
+
+    /// Maximum number of array elements rendered in `debug_dump` mode.
+    max_array_len: Option<usize>,
 }
 
 impl TextDocumentConfig {
     fn parse(
         &mut self,
         config: &ConverterSettings,
-        rerun_name: RerunName,
+        rerun_name: &RerunName,
         ros_type: &ROSTypeString<'_>,
     ) -> anyhow::Result<(), ConverterError> {
         let field = config.0.get("field");
         if let Some(field) = field {
             let field_str = field.as_str().ok_or(ConverterError::InvalidConfig(
-                rerun_name,
+                rerun_name.clone(),
                 ros_type.to_string(),
                 anyhow::anyhow!("'field' must be a string"),
             ))?;
             self.field = Some(field_str.to_owned());
         }
+        if let Some(debug_dump) = config.0.get("debug_dump") {
+            self.debug_dump = debug_dump.as_bool().ok_or(ConverterError::InvalidConfig(
+                rerun_name.clone(),
+                ros_type.to_string(),
+                anyhow::anyhow!("'debug_dump' must be a boolean"),
+            ))?;
+        }
+        for (key, setting) in [
+            ("max_depth", &mut self.max_depth),
+            ("max_array_len", &mut self.max_array_len),
+        ] {
+            if let Some(value) = config.0.get(key) {
+                let value = value
+                    .as_integer()
+                    .and_then(|v| usize::try_from(v).ok())
+                    .filter(|v| *v > 0)
+                    .ok_or(ConverterError::InvalidConfig(
+                        rerun_name.clone(),
+                        ros_type.to_string(),
+                        anyhow::anyhow!("'{key}' must be a positive integer"),
+                    ))?;
+                *setting = Some(value);
+            }
+        }
         Ok(())
     }
+
+    fn dump_limits(&self) -> DumpLimits {
+        let default = DumpLimits::default();
+        DumpLimits {
+            max_depth: self.max_depth.unwrap_or(default.max_depth),
+            max_array_len: self.max_array_len.unwrap_or(default.max_array_len),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -63,6 +104,10 @@ impl Converter for StdStringToTextDocument {
         RerunName::RerunArchetype(rerun::TextDocument::name())
     }
 
+    fn id(&self) -> &'static str {
+        "StdStringToTextDocument"
+    }
+
     fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
         Some(&STD_MSGS_STRING)
     }
@@ -72,10 +117,7 @@ impl Converter for StdStringToTextDocument {
         msg: rclrs::DynamicMessageView<'a>,
     ) -> anyhow::Result<ConverterData, ConverterError> {
         if let Some(text) = msg.get_string("data") {
-            Ok(ConverterData {
-                header: None,
-                components: Arc::new(rerun::TextDocument::new(text)),
-            })
+            Ok(ConverterData::new(rerun::TextDocument::new(text)))
         } else {
             Err(ConverterError::Conversion(
                 self.rerun_name(),
@@ -95,7 +137,7 @@ impl ConverterCfg for AnyToTextDocument {
     fn set_config(&mut self, config: ConverterSettings) -> anyhow::Result<(), ConverterError> {
         self.config = TextDocumentConfig::default();
         self.config
-            .parse(&config, self.rerun_name(), &ROSTypeString::default())
+            .parse(&config, &self.rerun_name(), &ROSTypeString::default())
     }
 }
 
@@ -105,6 +147,10 @@ impl Converter for AnyToTextDocument {
         RerunName::RerunArchetype(rerun::TextDocument::name())
     }
 
+    fn id(&self) -> &'static str {
+        "AnyToTextDocument"
+    }
+
     fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
         None
     }
@@ -113,20 +159,251 @@ impl Converter for AnyToTextDocument {
         &self,
         msg: rclrs::DynamicMessageView<'a>,
     ) -> anyhow::Result<ConverterData, ConverterError> {
-        let text = msg
-            .iter_by_type(BaseType::String)
-            .map(|value| match value {
-                rclrs::Value::Simple(rclrs::SimpleValue::String(value)) => value.to_string(),
-                _ => String::default(),
-            })
-            .reduce(|mut acc, item| {
-                acc.push_str(&item);
-                acc
-            })
-            .unwrap_or_default();
-        Ok(ConverterData {
-            header: None,
-            components: Arc::new(rerun::TextDocument::new(text)),
+        if self.config.debug_dump {
+            let dump = msg.dump(self.config.dump_limits());
+            return Ok(ConverterData::new(
+                rerun::TextDocument::new(dump_markdown(&dump))
+                    .with_media_type(rerun::components::MediaType::markdown()),
+            ));
+        }
+        let text = extract_text(&msg, self.config.field.as_deref());
+        Ok(ConverterData::new(rerun::TextDocument::new(text)))
+    }
+}
+
+/// Markdown of a message dump, as a YAML code block so the viewer keeps
+/// the indentation and highlights the values.
+fn dump_markdown(dump: &str) -> String {
+    format!("```yaml\n{dump}```\n")
+}
+
+/// Get the text of a single string field, or all string fields concatenated.
+fn extract_text(msg: &rclrs::DynamicMessageView<'_>, field: Option<&str>) -> String {
+    if let Some(field) = field {
+        return msg.get_string(field).unwrap_or_default();
+    }
+    msg.iter_by_type(BaseType::String)
+        .map(|value| match value {
+            rclrs::Value::Simple(rclrs::SimpleValue::String(value)) => value.to_string(),
+            _ => String::default(),
+        })
+        .reduce(|mut acc, item| {
+            acc.push_str(&item);
+            acc
         })
+        .unwrap_or_default()
+}
+
+/// Parse a log level name into a `rerun::TextLogLevel`.
+///
+/// # Errors
+/// Returns an error if the level is not one of the Rerun log levels.
+pub fn parse_level(level: &str) -> anyhow::Result<rerun::components::TextLogLevel> {
+    let level = match level.to_ascii_lowercase().as_str() {
+        "trace" => rerun::components::TextLogLevel::TRACE,
+        "debug" => rerun::components::TextLogLevel::DEBUG,
+        "info" => rerun::components::TextLogLevel::INFO,
+        "warn" | "warning" => rerun::components::TextLogLevel::WARN,
+        "error" => rerun::components::TextLogLevel::ERROR,
+        "critical" | "fatal" => rerun::components::TextLogLevel::CRITICAL,
+        _ => anyhow::bail!(
+            "unknown log level '{level}', expected one of trace, debug, info, warn, error, critical"
+        ),
+    };
+    Ok(level.into())
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TextLogConfig {
+    /// The field in the ROS message to extract the text from.
+    /// If `None`, it will output all text-like fields.
+    field: Option<String>,
+
+    /// Log level attached to every message.
+    level: Option<String>,
+
+    /// Color attached to every message.
+    color: Option<ColorSetting>,
+}
+
+/// Validated `TextLogConfig`.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct TextLogStyle {
+    field: Option<String>,
+    level: Option<rerun::components::TextLogLevel>,
+    color: Option<rerun::Color>,
+}
+
+impl TextLogStyle {
+    fn parse(config: &ConverterSettings) -> anyhow::Result<Self> {
+        let config: TextLogConfig = config.parse()?;
+        Ok(Self {
+            field: config.field,
+            level: config.level.as_deref().map(parse_level).transpose()?,
+            color: config
+                .color
+                .as_ref()
+                .map(ColorSetting::to_color)
+                .transpose()?,
+        })
+    }
+
+    fn text_log(&self, text: String) -> rerun::TextLog {
+        let mut log = rerun::TextLog::new(text);
+        if let Some(level) = &self.level {
+            log = log.with_level(level.clone());
+        }
+        if let Some(color) = self.color {
+            log = log.with_color(color);
+        }
+        log
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StdStringToTextLog {
+    style: TextLogStyle,
+}
+
+impl ConverterCfg for StdStringToTextLog {
+    fn set_config(&mut self, config: ConverterSettings) -> anyhow::Result<(), ConverterError> {
+        self.style = TextLogStyle::parse(&config)
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for StdStringToTextLog {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::TextLog::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "StdStringToTextLog"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(&STD_MSGS_STRING)
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> anyhow::Result<ConverterData, ConverterError> {
+        let field = self.style.field.as_deref().unwrap_or("data");
+        if let Some(text) = msg.get_string(field) {
+            Ok(ConverterData::new(self.style.text_log(text)))
+        } else {
+            Err(ConverterError::conversion(
+                self,
+                anyhow::anyhow!("Missing '{field}' field"),
+            ))
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AnyToTextLog {
+    style: TextLogStyle,
+}
+
+impl ConverterCfg for AnyToTextLog {
+    fn set_config(&mut self, config: ConverterSettings) -> anyhow::Result<(), ConverterError> {
+        self.style = TextLogStyle::parse(&config)
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for AnyToTextLog {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::TextLog::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "AnyToTextLog"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        None
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> anyhow::Result<ConverterData, ConverterError> {
+        let text = extract_text(&msg, self.style.field.as_deref());
+        Ok(ConverterData::new(self.style.text_log(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_log_style() {
+        let style = TextLogStyle::parse(&ConverterSettings(toml::toml! {
+            level = "Warning"
+            color = "#ff0000"
+        }))
+        .expect("valid config");
+        assert_eq!(
+            style.level,
+            Some(rerun::components::TextLogLevel::WARN.into())
+        );
+        assert_eq!(style.color, Some(rerun::Color::from_rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn dumps_nested_message() {
+        use crate::{
+            definitions::{nav_msgs::Odometry, ROSMessage as _},
+            dynamic_message::DumpLimits,
+            ROSTypeName,
+        };
+
+        let ros_type = ROSTypeName::try_from(Odometry::ros_type()).expect("nav_msgs is available");
+        let msg = rclrs::DynamicMessage::new(ros_type.into()).expect("valid message type");
+        let mut converter = AnyToTextDocument::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                debug_dump = true
+                max_depth = 3
+                max_array_len = 4
+            }))
+            .expect("valid config");
+        let dump = msg.view().dump(converter.config.dump_limits());
+        assert!(dump.starts_with("header:\n  stamp:\n"), "{dump}");
+        assert!(dump.contains("child_frame_id: \"\"\n"), "{dump}");
+        assert!(
+            dump.contains("    position: {...}\n"),
+            "messages below max_depth are elided: {dump}"
+        );
+        assert!(
+            dump.contains("  covariance: [0, 0, 0, 0, ... (32 more)]\n"),
+            "arrays are truncated: {dump}"
+        );
+        let markdown = dump_markdown(&dump);
+        assert!(markdown.starts_with("```yaml\nheader:"));
+        assert!(markdown.ends_with("\n```\n"));
+
+        let unlimited = msg.view().dump(DumpLimits {
+            max_depth: 8,
+            max_array_len: 36,
+        });
+        assert!(
+            unlimited.contains("    position:\n      x: 0\n"),
+            "{unlimited}"
+        );
+    }
+
+    #[test]
+    fn unknown_level() {
+        let config = ConverterSettings(toml::toml! { level = "loud" });
+        assert!(TextLogStyle::parse(&config).is_err(), "unknown level");
     }
 }
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::Archetype as _;
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{sensor_msgs::TimeReference, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Converts a time reference into the offset of the local clock in seconds,
+/// for monitoring the synchronization with GPS or PTP time sources.
+///
+/// The name of the time source is logged as a static text document,
+/// whenever it changes.
+#[derive(Clone, Debug, Default)]
+pub struct SensorTimeReferenceToScalars {
+    /// Last logged source, per subscription.
+    source: Arc<Mutex<Option<String>>>,
+}
+
+impl SensorTimeReferenceToScalars {
+    fn convert(&self, msg: &TimeReference) -> ConverterData {
+        let data = ConverterData::new(rerun::Scalars::new([msg.offset_secs()]))
+            .with_header(Header::from(&msg.header));
+        let mut source = self.source.lock();
+        if source.as_deref() == Some(msg.source.as_str()) {
+            return data;
+        }
+        *source = Some(msg.source.clone());
+        data.with_static(rerun::TextDocument::new(msg.source.clone()))
+    }
+}
+
+impl ConverterCfg for SensorTimeReferenceToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.source = Arc::default();
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("SensorTimeReferenceToScalars does not accept any configuration"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorTimeReferenceToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorTimeReferenceToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(TimeReference::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: TimeReference =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{builtin_interfaces::Time, std_msgs};
+
+    #[test]
+    fn logs_source_once() {
+        let converter = SensorTimeReferenceToScalars::default();
+        let msg = TimeReference {
+            header: std_msgs::Header {
+                stamp: Time {
+                    sec: 10,
+                    nanosec: 0,
+                },
+                ..Default::default()
+            },
+            time_ref: Time {
+                sec: 9,
+                nanosec: 500_000_000,
+            },
+            source: "gps".to_owned(),
+        };
+        assert!((msg.offset_secs() - 0.5).abs() < 1e-9);
+        assert!(converter.convert(&msg).static_components.is_some());
+        assert!(
+            converter.convert(&msg).static_components.is_none(),
+            "unchanged source is not logged again"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+
+use crate::{
+    converter::{
+        ColumnData, Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings,
+        Header,
+    },
+    definitions::{trajectory_msgs::JointTrajectory, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Converts a joint trajectory, e.g. a motion plan, into the planned
+/// position of each joint over time.
+///
+/// The positions of a joint are logged as `Scalars` on the child entity
+/// named after the joint, each at the `time_from_start` of its point.
+/// They are sent as columns, so the whole plan shows up at once, ahead
+/// of the executed motion. Points without a position for a joint are
+/// skipped for that joint.
+#[derive(Clone, Debug, Default)]
+pub struct TrajectoryJointTrajectoryToScalars;
+
+impl TrajectoryJointTrajectoryToScalars {
+    fn convert(&self, trajectory: &JointTrajectory) -> Result<ConverterData, ConverterError> {
+        let columns = trajectory
+            .joint_names
+            .iter()
+            .enumerate()
+            .map(|(joint, name)| {
+                let (offsets, positions): (Vec<_>, Vec<_>) = trajectory
+                    .points
+                    .iter()
+                    .filter_map(|point| {
+                        let position = *point.positions.get(joint)?;
+                        Some((point.time_from_start.as_nanos(), position))
+                    })
+                    .unzip();
+                let columns = rerun::Scalars::new(positions)
+                    .columns_of_unit_batches()
+                    .map_err(|err| ConverterError::conversion(self, err))?
+                    .collect();
+                Ok(ColumnData {
+                    entity_path: joint_entity_path(name),
+                    offsets,
+                    columns,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ConverterData::columns(columns).with_header(Header::from(&trajectory.header)))
+    }
+}
+
+/// Entity path of a joint relative to the topic, escaped so that joint
+/// names with slashes stay a single entity.
+fn joint_entity_path(name: &str) -> String {
+    let path = rerun::EntityPath::from(vec![rerun::EntityPathPart::new(name)]).to_string();
+    path.trim_start_matches('/').to_owned()
+}
+
+impl ConverterCfg for TrajectoryJointTrajectoryToScalars {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!(
+                    "TrajectoryJointTrajectoryToScalars does not accept any configuration"
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for TrajectoryJointTrajectoryToScalars {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Scalars::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "TrajectoryJointTrajectoryToScalars"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(JointTrajectory::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let trajectory: JointTrajectory =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(&trajectory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{builtin_interfaces::Duration, trajectory_msgs::JointTrajectoryPoint};
+
+    #[test]
+    fn joint_positions_over_time() {
+        let point = |sec, positions: &[f64]| JointTrajectoryPoint {
+            positions: positions.to_vec(),
+            time_from_start: Duration { sec, nanosec: 0 },
+            ..Default::default()
+        };
+        let trajectory = JointTrajectory {
+            joint_names: vec!["shoulder".to_owned(), "arm/elbow".to_owned()],
+            points: vec![
+                point(0, &[0.0, 1.0]),
+                point(1, &[0.5]),
+                point(2, &[1.0, 0.0]),
+            ],
+            ..Default::default()
+        };
+        let data = TrajectoryJointTrajectoryToScalars
+            .convert(&trajectory)
+            .expect("valid trajectory");
+        assert!(data.batch.is_empty());
+        assert_eq!(data.columns.len(), 2, "a column per joint");
+        let shoulder = &data.columns[0];
+        assert_eq!(shoulder.entity_path, "shoulder");
+        assert_eq!(shoulder.offsets, [0, 1_000_000_000, 2_000_000_000]);
+        assert_eq!(shoulder.columns.len(), 1, "only scalars");
+        let elbow = &data.columns[1];
+        assert_eq!(elbow.entity_path, r"arm\/elbow");
+        assert_eq!(
+            elbow.offsets,
+            [0, 2_000_000_000],
+            "the second point has no elbow position"
+        );
+    }
+}
@@ -0,0 +1,813 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock},
+};
+
+use ahash::{HashMap, HashMapExt as _};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rerun::{external::glam, Archetype as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, EntityData,
+        Header,
+    },
+    definitions::{
+        geometry_msgs::{Quaternion, Transform, TransformStamped, Vector3},
+        tf2_msgs::TFMessage,
+        ROSMessage as _,
+    },
+    dynamic_message::deserialize_view,
+    wildcard_match, ROSTypeString, RerunName,
+};
+
+/// Scale applied on top of a rigid transform, uniform or per axis.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ScaleSetting {
+    Uniform(f32),
+    PerAxis([f32; 3]),
+}
+
+impl From<ScaleSetting> for rerun::components::Scale3D {
+    fn from(scale: ScaleSetting) -> Self {
+        match scale {
+            ScaleSetting::Uniform(scale) => Self::uniform(scale),
+            ScaleSetting::PerAxis(scale) => Self::from(scale),
+        }
+    }
+}
+
+/// How the four fields of a `geometry_msgs/Quaternion` hold the orientation,
+/// for publishers that use other conventions than ROS.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrientationFormat {
+    /// The ROS convention, a quaternion in `x`, `y`, `z`, `w`.
+    #[default]
+    QuatXyzw,
+    /// A quaternion with the scalar first, so `x` holds w and `w` holds z.
+    QuatWxyz,
+    /// Roll, pitch and yaw in radians in `x`, `y` and `z`, rotating about
+    /// the fixed x, y and z axes in that order like `tf2`. `w` is unused.
+    EulerRpy,
+    /// Yaw, pitch and roll in radians in `x`, `y` and `z`, with the same
+    /// rotation order as `EulerRpy`. `w` is unused.
+    EulerYpr,
+}
+
+impl OrientationFormat {
+    /// Read the orientation held by the fields of `q`.
+    pub fn quaternion(self, q: &Quaternion) -> glam::DQuat {
+        let (roll, pitch, yaw) = match self {
+            Self::QuatXyzw => return glam::DQuat::from_xyzw(q.x, q.y, q.z, q.w),
+            Self::QuatWxyz => return glam::DQuat::from_xyzw(q.y, q.z, q.w, q.x),
+            Self::EulerRpy => (q.x, q.y, q.z),
+            Self::EulerYpr => (q.z, q.y, q.x),
+        };
+        glam::DQuat::from_euler(glam::EulerRot::ZYX, yaw, pitch, roll)
+    }
+
+    /// The orientation held by the fields of `q`, as a Rerun rotation.
+    pub fn rotation(self, q: &Quaternion) -> rerun::Quaternion {
+        rerun::Quaternion::from_xyzw(self.quaternion(q).as_quat().to_array())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TransformConfig {
+    /// Scale for visualization-only frames, e.g. normalized display frames.
+    /// Transforms are rigid when unset.
+    scale: Option<ScaleSetting>,
+
+    /// Convention of the rotation fields, defaults to `quat_xyzw`.
+    #[serde(default)]
+    orientation_format: OrientationFormat,
+
+    /// Only log TF frames matching one of these patterns, where `*` matches
+    /// any characters. All frames are logged when empty.
+    #[serde(default)]
+    frame_include: Vec<String>,
+
+    /// Do not log TF frames matching one of these patterns.
+    #[serde(default)]
+    frame_exclude: Vec<String>,
+
+    /// Names of TF frames in entity paths, e.g. `{ base_link = "robot" }`.
+    #[serde(default)]
+    frame_rename: BTreeMap<String, String>,
+
+    /// TF topic sharing the frame tree of this one, defaults to the topic
+    /// with the `_static` suffix added or removed, e.g. `/tf_static` for `/tf`.
+    paired_topic: Option<String>,
+}
+
+impl TransformConfig {
+    /// Build the Rerun transform for a ROS transform.
+    pub fn transform(&self, transform: &Transform) -> rerun::Transform3D {
+        let transform3d = rerun::Transform3D::from_translation_rotation(
+            rerun::datatypes::Vec3D::from(&transform.translation),
+            self.orientation_format.rotation(&transform.rotation),
+        );
+        match self.scale {
+            Some(scale) => transform3d.with_scale(rerun::components::Scale3D::from(scale)),
+            None => transform3d,
+        }
+    }
+
+    /// Whether all values are finite and the rotation is not a zero
+    /// quaternion, see `Transform::is_valid`.
+    fn is_valid(&self, transform: &Transform) -> bool {
+        let Vector3 { x, y, z } = transform.translation;
+        let rotation = self.orientation_format.quaternion(&transform.rotation);
+        [x, y, z].iter().all(|v| v.is_finite())
+            && rotation.is_finite()
+            && rotation.length_squared() > 0.0
+    }
+
+    /// Whether any of the TF frame options is set.
+    fn maps_frames(&self) -> bool {
+        !self.frame_include.is_empty()
+            || !self.frame_exclude.is_empty()
+            || !self.frame_rename.is_empty()
+            || self.paired_topic.is_some()
+    }
+
+    /// Whether `frame` passes `frame_include` and `frame_exclude`.
+    fn shows_frame(&self, frame: &str) -> bool {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| wildcard_match(pattern, frame))
+        };
+        (self.frame_include.is_empty() || matches(&self.frame_include))
+            && !matches(&self.frame_exclude)
+    }
+
+    /// Name of `frame` in entity paths, see `frame_rename`.
+    fn frame_name<'a>(&'a self, frame: &'a str) -> &'a str {
+        self.frame_rename.get(frame).map_or(frame, String::as_str)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TransformStampedToTransform3D {
+    config: TransformConfig,
+}
+
+impl ConverterCfg for TransformStampedToTransform3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        self.config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        if self.config.maps_frames() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!("frame options only apply to tf2_msgs/TFMessage"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for TransformStampedToTransform3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Transform3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "TransformStampedToTransform3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(TransformStamped::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: TransformStamped =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(ConverterData::new(self.config.transform(&msg.transform))
+            .with_header(Header::from(&msg.header)))
+    }
+}
+
+/// Frame tree of each pair of TF topics, shared by the converters of both,
+/// see `TransformConfig::paired_topic`.
+///
+/// Keyed by the sorted names of the two topics.
+static FRAME_TREES: LazyLock<Mutex<HashMap<[String; 2], Arc<Mutex<FrameTree>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A TF topic, the entity its frames are logged below and its config.
+#[derive(Clone, Debug, Default)]
+struct TfTopic {
+    name: String,
+    entity_path: String,
+    config: TransformConfig,
+}
+
+impl TfTopic {
+    /// Whether the topic has the static transforms of the pair, which hold
+    /// for all time and are logged as static data.
+    fn is_static(&self) -> bool {
+        self.name.ends_with("_static")
+    }
+
+    /// Topic sharing the frame tree, the configured `paired_topic` or else
+    /// the topic with the `_static` suffix added or removed.
+    fn paired_topic(&self) -> String {
+        if let Some(paired) = &self.config.paired_topic {
+            return paired.clone();
+        }
+        match self.name.strip_suffix("_static") {
+            Some(name) => name.to_owned(),
+            None => format!("{}_static", self.name),
+        }
+    }
+
+    /// Entity path of a frame logged at `path` below the topic's entity.
+    fn frame_entity(&self, path: &str) -> String {
+        if self.entity_path.is_empty() {
+            return path.to_owned();
+        }
+        format!("{}/{path}", self.entity_path.trim_end_matches('/'))
+    }
+}
+
+/// Transform last logged for a frame.
+#[derive(Debug)]
+struct LoggedFrame {
+    /// Topic whose converter logged the frame.
+    topic: Arc<TfTopic>,
+    /// Entity path relative to the entity of `topic`.
+    entity_path: String,
+    transform: rerun::Transform3D,
+    header: Header,
+}
+
+impl LoggedFrame {
+    fn entity_data(&self) -> EntityData {
+        EntityData::new(
+            self.topic.frame_entity(&self.entity_path),
+            self.transform.clone(),
+        )
+        .with_header(self.header.clone())
+        .with_static(self.topic.is_static())
+    }
+
+    /// Clear the entity of the frame, statically for static frames, as a
+    /// clear on the timeline does not remove static data.
+    fn clear(&self, header: &Header) -> EntityData {
+        EntityData::new(
+            self.topic.frame_entity(&self.entity_path),
+            rerun::Clear::new(false),
+        )
+        .with_header(header.clone())
+        .with_static(self.topic.is_static())
+    }
+}
+
+/// Parent of every frame seen on a pair of TF topics, with the topic it
+/// was learned from, and the transforms logged for them so far.
+#[derive(Debug, Default)]
+struct FrameTree {
+    parents: HashMap<String, (String, Arc<TfTopic>)>,
+    logged: HashMap<String, LoggedFrame>,
+}
+
+impl FrameTree {
+    /// Set the parent of `child` as learned from `topic`, returning whether it changed.
+    fn insert(&mut self, parent: &str, child: &str, topic: &Arc<TfTopic>) -> bool {
+        let changed = self
+            .parents
+            .get(child)
+            .is_none_or(|(known, _)| known != parent);
+        self.parents
+            .insert(child.to_owned(), (parent.to_owned(), topic.clone()));
+        changed
+    }
+
+    /// Forget the parents learned from `topic` and the frames it logged,
+    /// e.g. when its converter is configured again.
+    fn forget(&mut self, topic: &str) {
+        self.parents.retain(|_, (_, learned)| learned.name != topic);
+        self.logged.retain(|_, logged| logged.topic.name != topic);
+    }
+
+    /// Frames from the root of the tree down to `frame`.
+    ///
+    /// A cycle in the tree stops the walk at the first repeated frame.
+    fn path<'a>(&'a self, frame: &'a str) -> Vec<&'a str> {
+        let mut path = vec![frame];
+        let mut current = frame;
+        while let Some((parent, _)) = self.parents.get(current) {
+            if path.contains(&parent.as_str()) {
+                break;
+            }
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Entity path of a frame below the topic's entity, e.g. `map/odom/base_link`,
+    /// with each frame named by `name`.
+    fn entity_path<'a>(&'a self, frame: &'a str, name: impl Fn(&'a str) -> &'a str) -> String {
+        let parts: Vec<_> = self
+            .path(frame)
+            .into_iter()
+            .map(|frame| rerun::EntityPathPart::new(name(frame)))
+            .collect();
+        let path = rerun::EntityPath::from(parts).to_string();
+        path.trim_start_matches('/').to_owned()
+    }
+
+    /// Move the logged frames whose entity path changed since they were
+    /// logged, e.g. because the parent of a frame above them became known.
+    ///
+    /// The old entities are cleared at the time of `header`, and the last
+    /// transform of each frame is logged again on its new entity, below the
+    /// entity of the topic that logged it, unless it is hidden by the filters
+    /// of that topic now or is in `skip`, as it is about to be logged anyway.
+    fn relocate(&mut self, header: &Header, skip: &[&str]) -> Vec<EntityData> {
+        let mut moved: Vec<_> = self
+            .logged
+            .iter()
+            .filter_map(|(frame, logged)| {
+                let config = &logged.topic.config;
+                let entity_path = self.entity_path(frame, |frame| config.frame_name(frame));
+                (entity_path != logged.entity_path).then(|| (frame.clone(), entity_path))
+            })
+            .collect();
+        // Parents first, so the entities are logged in the order of the tree
+        moved.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+        let mut clears = Vec::with_capacity(moved.len());
+        let mut relogged = Vec::with_capacity(moved.len());
+        for (frame, entity_path) in moved {
+            let Some(mut logged) = self.logged.remove(&frame) else {
+                continue;
+            };
+            clears.push(logged.clear(header));
+            let shown = self
+                .path(&frame)
+                .into_iter()
+                .all(|frame| logged.topic.config.shows_frame(frame));
+            if !shown {
+                continue;
+            }
+            logged.entity_path = entity_path;
+            if !skip.contains(&frame.as_str()) {
+                relogged.push(logged.entity_data());
+            }
+            self.logged.insert(frame, logged);
+        }
+        clears.extend(relogged);
+        clears
+    }
+}
+
+/// Frame ID without the leading slash that ROS 1 allowed.
+fn frame_id(frame: &str) -> &str {
+    frame.trim_start_matches('/')
+}
+
+/// Converts every transform of a TF message into a transform on the entity
+/// of its child frame.
+///
+/// The entities follow the frame tree seen on the topic and its paired
+/// topic so far, e.g. the transform from `odom` to `base_link` is logged to
+/// `<topic>/map/odom/base_link` once the parent of `odom` is known, so Rerun
+/// chains the transforms. Frames logged before that are moved: their old
+/// entity is cleared and their last transform is logged again on the new one,
+/// below the entity of the topic that logged it.
+///
+/// The `tf` and `tf_static` topics of a namespace are paired by default, see
+/// `TransformConfig::paired_topic`. Give both the same entity path for Rerun
+/// to chain their transforms. The transforms of the static topic are logged
+/// as static data.
+///
+/// Frames are renamed in entity paths with `frame_rename`, and filtered by
+/// their ROS frame IDs with `frame_include` and `frame_exclude`. A frame is
+/// only logged when all frames above it pass the filters too, as Rerun
+/// could not place it without their transforms.
+#[derive(Clone, Debug, Default)]
+pub struct TFMessageToTransform3D {
+    topic: Arc<TfTopic>,
+    /// Frame tree of the topic pair, see `FRAME_TREES`.
+    frames: Arc<Mutex<FrameTree>>,
+}
+
+impl TFMessageToTransform3D {
+    fn convert(&self, msg: &TFMessage) -> ConverterData {
+        let config = &self.topic.config;
+        let mut frames = self.frames.lock();
+        let mut changed = false;
+        for transform in &msg.transforms {
+            changed |= frames.insert(
+                frame_id(&transform.header.frame_id),
+                frame_id(&transform.child_frame_id),
+                &self.topic,
+            );
+        }
+        let mut entities = match msg.transforms.first() {
+            Some(first) if changed => {
+                let children: Vec<_> = msg
+                    .transforms
+                    .iter()
+                    .map(|transform| frame_id(&transform.child_frame_id))
+                    .collect();
+                frames.relocate(&Header::from(&first.header), &children)
+            }
+            _ => Vec::new(),
+        };
+        let mut errors = Vec::new();
+        for transform in &msg.transforms {
+            let child = frame_id(&transform.child_frame_id);
+            if !frames
+                .path(child)
+                .into_iter()
+                .all(|frame| config.shows_frame(frame))
+            {
+                continue;
+            }
+            if !config.is_valid(&transform.transform) {
+                errors.push(anyhow::anyhow!(
+                    "transform to '{}' has non-finite values or a zero rotation",
+                    transform.child_frame_id
+                ));
+                continue;
+            }
+            let header = Header::from(&transform.header);
+            let logged = LoggedFrame {
+                topic: self.topic.clone(),
+                entity_path: frames.entity_path(child, |frame| config.frame_name(frame)),
+                transform: config.transform(&transform.transform),
+                header: header.clone(),
+            };
+            let entity = logged.entity_data();
+            // The frame moves over from the entity of the paired topic
+            if let Some(earlier) = frames.logged.get(child) {
+                if earlier.topic.frame_entity(&earlier.entity_path) != entity.entity_path {
+                    entities.push(earlier.clear(&header));
+                }
+            }
+            entities.push(entity);
+            frames.logged.insert(child.to_owned(), logged);
+        }
+        if entities.is_empty() || self.topic.entity_path.is_empty() {
+            return ConverterData::batch(entities).with_partial_errors(errors);
+        }
+        // Entity paths start at the root, as moved frames of the paired
+        // topic are logged below its entity
+        ConverterData::batch(entities)
+            .with_entity_path("/")
+            .with_partial_errors(errors)
+    }
+}
+
+impl ConverterCfg for TFMessageToTransform3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        let config = config
+            .parse()
+            .map_err(|err| ConverterError::invalid_config(self, err))?;
+        self.topic = Arc::new(TfTopic {
+            config,
+            ..TfTopic::clone(&self.topic)
+        });
+        if self.topic.name.is_empty() {
+            self.frames = Arc::default();
+            return Ok(());
+        }
+        let mut pair = [self.topic.name.clone(), self.topic.paired_topic()];
+        pair.sort();
+        self.frames = FRAME_TREES.lock().entry(pair).or_default().clone();
+        self.frames.lock().forget(&self.topic.name);
+        Ok(())
+    }
+
+    fn set_topic(&mut self, topic: &str, entity_path: &str) {
+        self.topic = Arc::new(TfTopic {
+            name: topic.to_owned(),
+            entity_path: entity_path.to_owned(),
+            config: self.topic.config.clone(),
+        });
+    }
+}
+
+#[async_trait]
+impl Converter for TFMessageToTransform3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Transform3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "TFMessageToTransform3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(TFMessage::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: TFMessage =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        Ok(self.convert(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_is_applied() {
+        let config: TransformConfig = ConverterSettings(toml::toml! { scale = [1.0, 2.0, 3.0] })
+            .parse()
+            .expect("valid config");
+        assert_eq!(config.scale, Some(ScaleSetting::PerAxis([1.0, 2.0, 3.0])));
+        let transform = config.transform(&Transform::default());
+        assert!(transform.scale.is_some(), "scale is logged");
+
+        let config: TransformConfig = ConverterSettings(toml::toml! { scale = 0.5 })
+            .parse()
+            .expect("valid config");
+        assert_eq!(config.scale, Some(ScaleSetting::Uniform(0.5)));
+    }
+
+    #[test]
+    fn rigid_by_default() {
+        let transform = TransformConfig::default().transform(&Transform::default());
+        assert!(transform.scale.is_none(), "no scale unless configured");
+        assert!(transform.translation.is_some(), "translation is logged");
+        assert!(transform.quaternion.is_some(), "rotation is logged");
+    }
+
+    #[test]
+    fn orientation_formats() {
+        let (roll, pitch, yaw) = (0.1, 0.2, 0.3);
+        let expected = glam::DQuat::from_rotation_z(yaw)
+            * glam::DQuat::from_rotation_y(pitch)
+            * glam::DQuat::from_rotation_x(roll);
+        let [x, y, z, w] = expected.to_array();
+        let formats = [
+            ("quat_xyzw", Quaternion { x, y, z, w }),
+            (
+                "quat_wxyz",
+                Quaternion {
+                    x: w,
+                    y: x,
+                    z: y,
+                    w: z,
+                },
+            ),
+            (
+                "euler_rpy",
+                Quaternion {
+                    x: roll,
+                    y: pitch,
+                    z: yaw,
+                    w: 0.0,
+                },
+            ),
+            (
+                "euler_ypr",
+                Quaternion {
+                    x: yaw,
+                    y: pitch,
+                    z: roll,
+                    w: 0.0,
+                },
+            ),
+        ];
+        for (name, rotation) in formats {
+            let settings = toml::Table::from_iter([("orientation_format".into(), name.into())]);
+            let config: TransformConfig =
+                ConverterSettings(settings).parse().expect("valid config");
+            let quaternion = config.orientation_format.quaternion(&rotation);
+            assert!(
+                quaternion.angle_between(expected) < 1e-9,
+                "{name}: {quaternion:?}"
+            );
+            let transform = Transform {
+                rotation,
+                ..Default::default()
+            };
+            assert!(config.is_valid(&transform), "{name}");
+        }
+        assert_eq!(
+            TransformConfig::default().orientation_format,
+            OrientationFormat::QuatXyzw
+        );
+    }
+
+    #[test]
+    fn tf_message_follows_frame_tree() {
+        use crate::definitions::{builtin_interfaces::Time, std_msgs};
+
+        let transform = |parent: &str, child: &str, sec: i32| TransformStamped {
+            header: std_msgs::Header {
+                stamp: Time { sec, nanosec: 0 },
+                frame_id: parent.to_owned(),
+            },
+            child_frame_id: child.to_owned(),
+            transform: Transform::default(),
+        };
+        let mut invalid = transform("map", "invalid", 1);
+        invalid.transform.rotation = Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        let converter = TFMessageToTransform3D::default();
+        let data = converter.convert(&TFMessage {
+            transforms: vec![
+                transform("odom", "base_link", 2),
+                transform("/map", "odom", 1),
+                invalid,
+            ],
+        });
+        let paths: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect();
+        assert_eq!(paths, ["map/odom/base_link", "map/odom"]);
+        let stamps: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.header.as_ref().and_then(|header| header.stamp))
+            .collect();
+        assert_eq!(stamps, [Some(2_000_000_000), Some(1_000_000_000)]);
+        assert_eq!(data.partial_errors.len(), 1, "zero rotation is skipped");
+
+        let mut tree = FrameTree::default();
+        tree.insert("a", "b");
+        tree.insert("b", "a");
+        assert_eq!(tree.path("a"), ["b", "a"], "cycles terminate");
+    }
+
+    #[test]
+    fn moves_frames_when_parent_is_learned() {
+        use crate::definitions::std_msgs;
+
+        let transform = |parent: &str, child: &str| TransformStamped {
+            header: std_msgs::Header {
+                frame_id: parent.to_owned(),
+                ..Default::default()
+            },
+            child_frame_id: child.to_owned(),
+            transform: Transform::default(),
+        };
+        let paths = |data: &ConverterData| -> Vec<String> {
+            data.batch
+                .iter()
+                .map(|entity| entity.entity_path.clone())
+                .collect()
+        };
+        let statics = |data: &ConverterData| -> Vec<bool> {
+            data.batch.iter().map(|entity| entity.is_static).collect()
+        };
+        let converter = |topic: &str, entity_path: &str| {
+            let mut converter = TFMessageToTransform3D::default();
+            converter.set_topic(topic, entity_path);
+            converter
+                .set_config(ConverterSettings::default())
+                .expect("valid config");
+            converter
+        };
+        let tf_static = converter("/moves_frames/tf_static", "/robot/static");
+        let mut tf = converter("/moves_frames/tf", "/robot/tf");
+
+        let data = tf_static.convert(&TFMessage {
+            transforms: vec![transform("base_link", "camera_link")],
+        });
+        assert_eq!(paths(&data), ["/robot/static/base_link/camera_link"]);
+        assert_eq!(statics(&data), [true]);
+        assert_eq!(data.entity_path.as_deref(), Some("/"), "paths are absolute");
+        let data = tf.convert(&TFMessage {
+            transforms: vec![transform("odom", "base_link")],
+        });
+        assert_eq!(
+            paths(&data),
+            [
+                "/robot/static/base_link/camera_link",
+                "/robot/static/odom/base_link/camera_link",
+                "/robot/tf/odom/base_link"
+            ],
+            "static frame is cleared and logged again below its new parent"
+        );
+        assert_eq!(statics(&data), [true, true, false], "static clear");
+        let data = tf.convert(&TFMessage {
+            transforms: vec![transform("odom", "base_link")],
+        });
+        assert_eq!(paths(&data), ["/robot/tf/odom/base_link"], "nothing moved");
+
+        let data = tf.convert(&TFMessage {
+            transforms: vec![transform("map", "odom"), transform("odom", "base_link")],
+        });
+        assert_eq!(
+            paths(&data),
+            [
+                "/robot/tf/odom/base_link",
+                "/robot/static/odom/base_link/camera_link",
+                "/robot/static/map/odom/base_link/camera_link",
+                "/robot/tf/map/odom",
+                "/robot/tf/map/odom/base_link"
+            ],
+            "frames in the message are not logged twice"
+        );
+
+        tf.set_config(ConverterSettings::default())
+            .expect("valid config");
+        let data = tf.convert(&TFMessage {
+            transforms: vec![transform("odom", "base_link")],
+        });
+        assert_eq!(
+            paths(&data),
+            [
+                "/robot/static/map/odom/base_link/camera_link",
+                "/robot/static/odom/base_link/camera_link",
+                "/robot/tf/odom/base_link"
+            ],
+            "a reconfigured topic forgets its frames, but not those of its pair"
+        );
+    }
+
+    #[test]
+    fn filters_and_renames_frames() {
+        use crate::definitions::std_msgs;
+
+        let transform = |parent: &str, child: &str| TransformStamped {
+            header: std_msgs::Header {
+                frame_id: parent.to_owned(),
+                ..Default::default()
+            },
+            child_frame_id: child.to_owned(),
+            transform: Transform::default(),
+        };
+        let mut converter = TFMessageToTransform3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                frame_exclude = ["*_optical_frame", "odom_debug"]
+                frame_rename = { base_link = "robot" }
+            }))
+            .expect("valid config");
+        let data = converter.convert(&TFMessage {
+            transforms: vec![
+                transform("map", "odom"),
+                transform("odom", "base_link"),
+                transform("base_link", "camera_link"),
+                transform("camera_link", "camera_optical_frame"),
+                transform("map", "odom_debug"),
+                transform("odom_debug", "marker"),
+            ],
+        });
+        let paths: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            ["map/odom", "map/odom/robot", "map/odom/robot/camera_link"],
+            "children of excluded frames are dropped, parents are renamed"
+        );
+
+        let mut converter = TFMessageToTransform3D::default();
+        converter
+            .set_config(ConverterSettings(toml::toml! {
+                frame_include = ["map", "odom", "base_*"]
+            }))
+            .expect("valid config");
+        let data = converter.convert(&TFMessage {
+            transforms: vec![
+                transform("map", "odom"),
+                transform("odom", "base_link"),
+                transform("base_link", "camera_link"),
+            ],
+        });
+        assert_eq!(data.batch.len(), 2, "camera_link is not included");
+
+        assert!(
+            TransformStampedToTransform3D::default()
+                .set_config(ConverterSettings(toml::toml! {
+                    frame_exclude = ["odom"]
+                }))
+                .is_err(),
+            "a single transform has no frame tree"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{sensor_msgs::CompressedImage, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Logs H.264 encoded `sensor_msgs/CompressedImage` frames as a video stream.
+///
+/// Frames are passed through without re-encoding, so the publisher must
+/// send Annex B H.264, e.g. `ffmpeg_image_transport` with the `h264` format.
+/// A video stream is a fraction of the size of per-frame images, but the
+/// viewer has to decode from the previous keyframe when seeking, so
+/// publishers should emit keyframes regularly.
+///
+/// Raw `sensor_msgs/Image` topics are not encoded to video, as that needs
+/// an H.264 encoder, and the stream goes to every sink of the topic alike.
+#[derive(Clone, Debug, Default)]
+pub struct SensorCompressedImageToVideoStream {}
+
+impl SensorCompressedImageToVideoStream {
+    fn convert(&self, msg: CompressedImage) -> Result<ConverterData, ConverterError> {
+        if !msg.is_h264() {
+            return Err(ConverterError::conversion(
+                self,
+                anyhow::anyhow!("unsupported video format '{}', expected h264", msg.format),
+            ));
+        }
+        let header = Header::from(&msg.header);
+        let sample = rerun::components::VideoSample(rerun::datatypes::Blob::from(msg.data));
+        let stream =
+            rerun::VideoStream::new(rerun::components::VideoCodec::H264).with_sample(sample);
+        Ok(ConverterData::new(stream).with_header(header))
+    }
+}
+
+impl ConverterCfg for SensorCompressedImageToVideoStream {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if !config.0.is_empty() {
+            return Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!(
+                    "SensorCompressedImageToVideoStream does not accept any configuration"
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Converter for SensorCompressedImageToVideoStream {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::VideoStream::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "SensorCompressedImageToVideoStream"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(CompressedImage::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let image: CompressedImage =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        self.convert(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_still_images() {
+        let converter = SensorCompressedImageToVideoStream::default();
+        let frame = |format: &str| CompressedImage {
+            format: format.to_owned(),
+            data: vec![0, 0, 0, 1],
+            ..Default::default()
+        };
+        assert!(converter.convert(frame("h264")).is_ok());
+        assert!(converter
+            .convert(frame("rgb8; jpeg compressed bgr8"))
+            .is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use rerun::Archetype as _;
+
+use crate::{
+    converter::{
+        Converter, ConverterCfg, ConverterData, ConverterError, ConverterSettings, Header,
+    },
+    definitions::{vision_msgs::Detection3DArray, ROSMessage as _},
+    dynamic_message::deserialize_view,
+    ROSTypeString, RerunName,
+};
+
+/// Oriented boxes of a set of 3D detections.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrientedBoxes {
+    pub centers: Vec<rerun::datatypes::Vec3D>,
+    pub half_sizes: Vec<rerun::datatypes::Vec3D>,
+    pub quaternions: Vec<rerun::Quaternion>,
+    pub labels: Vec<String>,
+}
+
+impl OrientedBoxes {
+    /// Boxes rotated by the orientation of each detection's bounding box.
+    pub fn from_detections(msg: &Detection3DArray) -> Self {
+        let mut boxes = Self::default();
+        for detection in &msg.detections {
+            let bbox = &detection.bbox;
+            let size = rerun::datatypes::Vec3D::from(&bbox.size);
+            boxes.centers.push((&bbox.center.position).into());
+            boxes.half_sizes.push(rerun::datatypes::Vec3D::new(
+                size.x() / 2.0,
+                size.y() / 2.0,
+                size.z() / 2.0,
+            ));
+            boxes.quaternions.push((&bbox.center.orientation).into());
+            boxes.labels.push(detection.label().to_owned());
+        }
+        boxes
+    }
+}
+
+impl From<OrientedBoxes> for rerun::Boxes3D {
+    fn from(boxes: OrientedBoxes) -> Self {
+        Self::from_centers_and_half_sizes(boxes.centers, boxes.half_sizes)
+            .with_quaternions(boxes.quaternions)
+            .with_labels(boxes.labels)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VisionDetection3DArrayToBoxes3D {}
+
+impl ConverterCfg for VisionDetection3DArrayToBoxes3D {
+    fn set_config(&mut self, config: ConverterSettings) -> Result<(), ConverterError> {
+        if config.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ConverterError::invalid_config(
+                self,
+                anyhow::anyhow!(
+                    "VisionDetection3DArrayToBoxes3D does not accept any configuration"
+                ),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl Converter for VisionDetection3DArrayToBoxes3D {
+    fn rerun_name(&self) -> RerunName {
+        RerunName::RerunArchetype(rerun::Boxes3D::name())
+    }
+
+    fn id(&self) -> &'static str {
+        "VisionDetection3DArrayToBoxes3D"
+    }
+
+    fn ros_type(&self) -> Option<&ROSTypeString<'static>> {
+        Some(Detection3DArray::ros_type())
+    }
+
+    async fn convert_view<'a>(
+        &self,
+        msg: rclrs::DynamicMessageView<'a>,
+    ) -> Result<ConverterData, ConverterError> {
+        let msg: Detection3DArray =
+            deserialize_view(&msg).map_err(|err| ConverterError::conversion(self, err))?;
+        let boxes = rerun::Boxes3D::from(OrientedBoxes::from_detections(&msg));
+        Ok(ConverterData::new(boxes).with_header(Header::from(&msg.header)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{
+        geometry_msgs::{Point, Pose, Quaternion, Vector3},
+        vision_msgs::{BoundingBox3D, Detection3D},
+    };
+
+    #[test]
+    fn rotated_box() {
+        let yaw_90 = Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: std::f64::consts::FRAC_1_SQRT_2,
+            w: std::f64::consts::FRAC_1_SQRT_2,
+        };
+        let msg = Detection3DArray {
+            detections: vec![Detection3D {
+                bbox: BoundingBox3D {
+                    center: Pose {
+                        position: Point {
+                            x: 1.0,
+                            y: 2.0,
+                            z: 0.5,
+                        },
+                        orientation: yaw_90,
+                    },
+                    size: Vector3 {
+                        x: 4.0,
+                        y: 2.0,
+                        z: 1.0,
+                    },
+                },
+                id: "car".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let boxes = OrientedBoxes::from_detections(&msg);
+        assert_eq!(
+            boxes.quaternions,
+            vec![rerun::Quaternion::from(&yaw_90)],
+            "orientation is kept"
+        );
+        assert_eq!(
+            boxes.half_sizes,
+            vec![rerun::datatypes::Vec3D::new(2.0, 1.0, 0.5)],
+            "sizes are in the box frame, not axis aligned"
+        );
+        assert_eq!(
+            boxes.centers,
+            vec![rerun::datatypes::Vec3D::new(1.0, 2.0, 0.5)]
+        );
+        assert_eq!(boxes.labels, vec!["car".to_owned()]);
+    }
+
+    #[test]
+    fn split_detections() {
+        use rerun::external::arrow::array::Array as _;
+
+        use crate::converter::InstanceMode;
+
+        let detection = |id: &str| Detection3D {
+            id: id.to_owned(),
+            ..Default::default()
+        };
+        let msg = Detection3DArray {
+            detections: vec![detection("car"), detection("bike")],
+            ..Default::default()
+        };
+        let boxes = || rerun::Boxes3D::from(OrientedBoxes::from_detections(&msg));
+        let data = InstanceMode::Instanced.apply(ConverterData::new(boxes()));
+        assert!(data.batch.is_empty(), "a single entity by default");
+
+        let data = InstanceMode::Split.apply(
+            ConverterData::new(boxes().with_radii([0.05])).with_header(Header {
+                stamp: Some(1),
+                frame: Some("base_link".to_owned()),
+            }),
+        );
+        let paths: Vec<_> = data
+            .batch
+            .iter()
+            .map(|entity| entity.entity_path.as_str())
+            .collect();
+        assert_eq!(paths, ["0", "1"]);
+        for entity in &data.batch {
+            assert_eq!(entity.header, data.header, "elements keep the header");
+            for batch in entity.components.as_serialized_batches() {
+                assert_eq!(batch.array.len(), 1, "{}", batch.descriptor);
+            }
+            let components: Vec<_> = entity
+                .components
+                .as_serialized_batches()
+                .iter()
+                .map(|batch| batch.descriptor.component.to_string())
+                .collect();
+            assert!(
+                components.contains(&"Boxes3D:radii".to_owned()),
+                "the shared radius is on every box"
+            );
+        }
+    }
+}
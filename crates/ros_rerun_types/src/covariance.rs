@@ -0,0 +1,112 @@
+//! Helpers for visualizing covariance matrices.
+
+use rerun::external::glam;
+
+/// A symmetric 3x3 matrix, row-major.
+pub type Matrix3 = [[f64; 3]; 3];
+
+/// The upper left 3x3 block of a row-major 6x6 covariance,
+/// i.e. the covariance of the linear part.
+///
+/// Returns `None` if the covariance does not have 36 values.
+pub fn linear_block(covariance: &[f64]) -> Option<Matrix3> {
+    if covariance.len() != 36 {
+        return None;
+    }
+    Some(std::array::from_fn(|row| {
+        std::array::from_fn(|col| covariance[row * 6 + col])
+    }))
+}
+
+/// Eigen-decomposition of a symmetric matrix with the Jacobi method.
+///
+/// Returns the eigenvalues and a matrix with the matching
+/// eigenvectors as columns.
+pub fn symmetric_eigen(mut a: Matrix3) -> ([f64; 3], Matrix3) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..32 {
+        let off_diagonal = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        if off_diagonal < 1e-30 {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            // Rotation zeroing a[p][q], applied as A' = J^T A J and V' = V J
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + theta.hypot(1.0));
+            let c = 1.0 / t.hypot(1.0);
+            let s = t * c;
+            for row in a.iter_mut().chain(v.iter_mut()) {
+                let (x, y) = (row[p], row[q]);
+                row[p] = c * x - s * y;
+                row[q] = s * x + c * y;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            a[p] = std::array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+            a[q] = std::array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+        }
+    }
+    ([a[0][0], a[1][1], a[2][2]], v)
+}
+
+/// Half sizes and orientation of the ellipsoid covering `sigma` standard
+/// deviations of a covariance.
+///
+/// Returns `None` if the covariance has non-finite values.
+pub fn ellipsoid(covariance: Matrix3, sigma: f32) -> Option<([f32; 3], rerun::Quaternion)> {
+    if !covariance.iter().flatten().all(|v| v.is_finite()) {
+        return None;
+    }
+    let (values, vectors) = symmetric_eigen(covariance);
+    // Negative eigenvalues are numerical noise of a degenerate covariance
+    let half_sizes = values.map(|value| sigma * value.max(0.0).sqrt() as f32);
+    let column = |col: usize| {
+        glam::Vec3::new(
+            vectors[0][col] as f32,
+            vectors[1][col] as f32,
+            vectors[2][col] as f32,
+        )
+    };
+    let (x, y, mut z) = (column(0), column(1), column(2));
+    // Eigenvectors may form a reflection, which is not a rotation
+    if x.cross(y).dot(z) < 0.0 {
+        z = -z;
+    }
+    let rotation = glam::Quat::from_mat3(&glam::Mat3::from_cols(x, y, z));
+    Some((half_sizes, rotation.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eigen_decomposition() {
+        let matrix = [[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 3.0]];
+        let (values, vectors) = symmetric_eigen(matrix);
+        let mut sorted = values;
+        sorted.sort_by(f64::total_cmp);
+        for (value, expected) in sorted.iter().zip([1.0, 3.0, 3.0]) {
+            assert!((value - expected).abs() < 1e-9, "{sorted:?}");
+        }
+        for (col, value) in values.iter().enumerate() {
+            let vector = vectors.map(|row| row[col]);
+            for (matrix_row, component) in matrix.iter().zip(vector) {
+                let product: f64 = matrix_row.iter().zip(vector).map(|(m, v)| m * v).sum();
+                assert!((product - value * component).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn linear_block_of_6x6() {
+        let covariance: Vec<f64> = (0..36).map(f64::from).collect();
+        assert_eq!(
+            linear_block(&covariance),
+            Some([[0.0, 1.0, 2.0], [6.0, 7.0, 8.0], [12.0, 13.0, 14.0]])
+        );
+        assert_eq!(linear_block(&covariance[..9]), None);
+    }
+}
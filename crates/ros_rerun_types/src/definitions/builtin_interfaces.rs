@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// `builtin_interfaces/Time`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Time {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+impl Time {
+    /// Nanoseconds since the Unix epoch.
+    pub fn as_nanos(&self) -> i64 {
+        i64::from(self.sec) * 1_000_000_000 + i64::from(self.nanosec)
+    }
+
+    /// Whether the time is unset, which many publishers use for "no stamp".
+    pub fn is_zero(&self) -> bool {
+        self.sec == 0 && self.nanosec == 0
+    }
+}
+
+/// `builtin_interfaces/Duration`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Duration {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+impl Duration {
+    /// Total length of the duration in nanoseconds.
+    pub fn as_nanos(&self) -> i64 {
+        i64::from(self.sec) * 1_000_000_000 + i64::from(self.nanosec)
+    }
+}
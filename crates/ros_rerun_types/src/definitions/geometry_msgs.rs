@@ -0,0 +1,237 @@
+use rerun::external::glam;
+use serde::{Deserialize, Serialize};
+
+use super::{ros_message, std_msgs::Header};
+
+/// `geometry_msgs/Vector3`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl From<&Vector3> for rerun::datatypes::Vec3D {
+    fn from(v: &Vector3) -> Self {
+        Self::new(v.x as f32, v.y as f32, v.z as f32)
+    }
+}
+
+/// `geometry_msgs/Twist`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Twist {
+    pub linear: Vector3,
+    pub angular: Vector3,
+}
+
+ros_message!(Twist, "geometry_msgs", "Twist");
+
+/// `geometry_msgs/TwistStamped`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TwistStamped {
+    pub header: Header,
+    pub twist: Twist,
+}
+
+ros_message!(TwistStamped, "geometry_msgs", "TwistStamped");
+
+/// `geometry_msgs/Accel`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Accel {
+    pub linear: Vector3,
+    pub angular: Vector3,
+}
+
+ros_message!(Accel, "geometry_msgs", "Accel");
+
+/// `geometry_msgs/AccelStamped`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct AccelStamped {
+    pub header: Header,
+    pub accel: Accel,
+}
+
+ros_message!(AccelStamped, "geometry_msgs", "AccelStamped");
+
+/// `geometry_msgs/Inertia`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Inertia {
+    /// Mass in kg
+    pub m: f64,
+    /// Center of mass in m
+    pub com: Vector3,
+    pub ixx: f64,
+    pub ixy: f64,
+    pub ixz: f64,
+    pub iyy: f64,
+    pub iyz: f64,
+    pub izz: f64,
+}
+
+ros_message!(Inertia, "geometry_msgs", "Inertia");
+
+impl Inertia {
+    /// The symmetric inertia tensor in kg m^2, row by row.
+    pub fn tensor(&self) -> [[f64; 3]; 3] {
+        [
+            [self.ixx, self.ixy, self.ixz],
+            [self.ixy, self.iyy, self.iyz],
+            [self.ixz, self.iyz, self.izz],
+        ]
+    }
+}
+
+/// `geometry_msgs/InertiaStamped`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct InertiaStamped {
+    pub header: Header,
+    pub inertia: Inertia,
+}
+
+ros_message!(InertiaStamped, "geometry_msgs", "InertiaStamped");
+
+/// `geometry_msgs/Point32`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point32 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+ros_message!(Point32, "geometry_msgs", "Point32");
+
+impl From<&Point32> for rerun::datatypes::Vec3D {
+    fn from(p: &Point32) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+
+/// `geometry_msgs/Point`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+ros_message!(Point, "geometry_msgs", "Point");
+
+impl From<&Point> for rerun::datatypes::Vec3D {
+    fn from(p: &Point) -> Self {
+        Self::new(p.x as f32, p.y as f32, p.z as f32)
+    }
+}
+
+/// `geometry_msgs/Quaternion`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }
+    }
+}
+
+impl Quaternion {
+    /// The normalized rotation, or the identity for a zero quaternion, which
+    /// RViz treats as no rotation.
+    pub fn rotation(&self) -> glam::DQuat {
+        let rotation = glam::DQuat::from_xyzw(self.x, self.y, self.z, self.w);
+        if rotation.length_squared() > 0.0 {
+            rotation.normalize()
+        } else {
+            glam::DQuat::IDENTITY
+        }
+    }
+}
+
+impl From<&Quaternion> for rerun::Quaternion {
+    fn from(q: &Quaternion) -> Self {
+        Self::from_xyzw([q.x as f32, q.y as f32, q.z as f32, q.w as f32])
+    }
+}
+
+/// `geometry_msgs/Pose`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    pub position: Point,
+    pub orientation: Quaternion,
+}
+
+ros_message!(Pose, "geometry_msgs", "Pose");
+
+/// `geometry_msgs/PoseStamped`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PoseStamped {
+    pub header: Header,
+    pub pose: Pose,
+}
+
+ros_message!(PoseStamped, "geometry_msgs", "PoseStamped");
+
+/// `geometry_msgs/PoseArray`, e.g. the particles of a particle filter.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PoseArray {
+    pub header: Header,
+    pub poses: Vec<Pose>,
+}
+
+ros_message!(PoseArray, "geometry_msgs", "PoseArray");
+
+/// `geometry_msgs/PoseWithCovariance`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PoseWithCovariance {
+    pub pose: Pose,
+    /// Row-major 6x6 covariance of x, y, z and the rotations about them.
+    pub covariance: Vec<f64>,
+}
+
+/// `geometry_msgs/TwistWithCovariance`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TwistWithCovariance {
+    pub twist: Twist,
+    /// Row-major 6x6 covariance of the linear and angular velocities.
+    pub covariance: Vec<f64>,
+}
+
+/// `geometry_msgs/Transform`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+}
+
+impl Transform {
+    /// Whether all values are finite and the rotation is not a zero quaternion.
+    pub fn is_valid(&self) -> bool {
+        let Vector3 { x, y, z } = self.translation;
+        let Quaternion {
+            x: qx,
+            y: qy,
+            z: qz,
+            w: qw,
+        } = self.rotation;
+        [x, y, z, qx, qy, qz, qw].iter().all(|v| v.is_finite())
+            && [qx, qy, qz, qw].iter().any(|v| *v != 0.0)
+    }
+}
+
+/// `geometry_msgs/TransformStamped`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TransformStamped {
+    pub header: Header,
+    pub child_frame_id: String,
+    pub transform: Transform,
+}
+
+ros_message!(TransformStamped, "geometry_msgs", "TransformStamped");
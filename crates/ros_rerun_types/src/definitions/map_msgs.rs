@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ros_message, std_msgs::Header};
+
+/// `map_msgs/OccupancyGridUpdate`
+///
+/// A patch of `width` x `height` cells to copy into the
+/// full grid, with its lower left corner at cell (`x`, `y`).
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OccupancyGridUpdate {
+    pub header: Header,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<i8>,
+}
+
+ros_message!(OccupancyGridUpdate, "map_msgs", "OccupancyGridUpdate");
@@ -0,0 +1,39 @@
+//! Typed definitions of commonly used ROS messages.
+//!
+//! Converters deserialize dynamic messages into these definitions with
+//! [`crate::dynamic_message::deserialize_view`]. Definitions may omit fields
+//! that no converter needs, unknown fields are ignored when deserializing.
+
+use serde::de::DeserializeOwned;
+
+use crate::ROSTypeString;
+
+pub mod builtin_interfaces;
+pub mod geometry_msgs;
+pub mod map_msgs;
+pub mod nav_msgs;
+pub mod sensor_msgs;
+pub mod std_msgs;
+pub mod tf2_msgs;
+pub mod trajectory_msgs;
+pub mod vision_msgs;
+pub mod visualization_msgs;
+
+/// A typed ROS message definition.
+pub trait ROSMessage: DeserializeOwned + Send + Sync + 'static {
+    /// The ROS type this definition is deserialized from.
+    fn ros_type() -> &'static ROSTypeString<'static>;
+}
+
+/// Implement [`ROSMessage`] for a definition.
+macro_rules! ros_message {
+    ($ty:ty, $package:literal, $name:literal) => {
+        impl $crate::definitions::ROSMessage for $ty {
+            fn ros_type() -> &'static $crate::ROSTypeString<'static> {
+                &const { $crate::ROSTypeString::new($package, $name) }
+            }
+        }
+    };
+}
+
+pub(crate) use ros_message;
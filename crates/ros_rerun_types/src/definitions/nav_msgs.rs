@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    builtin_interfaces::Time,
+    geometry_msgs::{Point, Pose, PoseStamped, PoseWithCovariance, TwistWithCovariance},
+    ros_message,
+    std_msgs::Header,
+};
+
+/// `nav_msgs/MapMetaData`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct MapMetaData {
+    pub map_load_time: Time,
+    pub resolution: f32,
+    pub width: u32,
+    pub height: u32,
+    pub origin: Pose,
+}
+
+/// `nav_msgs/OccupancyGrid`
+///
+/// Cells are stored row-major starting at the origin,
+/// with values from 0 (free) to 100 (occupied) and -1 for unknown.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct OccupancyGrid {
+    pub header: Header,
+    pub info: MapMetaData,
+    pub data: Vec<i8>,
+}
+
+ros_message!(OccupancyGrid, "nav_msgs", "OccupancyGrid");
+
+/// `nav_msgs/GridCells`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct GridCells {
+    pub header: Header,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub cells: Vec<Point>,
+}
+
+ros_message!(GridCells, "nav_msgs", "GridCells");
+
+/// `nav_msgs/Odometry`
+///
+/// The pose is in the header frame, the twist in `child_frame_id`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Odometry {
+    pub header: Header,
+    pub child_frame_id: String,
+    pub pose: PoseWithCovariance,
+    pub twist: TwistWithCovariance,
+}
+
+ros_message!(Odometry, "nav_msgs", "Odometry");
+
+/// `nav_msgs/Path`, e.g. a planned path.
+///
+/// The poses each have a header, but are usually all in the frame of the path.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    pub header: Header,
+    pub poses: Vec<PoseStamped>,
+}
+
+ros_message!(Path, "nav_msgs", "Path");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{definitions::ROSMessage as _, dynamic_message::deserialize_view, ROSTypeName};
+
+    #[test]
+    fn deserializes_odometry() {
+        let ros_type = ROSTypeName::try_from(Odometry::ros_type()).expect("nav_msgs is available");
+        let msg = rclrs::DynamicMessage::new(ros_type.into()).expect("valid message type");
+        let odometry: Odometry = deserialize_view(&msg.view()).expect("matching definition");
+        assert_eq!(odometry.child_frame_id, "");
+        assert_eq!(odometry.pose.covariance.len(), 36, "fixed size 6x6 array");
+        assert_eq!(odometry.twist.covariance.len(), 36);
+        assert_eq!(odometry.pose.pose.orientation.w, 1.0, "identity rotation");
+    }
+}
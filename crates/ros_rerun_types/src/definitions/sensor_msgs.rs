@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    builtin_interfaces::Time,
+    geometry_msgs::{Point32, Quaternion, Vector3},
+    ros_message,
+    std_msgs::Header,
+};
+
+/// `sensor_msgs/PointField`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PointField {
+    pub name: String,
+    pub offset: u32,
+    pub datatype: u8,
+    pub count: u32,
+}
+
+impl PointField {
+    pub const INT8: u8 = 1;
+    pub const UINT8: u8 = 2;
+    pub const INT16: u8 = 3;
+    pub const UINT16: u8 = 4;
+    pub const INT32: u8 = 5;
+    pub const UINT32: u8 = 6;
+    pub const FLOAT32: u8 = 7;
+    pub const FLOAT64: u8 = 8;
+
+    /// Name of the field datatype, as used in the message definition.
+    pub fn datatype_name(&self) -> &'static str {
+        match self.datatype {
+            Self::INT8 => "INT8",
+            Self::UINT8 => "UINT8",
+            Self::INT16 => "INT16",
+            Self::UINT16 => "UINT16",
+            Self::INT32 => "INT32",
+            Self::UINT32 => "UINT32",
+            Self::FLOAT32 => "FLOAT32",
+            Self::FLOAT64 => "FLOAT64",
+            _ => "UNKNOWN",
+        }
+    }
+}
+
+/// `sensor_msgs/PointCloud2`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PointCloud2 {
+    pub header: Header,
+    pub height: u32,
+    pub width: u32,
+    pub fields: Vec<PointField>,
+    pub is_bigendian: bool,
+    pub point_step: u32,
+    pub row_step: u32,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    pub is_dense: bool,
+}
+
+ros_message!(PointCloud2, "sensor_msgs", "PointCloud2");
+
+/// `sensor_msgs/Image`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Image {
+    pub header: Header,
+    pub height: u32,
+    pub width: u32,
+    pub encoding: String,
+    pub is_bigendian: u8,
+    /// Length of a row in bytes, including any padding.
+    pub step: u32,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+ros_message!(Image, "sensor_msgs", "Image");
+
+/// `sensor_msgs/LaserScan`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct LaserScan {
+    pub header: Header,
+    pub angle_min: f32,
+    pub angle_max: f32,
+    pub angle_increment: f32,
+    pub time_increment: f32,
+    pub scan_time: f32,
+    pub range_min: f32,
+    pub range_max: f32,
+    pub ranges: Vec<f32>,
+    pub intensities: Vec<f32>,
+}
+
+ros_message!(LaserScan, "sensor_msgs", "LaserScan");
+
+/// `sensor_msgs/CompressedImage`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompressedImage {
+    pub header: Header,
+    pub format: String,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+ros_message!(CompressedImage, "sensor_msgs", "CompressedImage");
+
+impl CompressedImage {
+    /// Whether `data` is an H.264 encoded video frame rather than a still image.
+    pub fn is_h264(&self) -> bool {
+        self.format.to_ascii_lowercase().contains("h264")
+    }
+}
+
+/// `sensor_msgs/RegionOfInterest`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegionOfInterest {
+    pub x_offset: u32,
+    pub y_offset: u32,
+    pub height: u32,
+    pub width: u32,
+    pub do_rectify: bool,
+}
+
+ros_message!(RegionOfInterest, "sensor_msgs", "RegionOfInterest");
+
+impl RegionOfInterest {
+    /// Whether the region is unset, which `CameraInfo` uses for the full image.
+    pub fn is_full_image(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// `sensor_msgs/ChannelFloat32`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ChannelFloat32 {
+    pub name: String,
+    pub values: Vec<f32>,
+}
+
+ros_message!(ChannelFloat32, "sensor_msgs", "ChannelFloat32");
+
+/// `sensor_msgs/PointCloud`, the legacy predecessor of `PointCloud2`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PointCloud {
+    pub header: Header,
+    pub points: Vec<Point32>,
+    pub channels: Vec<ChannelFloat32>,
+}
+
+ros_message!(PointCloud, "sensor_msgs", "PointCloud");
+
+impl PointCloud {
+    /// Values of the channel called `name`, if it has one value per point.
+    pub fn channel(&self, name: &str) -> Option<&[f32]> {
+        self.channels
+            .iter()
+            .find(|channel| channel.name == name)
+            .map(|channel| channel.values.as_slice())
+            .filter(|values| values.len() == self.points.len())
+    }
+}
+
+/// `sensor_msgs/TimeReference`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeReference {
+    pub header: Header,
+    pub time_ref: Time,
+    pub source: String,
+}
+
+ros_message!(TimeReference, "sensor_msgs", "TimeReference");
+
+impl TimeReference {
+    /// Offset of the local clock from the reference clock, in seconds.
+    pub fn offset_secs(&self) -> f64 {
+        let nanos = self.header.stamp.as_nanos() - self.time_ref.as_nanos();
+        nanos as f64 / 1e9
+    }
+}
+
+/// `sensor_msgs/RelativeHumidity`, from 0 (dry) to 1 (saturated).
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct RelativeHumidity {
+    pub header: Header,
+    pub relative_humidity: f64,
+    pub variance: f64,
+}
+
+ros_message!(RelativeHumidity, "sensor_msgs", "RelativeHumidity");
+
+/// `sensor_msgs/Illuminance` in lux.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Illuminance {
+    pub header: Header,
+    pub illuminance: f64,
+    pub variance: f64,
+}
+
+ros_message!(Illuminance, "sensor_msgs", "Illuminance");
+
+/// `sensor_msgs/FluidPressure` in Pascals.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct FluidPressure {
+    pub header: Header,
+    pub fluid_pressure: f64,
+    pub variance: f64,
+}
+
+ros_message!(FluidPressure, "sensor_msgs", "FluidPressure");
+
+/// `sensor_msgs/Temperature` in degrees Celsius.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Temperature {
+    pub header: Header,
+    pub temperature: f64,
+    pub variance: f64,
+}
+
+ros_message!(Temperature, "sensor_msgs", "Temperature");
+
+/// `sensor_msgs/MagneticField` in Tesla.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct MagneticField {
+    pub header: Header,
+    pub magnetic_field: Vector3,
+    /// Row-major 3x3 covariance, all zeros if unknown.
+    pub magnetic_field_covariance: Vec<f64>,
+}
+
+ros_message!(MagneticField, "sensor_msgs", "MagneticField");
+
+/// `sensor_msgs/Imu`
+///
+/// The accelerometer measures the specific force, so a device at rest
+/// reads an acceleration of about 9.81 m/s² pointing up.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Imu {
+    pub header: Header,
+    pub orientation: Quaternion,
+    /// Row-major 3x3 covariance, with -1 in the first element if the
+    /// orientation is not estimated.
+    pub orientation_covariance: Vec<f64>,
+    pub angular_velocity: Vector3,
+    pub angular_velocity_covariance: Vec<f64>,
+    pub linear_acceleration: Vector3,
+    pub linear_acceleration_covariance: Vec<f64>,
+}
+
+ros_message!(Imu, "sensor_msgs", "Imu");
+
+impl Imu {
+    /// Whether the IMU estimates its orientation, which it signals
+    /// by setting the first element of the covariance to -1 if not.
+    pub fn has_orientation(&self) -> bool {
+        self.orientation_covariance
+            .first()
+            .is_none_or(|&first| first != -1.0)
+    }
+}
+
+/// `sensor_msgs/Range` of a single beam sensor, e.g. ultrasonic or infrared,
+/// measured along the x axis of the header frame.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Range {
+    pub header: Header,
+    pub radiation_type: u8,
+    /// Angular size of the arc the distance reading is valid for, in radians.
+    pub field_of_view: f32,
+    pub min_range: f32,
+    pub max_range: f32,
+    pub range: f32,
+}
+
+ros_message!(Range, "sensor_msgs", "Range");
+
+impl Range {
+    pub const ULTRASOUND: u8 = 0;
+    pub const INFRARED: u8 = 1;
+}
+
+/// `sensor_msgs/BatteryState`
+///
+/// Values that are not measured are NaN.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct BatteryState {
+    pub header: Header,
+    /// Voltage in volts.
+    pub voltage: f32,
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    /// Current in amperes, negative when discharging.
+    pub current: f32,
+    /// Charge in amp-hours.
+    pub charge: f32,
+    /// Capacity in amp-hours.
+    pub capacity: f32,
+    pub design_capacity: f32,
+    /// Charge percentage from 0 to 1.
+    pub percentage: f32,
+    pub power_supply_status: u8,
+    pub power_supply_health: u8,
+    pub power_supply_technology: u8,
+    pub present: bool,
+    pub cell_voltage: Vec<f32>,
+    pub cell_temperature: Vec<f32>,
+    pub location: String,
+    pub serial_number: String,
+}
+
+ros_message!(BatteryState, "sensor_msgs", "BatteryState");
+
+impl BatteryState {
+    pub const POWER_SUPPLY_STATUS_UNKNOWN: u8 = 0;
+    pub const POWER_SUPPLY_STATUS_CHARGING: u8 = 1;
+    pub const POWER_SUPPLY_STATUS_DISCHARGING: u8 = 2;
+    pub const POWER_SUPPLY_STATUS_NOT_CHARGING: u8 = 3;
+    pub const POWER_SUPPLY_STATUS_FULL: u8 = 4;
+
+    pub const POWER_SUPPLY_HEALTH_UNKNOWN: u8 = 0;
+    pub const POWER_SUPPLY_HEALTH_GOOD: u8 = 1;
+    pub const POWER_SUPPLY_HEALTH_OVERHEAT: u8 = 2;
+    pub const POWER_SUPPLY_HEALTH_DEAD: u8 = 3;
+    pub const POWER_SUPPLY_HEALTH_OVERVOLTAGE: u8 = 4;
+    pub const POWER_SUPPLY_HEALTH_UNSPEC_FAILURE: u8 = 5;
+    pub const POWER_SUPPLY_HEALTH_COLD: u8 = 6;
+    pub const POWER_SUPPLY_HEALTH_WATCHDOG_TIMER_EXPIRE: u8 = 7;
+    pub const POWER_SUPPLY_HEALTH_SAFETY_TIMER_EXPIRE: u8 = 8;
+
+    /// Name of the charging status, as in the message definition.
+    pub fn status_name(&self) -> &'static str {
+        match self.power_supply_status {
+            Self::POWER_SUPPLY_STATUS_CHARGING => "CHARGING",
+            Self::POWER_SUPPLY_STATUS_DISCHARGING => "DISCHARGING",
+            Self::POWER_SUPPLY_STATUS_NOT_CHARGING => "NOT_CHARGING",
+            Self::POWER_SUPPLY_STATUS_FULL => "FULL",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// Name of the battery health, as in the message definition.
+    pub fn health_name(&self) -> &'static str {
+        match self.power_supply_health {
+            Self::POWER_SUPPLY_HEALTH_GOOD => "GOOD",
+            Self::POWER_SUPPLY_HEALTH_OVERHEAT => "OVERHEAT",
+            Self::POWER_SUPPLY_HEALTH_DEAD => "DEAD",
+            Self::POWER_SUPPLY_HEALTH_OVERVOLTAGE => "OVERVOLTAGE",
+            Self::POWER_SUPPLY_HEALTH_UNSPEC_FAILURE => "UNSPEC_FAILURE",
+            Self::POWER_SUPPLY_HEALTH_COLD => "COLD",
+            Self::POWER_SUPPLY_HEALTH_WATCHDOG_TIMER_EXPIRE => "WATCHDOG_TIMER_EXPIRE",
+            Self::POWER_SUPPLY_HEALTH_SAFETY_TIMER_EXPIRE => "SAFETY_TIMER_EXPIRE",
+            _ => "UNKNOWN",
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use super::{builtin_interfaces::Time, ros_message};
+use crate::dynamic_message::{deserialize_view, with_path, DeserializeError};
+
+/// `std_msgs/Header`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Header {
+    pub stamp: Time,
+    pub frame_id: String,
+}
+
+ros_message!(Header, "std_msgs", "Header");
+
+impl Header {
+    /// Read the `header` field of any message, if it has one.
+    ///
+    /// # Errors
+    /// Returns a `DeserializeError` if the field is not a `std_msgs/Header`.
+    pub fn from_view(
+        view: &rclrs::DynamicMessageView<'_>,
+    ) -> Result<Option<Self>, DeserializeError> {
+        Self::from_path(view, "header")
+    }
+
+    /// Read a header at a dotted field `path` of any message, e.g. `meta.header`.
+    ///
+    /// Returns `None` if there is no field at the path.
+    ///
+    /// # Errors
+    /// Returns a `DeserializeError` if the field is not a `std_msgs/Header`.
+    pub fn from_path(
+        view: &rclrs::DynamicMessageView<'_>,
+        path: &str,
+    ) -> Result<Option<Self>, DeserializeError> {
+        with_path(view, path, |value| match value {
+            rclrs::Value::Simple(rclrs::SimpleValue::Message(header)) => deserialize_view(header),
+            _ => Err(DeserializeError::Custom(format!(
+                "'{path}' is not a std_msgs/Header"
+            ))),
+        })
+        .transpose()
+    }
+}
+
+/// `std_msgs/Empty`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Empty {}
+
+ros_message!(Empty, "std_msgs", "Empty");
+
+impl From<&Header> for crate::converter::Header {
+    fn from(header: &Header) -> Self {
+        Self {
+            stamp: (!header.stamp.is_zero()).then(|| header.stamp.as_nanos()),
+            frame: (!header.frame_id.is_empty()).then(|| header.frame_id.clone()),
+        }
+    }
+}
+
+/// `std_msgs/ColorRGBA`, with channels from 0 to 1.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColorRGBA {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+ros_message!(ColorRGBA, "std_msgs", "ColorRGBA");
+
+impl From<&ColorRGBA> for rerun::Color {
+    fn from(color: &ColorRGBA) -> Self {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::from_unmultiplied_rgba(
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            channel(color.a),
+        )
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::{geometry_msgs::TransformStamped, ros_message};
+
+/// `tf2_msgs/TFMessage`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TFMessage {
+    pub transforms: Vec<TransformStamped>,
+}
+
+ros_message!(TFMessage, "tf2_msgs", "TFMessage");
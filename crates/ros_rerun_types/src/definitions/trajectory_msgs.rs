@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use super::{builtin_interfaces::Duration, ros_message, std_msgs::Header};
+
+/// `trajectory_msgs/JointTrajectoryPoint`
+///
+/// Each of the arrays is either empty or has a value per joint of the
+/// trajectory, in the order of its `joint_names`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct JointTrajectoryPoint {
+    pub positions: Vec<f64>,
+    pub velocities: Vec<f64>,
+    pub accelerations: Vec<f64>,
+    pub effort: Vec<f64>,
+    pub time_from_start: Duration,
+}
+
+ros_message!(
+    JointTrajectoryPoint,
+    "trajectory_msgs",
+    "JointTrajectoryPoint"
+);
+
+/// `trajectory_msgs/JointTrajectory`, e.g. a motion plan.
+///
+/// The points are reached at their `time_from_start` after the header stamp,
+/// or after the trajectory is received if the stamp is zero.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct JointTrajectory {
+    pub header: Header,
+    pub joint_names: Vec<String>,
+    pub points: Vec<JointTrajectoryPoint>,
+}
+
+ros_message!(JointTrajectory, "trajectory_msgs", "JointTrajectory");
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    geometry_msgs::{Pose, PoseWithCovariance, Vector3},
+    ros_message,
+    std_msgs::Header,
+};
+
+/// `vision_msgs/ObjectHypothesis`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ObjectHypothesis {
+    pub class_id: String,
+    pub score: f64,
+}
+
+/// `vision_msgs/ObjectHypothesisWithPose`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ObjectHypothesisWithPose {
+    pub hypothesis: ObjectHypothesis,
+    pub pose: PoseWithCovariance,
+}
+
+/// `vision_msgs/BoundingBox3D`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct BoundingBox3D {
+    pub center: Pose,
+    pub size: Vector3,
+}
+
+/// `vision_msgs/Detection3D`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Detection3D {
+    pub header: Header,
+    pub results: Vec<ObjectHypothesisWithPose>,
+    pub bbox: BoundingBox3D,
+    pub id: String,
+}
+
+impl Detection3D {
+    /// Label of the detection, the most likely class or else the tracking id.
+    pub fn label(&self) -> &str {
+        self.results
+            .iter()
+            .max_by(|a, b| a.hypothesis.score.total_cmp(&b.hypothesis.score))
+            .map_or(self.id.as_str(), |result| {
+                result.hypothesis.class_id.as_str()
+            })
+    }
+}
+
+/// `vision_msgs/Detection3DArray`
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Detection3DArray {
+    pub header: Header,
+    pub detections: Vec<Detection3D>,
+}
+
+ros_message!(Detection3DArray, "vision_msgs", "Detection3DArray");
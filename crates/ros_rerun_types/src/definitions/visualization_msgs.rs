@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    geometry_msgs::{Point, Pose, Vector3},
+    ros_message,
+    std_msgs::{ColorRGBA, Header},
+};
+
+/// `visualization_msgs/Marker`, without the texture and mesh fields.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Marker {
+    pub header: Header,
+    pub ns: String,
+    pub id: i32,
+    #[serde(rename = "type")]
+    pub marker_type: i32,
+    pub action: i32,
+    pub pose: Pose,
+    pub scale: Vector3,
+    pub color: ColorRGBA,
+    pub points: Vec<Point>,
+    pub colors: Vec<ColorRGBA>,
+    pub text: String,
+}
+
+ros_message!(Marker, "visualization_msgs", "Marker");
+
+impl Marker {
+    pub const ARROW: i32 = 0;
+    pub const CUBE: i32 = 1;
+    pub const SPHERE: i32 = 2;
+    pub const CYLINDER: i32 = 3;
+    pub const LINE_STRIP: i32 = 4;
+    pub const LINE_LIST: i32 = 5;
+    pub const CUBE_LIST: i32 = 6;
+    pub const SPHERE_LIST: i32 = 7;
+    pub const POINTS: i32 = 8;
+    pub const TEXT_VIEW_FACING: i32 = 9;
+    pub const MESH_RESOURCE: i32 = 10;
+    pub const TRIANGLE_LIST: i32 = 11;
+
+    pub const ADD: i32 = 0;
+    pub const DELETE: i32 = 2;
+    pub const DELETEALL: i32 = 3;
+}
@@ -1,10 +1,48 @@
-use rclrs::{BaseType, DynamicMessageView, Value};
+use rclrs::{
+    ArrayValue, BaseType, BoundedSequenceValue, DynamicMessageView, MessageStructure,
+    SequenceValue, SimpleValue, Value, ValueKind,
+};
+
+use serde::Serialize;
+
+mod cdr;
+mod de;
+
+pub use cdr::{serialize_cdr, SerializeError};
+pub use de::{deserialize_view, DeserializeError};
+
+/// Limits applied when rendering a whole message as text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DumpLimits {
+    /// Maximum nesting depth of messages to render.
+    pub max_depth: usize,
+    /// Maximum number of elements rendered for each array.
+    pub max_array_len: usize,
+}
+
+impl Default for DumpLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_array_len: 16,
+        }
+    }
+}
 
 /// Provides methods for easier access to fields in a dynamic message.
 pub trait MessageVisitor {
     fn iter_by_type(&self, value_type: BaseType) -> impl Iterator<Item = Value<'_>>;
 
     fn get_string(&self, field_name: &str) -> Option<String>;
+
+    /// Get a numeric field as a `f64`, regardless of its integer or float type.
+    fn get_f64(&self, field_name: &str) -> Option<f64>;
+
+    /// Render all fields of the message as an indented key/value tree.
+    ///
+    /// Nested messages and arrays are rendered recursively,
+    /// truncated according to `limits`.
+    fn dump(&self, limits: DumpLimits) -> String;
 }
 
 impl MessageVisitor for DynamicMessageView<'_> {
@@ -24,4 +62,458 @@ impl MessageVisitor for DynamicMessageView<'_> {
             _ => None,
         }
     }
+
+    fn get_f64(&self, field_name: &str) -> Option<f64> {
+        match self.get(field_name) {
+            Some(Value::Simple(simple)) => simple_to_f64(&simple),
+            _ => None,
+        }
+    }
+
+    fn dump(&self, limits: DumpLimits) -> String {
+        let mut out = String::new();
+        dump_view(self, limits, 0, &mut out);
+        out
+    }
+}
+
+/// Borrowed elements of an array-like ROS field.
+///
+/// Arrays, sequences and bounded sequences are all normalized into
+/// this type, so consumers only need to handle each element type once.
+pub enum Elements<'a, 'msg> {
+    Float(&'a [f32]),
+    Double(&'a [f64]),
+    Boolean(&'a [bool]),
+    Uint8(&'a [u8]),
+    Int8(&'a [i8]),
+    Uint16(&'a [u16]),
+    Int16(&'a [i16]),
+    Uint32(&'a [u32]),
+    Int32(&'a [i32]),
+    Uint64(&'a [u64]),
+    Int64(&'a [i64]),
+    String(Vec<String>),
+    Message(&'a [DynamicMessageView<'msg>]),
+}
+
+impl Elements<'_, '_> {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Float(items) => items.len(),
+            Self::Double(items) => items.len(),
+            Self::Boolean(items) => items.len(),
+            Self::Uint8(items) => items.len(),
+            Self::Int8(items) => items.len(),
+            Self::Uint16(items) => items.len(),
+            Self::Int16(items) => items.len(),
+            Self::Uint32(items) => items.len(),
+            Self::Int32(items) => items.len(),
+            Self::Uint64(items) => items.len(),
+            Self::Int64(items) => items.len(),
+            Self::String(items) => items.len(),
+            Self::Message(items) => items.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Get the elements of an array, sequence or bounded sequence value.
+///
+/// Returns `None` for simple values and for element types
+/// that are not supported (long doubles, wide and bounded strings).
+pub fn elements<'a, 'msg>(value: &'a Value<'msg>) -> Option<Elements<'a, 'msg>> {
+    let elements = match value {
+        Value::Simple(_) => return None,
+        Value::Array(array) => match array {
+            ArrayValue::FloatArray(items) => Elements::Float(&items[..]),
+            ArrayValue::DoubleArray(items) => Elements::Double(&items[..]),
+            ArrayValue::BooleanArray(items) => Elements::Boolean(&items[..]),
+            ArrayValue::CharArray(items)
+            | ArrayValue::OctetArray(items)
+            | ArrayValue::Uint8Array(items) => Elements::Uint8(&items[..]),
+            ArrayValue::Int8Array(items) => Elements::Int8(&items[..]),
+            ArrayValue::WCharArray(items) | ArrayValue::Uint16Array(items) => {
+                Elements::Uint16(&items[..])
+            }
+            ArrayValue::Int16Array(items) => Elements::Int16(&items[..]),
+            ArrayValue::Uint32Array(items) => Elements::Uint32(&items[..]),
+            ArrayValue::Int32Array(items) => Elements::Int32(&items[..]),
+            ArrayValue::Uint64Array(items) => Elements::Uint64(&items[..]),
+            ArrayValue::Int64Array(items) => Elements::Int64(&items[..]),
+            ArrayValue::StringArray(items) => {
+                Elements::String(items.iter().map(ToString::to_string).collect())
+            }
+            ArrayValue::MessageArray(items) => Elements::Message(&items[..]),
+            _ => return None,
+        },
+        Value::Sequence(sequence) => match sequence {
+            SequenceValue::FloatSequence(items) => Elements::Float(&items[..]),
+            SequenceValue::DoubleSequence(items) => Elements::Double(&items[..]),
+            SequenceValue::BooleanSequence(items) => Elements::Boolean(&items[..]),
+            SequenceValue::CharSequence(items)
+            | SequenceValue::OctetSequence(items)
+            | SequenceValue::Uint8Sequence(items) => Elements::Uint8(&items[..]),
+            SequenceValue::Int8Sequence(items) => Elements::Int8(&items[..]),
+            SequenceValue::WCharSequence(items) | SequenceValue::Uint16Sequence(items) => {
+                Elements::Uint16(&items[..])
+            }
+            SequenceValue::Int16Sequence(items) => Elements::Int16(&items[..]),
+            SequenceValue::Uint32Sequence(items) => Elements::Uint32(&items[..]),
+            SequenceValue::Int32Sequence(items) => Elements::Int32(&items[..]),
+            SequenceValue::Uint64Sequence(items) => Elements::Uint64(&items[..]),
+            SequenceValue::Int64Sequence(items) => Elements::Int64(&items[..]),
+            SequenceValue::StringSequence(items) => {
+                Elements::String(items.iter().map(ToString::to_string).collect())
+            }
+            SequenceValue::MessageSequence(items) => Elements::Message(&items[..]),
+            _ => return None,
+        },
+        Value::BoundedSequence(sequence) => match sequence {
+            BoundedSequenceValue::FloatBoundedSequence(items) => Elements::Float(&items[..]),
+            BoundedSequenceValue::DoubleBoundedSequence(items) => Elements::Double(&items[..]),
+            BoundedSequenceValue::BooleanBoundedSequence(items) => Elements::Boolean(&items[..]),
+            BoundedSequenceValue::CharBoundedSequence(items)
+            | BoundedSequenceValue::OctetBoundedSequence(items)
+            | BoundedSequenceValue::Uint8BoundedSequence(items) => Elements::Uint8(&items[..]),
+            BoundedSequenceValue::Int8BoundedSequence(items) => Elements::Int8(&items[..]),
+            BoundedSequenceValue::WCharBoundedSequence(items)
+            | BoundedSequenceValue::Uint16BoundedSequence(items) => Elements::Uint16(&items[..]),
+            BoundedSequenceValue::Int16BoundedSequence(items) => Elements::Int16(&items[..]),
+            BoundedSequenceValue::Uint32BoundedSequence(items) => Elements::Uint32(&items[..]),
+            BoundedSequenceValue::Int32BoundedSequence(items) => Elements::Int32(&items[..]),
+            BoundedSequenceValue::Uint64BoundedSequence(items) => Elements::Uint64(&items[..]),
+            BoundedSequenceValue::Int64BoundedSequence(items) => Elements::Int64(&items[..]),
+            BoundedSequenceValue::StringBoundedSequence(items) => {
+                Elements::String(items.iter().map(ToString::to_string).collect())
+            }
+            BoundedSequenceValue::MessageBoundedSequence(items) => Elements::Message(&items[..]),
+            _ => return None,
+        },
+    };
+    Some(elements)
+}
+
+/// Call `f` with the value at a dotted `path` of nested message fields,
+/// e.g. `skeleton.joints`.
+///
+/// Returns `None` if a field along the path is missing or not a message.
+pub fn with_path<R>(
+    view: &DynamicMessageView<'_>,
+    path: &str,
+    f: impl FnOnce(&Value<'_>) -> R,
+) -> Option<R> {
+    let (name, rest) = match path.split_once('.') {
+        Some((name, rest)) => (name, Some(rest)),
+        None => (path, None),
+    };
+    let value = view.get(name)?;
+    match (rest, &value) {
+        (None, _) => Some(f(&value)),
+        (Some(rest), Value::Simple(SimpleValue::Message(nested))) => with_path(nested, rest, f),
+        _ => None,
+    }
+}
+
+/// Convert a numeric simple value to a `f64`.
+///
+/// Returns `None` for strings, messages and other non-numeric values.
+pub fn simple_to_f64(value: &SimpleValue<'_>) -> Option<f64> {
+    let value = match value {
+        SimpleValue::Float(v) => f64::from(**v),
+        SimpleValue::Double(v) => **v,
+        SimpleValue::Uint8(v) | SimpleValue::Octet(v) => f64::from(**v),
+        SimpleValue::Int8(v) => f64::from(**v),
+        SimpleValue::Uint16(v) => f64::from(**v),
+        SimpleValue::Int16(v) => f64::from(**v),
+        SimpleValue::Uint32(v) => f64::from(**v),
+        SimpleValue::Int32(v) => f64::from(**v),
+        SimpleValue::Uint64(v) => **v as f64,
+        SimpleValue::Int64(v) => **v as f64,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Convert an integer simple value to an `i64`.
+///
+/// Returns `None` for non-integer values and for `u64` values beyond `i64::MAX`.
+pub fn simple_to_i64(value: &SimpleValue<'_>) -> Option<i64> {
+    let value = match value {
+        SimpleValue::Uint8(v) | SimpleValue::Octet(v) => i64::from(**v),
+        SimpleValue::Int8(v) => i64::from(**v),
+        SimpleValue::Uint16(v) => i64::from(**v),
+        SimpleValue::Int16(v) => i64::from(**v),
+        SimpleValue::Uint32(v) => i64::from(**v),
+        SimpleValue::Int32(v) => i64::from(**v),
+        SimpleValue::Uint64(v) => i64::try_from(**v).ok()?,
+        SimpleValue::Int64(v) => **v,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Format a simple (non-message) value for display.
+pub fn format_simple(value: &SimpleValue<'_>) -> String {
+    match value {
+        SimpleValue::Float(v) => v.to_string(),
+        SimpleValue::Double(v) => v.to_string(),
+        SimpleValue::Boolean(v) => v.to_string(),
+        SimpleValue::Char(v) | SimpleValue::Octet(v) | SimpleValue::Uint8(v) => v.to_string(),
+        SimpleValue::Int8(v) => v.to_string(),
+        SimpleValue::WChar(v) | SimpleValue::Uint16(v) => v.to_string(),
+        SimpleValue::Int16(v) => v.to_string(),
+        SimpleValue::Uint32(v) => v.to_string(),
+        SimpleValue::Int32(v) => v.to_string(),
+        SimpleValue::Uint64(v) => v.to_string(),
+        SimpleValue::Int64(v) => v.to_string(),
+        SimpleValue::String(v) => format!("{:?}", v.to_string()),
+        SimpleValue::Message(_) => "{...}".to_owned(),
+        _ => "<unsupported>".to_owned(),
+    }
+}
+
+fn dump_view(view: &DynamicMessageView<'_>, limits: DumpLimits, depth: usize, out: &mut String) {
+    for field in &view.fields {
+        if let Some(value) = view.get(&field.name) {
+            dump_field(&field.name, &value, limits, depth, out);
+        }
+    }
+}
+
+fn dump_field(name: &str, value: &Value<'_>, limits: DumpLimits, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let nested_allowed = depth + 1 < limits.max_depth;
+    match value {
+        Value::Simple(SimpleValue::Message(view)) => {
+            if nested_allowed {
+                out.push_str(&format!("{indent}{name}:\n"));
+                dump_view(view, limits, depth + 1, out);
+            } else {
+                out.push_str(&format!("{indent}{name}: {{...}}\n"));
+            }
+        }
+        Value::Simple(simple) => {
+            out.push_str(&format!("{indent}{name}: {}\n", format_simple(simple)));
+        }
+        _ => match elements(value) {
+            Some(Elements::Message(views)) => {
+                out.push_str(&format!("{indent}{name}: ({} items)\n", views.len()));
+                if !nested_allowed {
+                    return;
+                }
+                for (i, view) in views.iter().take(limits.max_array_len).enumerate() {
+                    out.push_str(&format!("{indent}  [{i}]:\n"));
+                    dump_view(view, limits, depth + 2, out);
+                }
+                if views.len() > limits.max_array_len {
+                    let remaining = views.len() - limits.max_array_len;
+                    out.push_str(&format!("{indent}  ... ({remaining} more)\n"));
+                }
+            }
+            Some(elements) => {
+                out.push_str(&format!(
+                    "{indent}{name}: {}\n",
+                    format_elements(&elements, limits.max_array_len)
+                ));
+            }
+            None => out.push_str(&format!("{indent}{name}: <unsupported>\n")),
+        },
+    }
+}
+
+fn format_elements(elements: &Elements<'_, '_>, max_len: usize) -> String {
+    fn join<T: std::fmt::Display>(items: &[T], max_len: usize) -> String {
+        let shown = items
+            .iter()
+            .take(max_len)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if items.len() > max_len {
+            format!("[{shown}, ... ({} more)]", items.len() - max_len)
+        } else {
+            format!("[{shown}]")
+        }
+    }
+
+    match elements {
+        Elements::Float(items) => join(items, max_len),
+        Elements::Double(items) => join(items, max_len),
+        Elements::Boolean(items) => join(items, max_len),
+        Elements::Uint8(items) => join(items, max_len),
+        Elements::Int8(items) => join(items, max_len),
+        Elements::Uint16(items) => join(items, max_len),
+        Elements::Int16(items) => join(items, max_len),
+        Elements::Uint32(items) => join(items, max_len),
+        Elements::Int32(items) => join(items, max_len),
+        Elements::Uint64(items) => join(items, max_len),
+        Elements::Int64(items) => join(items, max_len),
+        Elements::String(items) => join(
+            &items.iter().map(|s| format!("{s:?}")).collect::<Vec<_>>(),
+            max_len,
+        ),
+        Elements::Message(items) => format!("({} items)", items.len()),
+    }
+}
+
+/// How many values a field holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Single,
+    /// Fixed size array with the given length.
+    Array(usize),
+    Sequence,
+    BoundedSequence,
+}
+
+/// A field of a message definition, as listed by `schema`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldSchema {
+    /// Dotted path of the field, as accepted by `with_path`, e.g. `header.stamp`.
+    pub path: String,
+    /// The `rclrs::BaseType` of the field, or `Message` for nested messages.
+    pub base_type: String,
+    pub kind: FieldKind,
+}
+
+impl std::fmt::Display for FieldSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.base_type)?;
+        match self.kind {
+            FieldKind::Single => Ok(()),
+            FieldKind::Array(len) => write!(f, "[{len}]"),
+            FieldKind::Sequence => write!(f, "[]"),
+            FieldKind::BoundedSequence => write!(f, "[<=]"),
+        }
+    }
+}
+
+/// List the fields of a message, with the fields of nested messages
+/// following their parent.
+///
+/// The fields of messages in sequences are read from the first element,
+/// or from the message definition when the sequence is empty, so a view
+/// of a default message lists the whole definition.
+pub fn schema(view: &DynamicMessageView<'_>) -> Vec<FieldSchema> {
+    let mut fields = Vec::new();
+    schema_fields(view, "", &mut fields);
+    fields
+}
+
+fn schema_fields(view: &DynamicMessageView<'_>, prefix: &str, out: &mut Vec<FieldSchema>) {
+    for field in &view.fields {
+        let Some(value) = view.get(&field.name) else {
+            continue;
+        };
+        let path = format!("{prefix}{}", field.name);
+        let kind = match &value {
+            Value::Simple(_) => FieldKind::Single,
+            Value::Array(_) => FieldKind::Array(elements(&value).map_or(0, |items| items.len())),
+            Value::Sequence(_) => FieldKind::Sequence,
+            Value::BoundedSequence(_) => FieldKind::BoundedSequence,
+        };
+        let nested = match &value {
+            Value::Simple(SimpleValue::Message(nested)) => Some(Some(nested)),
+            _ => match elements(&value) {
+                Some(Elements::Message(views)) => Some(views.first()),
+                _ => None,
+            },
+        };
+        let base_type = match nested {
+            Some(_) => "Message".to_owned(),
+            None => format!("{:?}", field.base_type),
+        };
+        out.push(FieldSchema {
+            path: path.clone(),
+            base_type,
+            kind,
+        });
+        match nested {
+            Some(Some(nested)) => schema_fields(nested, &format!("{path}."), out),
+            // An empty sequence has no element to read the fields from
+            Some(None) => {
+                if let BaseType::Message(structure) = &field.base_type {
+                    structure_fields(structure, &format!("{path}."), out);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// List the fields of a message definition, like `schema_fields` does
+/// for the fields of a message view.
+fn structure_fields(structure: &MessageStructure, prefix: &str, out: &mut Vec<FieldSchema>) {
+    for field in &structure.fields {
+        let path = format!("{prefix}{}", field.name);
+        let kind = match field.value_kind {
+            ValueKind::Simple => FieldKind::Single,
+            ValueKind::Array { length } => FieldKind::Array(length),
+            ValueKind::Sequence => FieldKind::Sequence,
+            ValueKind::BoundedSequence { .. } => FieldKind::BoundedSequence,
+        };
+        let nested = match &field.base_type {
+            BaseType::Message(nested) => Some(nested),
+            _ => None,
+        };
+        let base_type = match nested {
+            Some(_) => "Message".to_owned(),
+            None => format!("{:?}", field.base_type),
+        };
+        out.push(FieldSchema {
+            path: path.clone(),
+            base_type,
+            kind,
+        });
+        if let Some(nested) = nested {
+            structure_fields(nested, &format!("{path}."), out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ROSTypeName;
+
+    #[test]
+    fn schema_lists_fields_of_nested_sequences() {
+        let ros_type = ROSTypeName::new("visualization_msgs", "MarkerArray");
+        let msg = rclrs::DynamicMessage::new(ros_type.into()).expect("valid message type");
+        let fields = schema(&msg.view());
+        let field = |path: &str| {
+            fields
+                .iter()
+                .find(|field| field.path == path)
+                .map(|field| (field.base_type.as_str(), field.kind))
+        };
+        assert_eq!(field("markers"), Some(("Message", FieldKind::Sequence)));
+        assert_eq!(
+            field("markers.header.frame_id").map(|(_, kind)| kind),
+            Some(FieldKind::Single),
+            "fields of an empty sequence"
+        );
+        assert_eq!(
+            field("markers.points"),
+            Some(("Message", FieldKind::Sequence))
+        );
+        assert!(
+            field("markers.points.x").is_some(),
+            "fields of a sequence in a sequence"
+        );
+        let position = fields
+            .iter()
+            .position(|field| field.path == "markers.points")
+            .expect("listed");
+        assert_eq!(
+            fields[position + 1].path,
+            "markers.points.x",
+            "nested fields follow their parent"
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use rclrs::{DynamicMessageView, SimpleValue, Value};
+use thiserror::Error;
+
+use super::{elements, Elements};
+
+/// Errors occurring while serializing a dynamic message.
+#[derive(Debug, Error)]
+pub enum SerializeError {
+    #[error("unsupported value type in dynamic message")]
+    Unsupported,
+}
+
+/// Little endian CDR encapsulation header, as used by ROS 2 middlewares.
+const CDR_LE_HEADER: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Serialize a dynamic message view into little endian CDR.
+///
+/// This is the encoding ROS 2 uses on the wire and in bags,
+/// so the result can be recorded and replayed by ROS tooling.
+///
+/// # Errors
+/// Returns a `SerializeError` for value types that cannot be serialized,
+/// i.e. long doubles, wide strings and bounded strings.
+pub fn serialize_cdr(view: &DynamicMessageView<'_>) -> Result<Vec<u8>, SerializeError> {
+    let mut writer = CdrWriter {
+        buf: CDR_LE_HEADER.to_vec(),
+    };
+    writer.message(view)?;
+    Ok(writer.buf)
+}
+
+struct CdrWriter {
+    buf: Vec<u8>,
+}
+
+impl CdrWriter {
+    /// Pad to a multiple of `size`, relative to the end of the header.
+    fn align(&mut self, size: usize) {
+        let position = self.buf.len() - CDR_LE_HEADER.len();
+        let padding = (size - position % size) % size;
+        self.buf.resize(self.buf.len() + padding, 0);
+    }
+
+    fn primitive<const N: usize>(&mut self, bytes: [u8; N]) {
+        self.align(N);
+        self.buf.extend_from_slice(&bytes);
+    }
+
+    fn length(&mut self, len: usize) -> Result<(), SerializeError> {
+        let len = u32::try_from(len).map_err(|_| SerializeError::Unsupported)?;
+        self.primitive(len.to_le_bytes());
+        Ok(())
+    }
+
+    fn string(&mut self, value: &str) -> Result<(), SerializeError> {
+        // The length includes the null terminator
+        self.length(value.len() + 1)?;
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(0);
+        Ok(())
+    }
+
+    fn message(&mut self, view: &DynamicMessageView<'_>) -> Result<(), SerializeError> {
+        for field in &view.fields {
+            let value = view.get(&field.name).ok_or(SerializeError::Unsupported)?;
+            self.value(&value)?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, value: &Value<'_>) -> Result<(), SerializeError> {
+        let simple = match value {
+            Value::Simple(simple) => simple,
+            // Fixed size arrays have no length prefix
+            Value::Array(_) => return self.elements(value),
+            Value::Sequence(_) | Value::BoundedSequence(_) => {
+                let items = elements(value).ok_or(SerializeError::Unsupported)?;
+                self.length(items.len())?;
+                return self.elements(value);
+            }
+        };
+        match simple {
+            SimpleValue::Float(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Double(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Boolean(v) => self.primitive([u8::from(**v)]),
+            SimpleValue::Char(v) | SimpleValue::Octet(v) | SimpleValue::Uint8(v) => {
+                self.primitive([**v]);
+            }
+            SimpleValue::Int8(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::WChar(v) | SimpleValue::Uint16(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Int16(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Uint32(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Int32(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Uint64(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::Int64(v) => self.primitive(v.to_le_bytes()),
+            SimpleValue::String(v) => self.string(&v.to_string())?,
+            SimpleValue::Message(view) => self.message(view)?,
+            _ => return Err(SerializeError::Unsupported),
+        }
+        Ok(())
+    }
+
+    fn elements(&mut self, value: &Value<'_>) -> Result<(), SerializeError> {
+        macro_rules! each {
+            ($items:expr) => {
+                for item in $items.iter() {
+                    self.primitive(item.to_le_bytes());
+                }
+            };
+        }
+
+        match elements(value).ok_or(SerializeError::Unsupported)? {
+            Elements::Float(items) => each!(items),
+            Elements::Double(items) => each!(items),
+            Elements::Boolean(items) => {
+                for item in items {
+                    self.primitive([u8::from(*item)]);
+                }
+            }
+            Elements::Uint8(items) => self.buf.extend_from_slice(items),
+            Elements::Int8(items) => each!(items),
+            Elements::Uint16(items) => each!(items),
+            Elements::Int16(items) => each!(items),
+            Elements::Uint32(items) => each!(items),
+            Elements::Int32(items) => each!(items),
+            Elements::Uint64(items) => each!(items),
+            Elements::Int64(items) => each!(items),
+            Elements::String(items) => {
+                for item in &items {
+                    self.string(item)?;
+                }
+            }
+            Elements::Message(views) => {
+                for view in views {
+                    self.message(view)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_after_header() {
+        let mut writer = CdrWriter {
+            buf: CDR_LE_HEADER.to_vec(),
+        };
+        writer.primitive([1_u8]);
+        writer.primitive(2_u32.to_le_bytes());
+        writer.string("ab").expect("short string");
+        assert_eq!(
+            writer.buf,
+            [0, 1, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, b'a', b'b', 0]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt::Display;
+
+use rclrs::{DynamicMessageView, SimpleValue, Value};
+use serde::de::{
+    self, value::SeqDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer as _,
+    MapAccess, SeqAccess, Visitor,
+};
+use thiserror::Error;
+
+use super::{elements, Elements};
+
+/// Errors occurring while deserializing a dynamic message into a definition.
+#[derive(Debug, Error)]
+pub enum DeserializeError {
+    #[error("{0}")]
+    Custom(String),
+
+    #[error("unsupported value type in dynamic message")]
+    Unsupported,
+}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Deserialize a dynamic message view into a message definition.
+///
+/// Messages are deserialized as maps of their fields, so definitions may omit
+/// fields they are not interested in. Arrays and sequences are deserialized
+/// as sequences, or as byte buffers when requested (e.g. with `serde_bytes`).
+///
+/// # Errors
+/// Returns a `DeserializeError` if the message does not match the definition.
+pub fn deserialize_view<T: DeserializeOwned>(
+    view: &DynamicMessageView<'_>,
+) -> Result<T, DeserializeError> {
+    T::deserialize(ViewDeserializer(view))
+}
+
+struct ViewDeserializer<'a, 'msg>(&'a DynamicMessageView<'msg>);
+
+impl<'de> de::Deserializer<'de> for ViewDeserializer<'_, '_> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(FieldsAccess {
+            view: self.0,
+            index: 0,
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct FieldsAccess<'a, 'msg> {
+    view: &'a DynamicMessageView<'msg>,
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess<'_, '_> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.view.fields.get(self.index) {
+            Some(field) => seed
+                .deserialize(field.name.as_str().into_deserializer())
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let field = self
+            .view
+            .fields
+            .get(self.index)
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        self.index += 1;
+        let value = self
+            .view
+            .get(&field.name)
+            .ok_or_else(|| de::Error::custom(format!("missing field '{}'", field.name)))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+}
+
+struct ValueDeserializer<'a>(Value<'a>);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let simple = match self.0 {
+            Value::Simple(simple) => simple,
+            value => {
+                return match elements(&value).ok_or(DeserializeError::Unsupported)? {
+                    Elements::Float(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Double(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Boolean(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Uint8(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Int8(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Uint16(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Int16(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Uint32(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Int32(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Uint64(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::Int64(items) => visit_items(visitor, items.iter().copied()),
+                    Elements::String(items) => visit_items(visitor, items.into_iter()),
+                    Elements::Message(views) => visitor.visit_seq(MessagesAccess {
+                        views: views.iter(),
+                    }),
+                };
+            }
+        };
+        match simple {
+            SimpleValue::Float(v) => visitor.visit_f32(*v),
+            SimpleValue::Double(v) => visitor.visit_f64(*v),
+            SimpleValue::Boolean(v) => visitor.visit_bool(*v),
+            SimpleValue::Char(v) | SimpleValue::Octet(v) | SimpleValue::Uint8(v) => {
+                visitor.visit_u8(*v)
+            }
+            SimpleValue::Int8(v) => visitor.visit_i8(*v),
+            SimpleValue::WChar(v) | SimpleValue::Uint16(v) => visitor.visit_u16(*v),
+            SimpleValue::Int16(v) => visitor.visit_i16(*v),
+            SimpleValue::Uint32(v) => visitor.visit_u32(*v),
+            SimpleValue::Int32(v) => visitor.visit_i32(*v),
+            SimpleValue::Uint64(v) => visitor.visit_u64(*v),
+            SimpleValue::Int64(v) => visitor.visit_i64(*v),
+            SimpleValue::String(v) => visitor.visit_string(v.to_string()),
+            SimpleValue::Message(view) => ViewDeserializer(&view).deserialize_any(visitor),
+            _ => Err(DeserializeError::Unsupported),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match elements(&self.0) {
+            Some(Elements::Uint8(items)) => visitor.visit_byte_buf(items.to_vec()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+fn visit_items<'de, V, I>(visitor: V, items: I) -> Result<V::Value, DeserializeError>
+where
+    V: Visitor<'de>,
+    I: Iterator,
+    I::Item: de::IntoDeserializer<'de, DeserializeError>,
+{
+    visitor.visit_seq(SeqDeserializer::new(items))
+}
+
+struct MessagesAccess<'a, 'msg> {
+    views: std::slice::Iter<'a, DynamicMessageView<'msg>>,
+}
+
+impl<'de> SeqAccess<'de> for MessagesAccess<'_, '_> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.views
+            .next()
+            .map(|view| seed.deserialize(ViewDeserializer(view)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.views.len())
+    }
+}
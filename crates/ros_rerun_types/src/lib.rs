@@ -6,10 +6,18 @@ use std::fmt::Display;
 
 pub mod converters;
 
+pub mod color;
 pub mod converter;
+pub mod covariance;
+pub mod definitions;
 pub mod dynamic_message;
+pub mod overrides;
+pub mod parsers;
 pub mod register;
 
+/// Version of the converters, recorded with the converter of each entity.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Represents a runtime-checked ROS message type.
 ///
 /// This ensures that the ROS type definition is available.
@@ -26,6 +34,11 @@ impl ROSTypeName {
             type_name: type_name.to_owned(),
         })
     }
+
+    /// Full type name as used in message schemas, e.g. `std_msgs/msg/String`.
+    pub fn schema_name(&self) -> String {
+        format!("{}/msg/{}", self.0.package_name, self.0.type_name)
+    }
 }
 
 impl TryFrom<&str> for ROSTypeName {
@@ -74,6 +87,58 @@ impl Display for ROSTypeName {
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct ROSTypeString<'a>(&'a str, &'a str);
 
+impl<'a> ROSTypeString<'a> {
+    pub const fn new(package_name: &'a str, type_name: &'a str) -> Self {
+        Self(package_name, type_name)
+    }
+
+    /// Types with the given names in the same package, for defining type lists.
+    pub const fn in_package<const N: usize>(
+        package_name: &'a str,
+        type_names: [&'a str; N],
+    ) -> [Self; N] {
+        let mut types: [Self; N] = [const { ROSTypeString("", "") }; N];
+        let mut i = 0;
+        while i < N {
+            types[i] = Self(package_name, type_names[i]);
+            i += 1;
+        }
+        types
+    }
+
+    /// Parse `pkg/Type`, `pkg/msg/Type` or `pkg::msg::Type`.
+    ///
+    /// Returns `None` for any other form, or when a part is empty.
+    pub fn parse(type_name: &'a str) -> Option<Self> {
+        let (package_name, type_name) = match type_name.split_once("::") {
+            Some((package_name, rest)) => (package_name, rest.strip_prefix("msg::")?),
+            None => {
+                let (package_name, rest) = type_name.split_once('/')?;
+                (package_name, rest.strip_prefix("msg/").unwrap_or(rest))
+            }
+        };
+        let valid = |part: &str| !part.is_empty() && !part.contains(['/', ':']);
+        (valid(package_name) && valid(type_name)).then_some(Self(package_name, type_name))
+    }
+
+    pub const fn package_name(&self) -> &'a str {
+        self.0
+    }
+
+    pub const fn type_name(&self) -> &'a str {
+        self.1
+    }
+
+    /// Whether `type_name` names this type in any of the forms accepted by `parse`.
+    ///
+    /// The `<ANY>` type matches every valid type name.
+    pub fn matches(&self, type_name: &str) -> bool {
+        ROSTypeString::parse(type_name).is_some_and(|other| {
+            (self.0.is_empty() && self.1.is_empty()) || (self.0 == other.0 && self.1 == other.1)
+        })
+    }
+}
+
 impl PartialEq<ROSTypeString<'_>> for ROSTypeName {
     fn eq(&self, other: &ROSTypeString<'_>) -> bool {
         self.0.package_name == other.0 && self.0.type_name == other.1
@@ -112,3 +177,64 @@ impl Display for RerunName {
         }
     }
 }
+
+/// Match a name against a pattern, where `*` matches any characters,
+/// e.g. a frame ID or a part of an entity path.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[u8], name: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some((b'*', rest)) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+            Some((c, rest)) => name
+                .split_first()
+                .is_some_and(|(n, name)| n == c && matches(rest, name)),
+        }
+    }
+    matches(pattern.as_bytes(), name.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_string_forms() {
+        let string = ROSTypeString::new("std_msgs", "String");
+        for form in [
+            "std_msgs/String",
+            "std_msgs/msg/String",
+            "std_msgs::msg::String",
+        ] {
+            assert_eq!(ROSTypeString::parse(form), Some(string.clone()), "{form}");
+            assert!(string.matches(form), "{form}");
+        }
+        for form in [
+            "std_msgs",
+            "std_msgs/",
+            "/String",
+            "std_msgs/srv/String",
+            "a/b/c/d",
+        ] {
+            assert_eq!(ROSTypeString::parse(form), None, "{form}");
+        }
+        assert!(!string.matches("std_msgs/Header"));
+        assert!(ROSTypeString::default().matches("std_msgs/Header"));
+    }
+
+    #[test]
+    fn type_lists() {
+        const TYPES: [ROSTypeString<'static>; 2] =
+            ROSTypeString::in_package("sensor_msgs", ["Range", "Temperature"]);
+        assert_eq!(TYPES[0], ROSTypeString::new("sensor_msgs", "Range"));
+        assert_eq!(TYPES[1].to_string(), "sensor_msgs/msg/Temperature");
+    }
+
+    #[test]
+    fn wildcards_match_any_characters() {
+        assert!(wildcard_match("camera_*_link", "camera_front_link"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("robot/*", "robot/arm/link"));
+        assert!(!wildcard_match("camera_*", "lidar_link"));
+        assert!(!wildcard_match("base", "base_link"));
+    }
+}
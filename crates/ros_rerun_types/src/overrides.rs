@@ -0,0 +1,111 @@
+//! Constant component values that replace the output of a converter.
+
+use anyhow::{bail, Result};
+use rerun::{Archetype as _, AsComponents as _, SerializedComponentBatch};
+use serde::{Deserialize, Serialize};
+
+use crate::{color::ColorSetting, RerunName};
+
+/// Components set to the same value for every message of a topic,
+/// given in the `overrides` table of the converter settings.
+///
+/// Overrides replace the components of the same name produced by the converter,
+/// e.g. to draw all points of a topic in one color.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OverrideConfig {
+    colors: Option<ColorSetting>,
+    labels: Option<String>,
+    class_ids: Option<u16>,
+    radii: Option<f32>,
+    show_labels: Option<bool>,
+}
+
+/// Serialize the overrides as components of `archetype`.
+///
+/// Each override is a single instance, which Rerun applies to all instances
+/// of the logged entity.
+///
+/// # Errors
+/// Returns an error if the archetype does not support the overrides,
+/// or if a value is invalid.
+pub fn override_batches(
+    archetype: &RerunName,
+    config: &OverrideConfig,
+) -> Result<Vec<SerializedComponentBatch>> {
+    let color = config
+        .colors
+        .as_ref()
+        .map(ColorSetting::to_color)
+        .transpose()?;
+    if config.radii.is_some_and(|radius| !radius.is_finite()) {
+        bail!("'radii' must be a finite number");
+    }
+    let RerunName::RerunArchetype(name) = archetype else {
+        bail!("overrides are not supported for {archetype}");
+    };
+
+    macro_rules! overrides {
+        ($($archetype:ty),+ $(,)?) => {
+            $(if *name == <$archetype>::name() {
+                let mut fields = <$archetype>::update_fields();
+                if let Some(color) = color {
+                    fields = fields.with_colors([color]);
+                }
+                if let Some(label) = &config.labels {
+                    fields = fields.with_labels([label.as_str()]);
+                }
+                if let Some(class_id) = config.class_ids {
+                    fields = fields.with_class_ids([class_id]);
+                }
+                if let Some(radius) = config.radii {
+                    fields = fields.with_radii([radius]);
+                }
+                if let Some(show_labels) = config.show_labels {
+                    fields = fields.with_show_labels(show_labels);
+                }
+                return Ok(fields.as_serialized_batches());
+            })+
+        };
+    }
+
+    overrides!(
+        rerun::Points2D,
+        rerun::Points3D,
+        rerun::Boxes2D,
+        rerun::Boxes3D,
+        rerun::Arrows2D,
+        rerun::Arrows3D,
+        rerun::LineStrips2D,
+        rerun::LineStrips3D,
+    );
+
+    bail!("overrides are not supported for {archetype}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archetype_compatibility() {
+        let config: OverrideConfig = toml::from_str(
+            r##"
+            colors = "#ff0000"
+            radii = 0.1
+            "##,
+        )
+        .expect("valid overrides");
+        let points = RerunName::RerunArchetype(rerun::Points3D::name());
+        let batches = override_batches(&points, &config).expect("points support overrides");
+        assert_eq!(batches.len(), 2);
+
+        let text = RerunName::RerunArchetype(rerun::TextDocument::name());
+        assert!(override_batches(&text, &config).is_err());
+        assert!(override_batches(&RerunName::Components, &config).is_err());
+
+        let invalid: OverrideConfig = toml::from_str("colors = [1, 2]").expect("parses");
+        assert!(override_batches(&points, &invalid).is_err());
+        assert!(toml::from_str::<OverrideConfig>("positions = 1").is_err());
+    }
+}
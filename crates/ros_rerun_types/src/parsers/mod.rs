@@ -0,0 +1,3 @@
+//! Parsers for the binary payloads carried by some ROS messages.
+
+pub mod sensor_msgs;
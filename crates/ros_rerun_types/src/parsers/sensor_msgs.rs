@@ -0,0 +1,684 @@
+use crate::definitions::sensor_msgs::{PointCloud2, PointField};
+
+/// Reads a single scalar field from the bytes of a point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldReader {
+    offset: usize,
+    datatype: u8,
+    big_endian: bool,
+}
+
+impl FieldReader {
+    /// Create a reader for `field`, or `None` if the datatype is unknown.
+    pub fn new(field: &PointField, big_endian: bool) -> Option<Self> {
+        (PointField::INT8..=PointField::FLOAT64)
+            .contains(&field.datatype)
+            .then(|| Self {
+                offset: field.offset as usize,
+                datatype: field.datatype,
+                big_endian,
+            })
+    }
+
+    /// Size in bytes of a single value of the field.
+    pub fn size(&self) -> usize {
+        match self.datatype {
+            PointField::INT8 | PointField::UINT8 => 1,
+            PointField::INT16 | PointField::UINT16 => 2,
+            PointField::INT32 | PointField::UINT32 | PointField::FLOAT32 => 4,
+            _ => 8,
+        }
+    }
+
+    /// Read the `index`th value of the field from field-major (SOA) data.
+    ///
+    /// See `SoaPosition3DIter` for the expected layout.
+    pub fn read_soa(&self, data: &[u8], index: usize) -> Option<f64> {
+        let start = index.checked_mul(self.size())?;
+        self.read(data.get(start..)?)
+    }
+
+    /// Read the field from the bytes of a single point.
+    pub fn read(&self, point: &[u8]) -> Option<f64> {
+        macro_rules! read {
+            ($ty:ty) => {{
+                let bytes = point.get(self.offset..self.offset + size_of::<$ty>())?;
+                let bytes = bytes.try_into().ok()?;
+                let value = if self.big_endian {
+                    <$ty>::from_be_bytes(bytes)
+                } else {
+                    <$ty>::from_le_bytes(bytes)
+                };
+                f64::from(value)
+            }};
+        }
+
+        let value = match self.datatype {
+            PointField::INT8 => read!(i8),
+            PointField::UINT8 => read!(u8),
+            PointField::INT16 => read!(i16),
+            PointField::UINT16 => read!(u16),
+            PointField::INT32 => read!(i32),
+            PointField::UINT32 => read!(u32),
+            PointField::FLOAT32 => read!(f32),
+            PointField::FLOAT64 => read!(f64),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Read the raw bits of a 4 byte field from the bytes of a single point,
+    /// e.g. of a color packed into a FLOAT32.
+    pub fn read_bits(&self, point: &[u8]) -> Option<u32> {
+        if self.size() != 4 {
+            return None;
+        }
+        let bytes = point.get(self.offset..self.offset + 4)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+}
+
+/// Find a field by name, trying each of the given names in order.
+pub fn find_field<'a>(fields: &'a [PointField], names: &[&str]) -> Option<&'a PointField> {
+    names
+        .iter()
+        .find_map(|name| fields.iter().find(|field| field.name == *name))
+}
+
+/// List the fields of a point cloud for diagnostics.
+pub fn describe_fields(fields: &[PointField]) -> String {
+    if fields.is_empty() {
+        return "<none>".to_owned();
+    }
+    fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{} (offset {}, {})",
+                field.name,
+                field.offset,
+                field.datatype_name()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Describe why the point-major (AOS) layout of a cloud does not fit its
+/// data, or `None` if all points can be read.
+///
+/// Rows may be padded from `width * point_step` up to `row_step`, but the
+/// data must hold `height` full rows. Without padding, the data must also
+/// be a whole number of points.
+pub fn layout_error(cloud: &PointCloud2) -> Option<String> {
+    let (width, height) = (cloud.width as usize, cloud.height as usize);
+    let (point_step, row_step) = (cloud.point_step as usize, cloud.row_step as usize);
+    let len = cloud.data.len();
+    if point_step == 0 {
+        return Some("point_step is 0".to_owned());
+    }
+    if row_step < width * point_step {
+        return Some(format!(
+            "row_step {row_step} is less than width {width} times point_step {point_step}"
+        ));
+    }
+    if row_step == width * point_step && len % point_step != 0 {
+        return Some(format!(
+            "{len} bytes of data are not a multiple of point_step {point_step}"
+        ));
+    }
+    if row_step * height > len {
+        return Some(format!(
+            "{len} bytes of data are less than height {height} times row_step {row_step}"
+        ));
+    }
+    None
+}
+
+/// Iterates over the points of a `PointCloud2`, in row-major order.
+///
+/// Yields the raw bytes of each point, respecting `row_step` padding.
+#[derive(Clone, Debug)]
+pub struct PointIter<'a> {
+    cloud: &'a PointCloud2,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> PointIter<'a> {
+    pub fn new(cloud: &'a PointCloud2) -> Self {
+        Self {
+            cloud,
+            index: 0,
+            len: cloud.width as usize * cloud.height as usize,
+        }
+    }
+}
+
+impl<'a> Iterator for PointIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let width = self.cloud.width as usize;
+        let (row, col) = (self.index / width, self.index % width);
+        let point_step = self.cloud.point_step as usize;
+        let start = row * self.cloud.row_step as usize + col * point_step;
+        self.index += 1;
+        let point = self.cloud.data.get(start..start + point_step);
+        if point.is_none() {
+            // Truncated data, stop instead of reading garbage.
+            self.index = self.len;
+        }
+        point
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len - self.index))
+    }
+}
+
+/// Find readers for the x/y/z coordinate fields of a cloud.
+fn position_readers(cloud: &PointCloud2) -> Option<[FieldReader; 3]> {
+    let [x, y, z] = Position3DIter::FIELD_NAMES.map(|names| {
+        find_field(&cloud.fields, &names)
+            .and_then(|field| FieldReader::new(field, cloud.is_bigendian))
+    });
+    Some([x?, y?, z?])
+}
+
+/// Iterates over the x/y/z coordinates of the points in a `PointCloud2`.
+///
+/// Expects the standard point-major (AOS) layout, where the fields of each
+/// point are interleaved within `point_step` bytes.
+#[derive(Clone, Debug)]
+pub struct Position3DIter<'a> {
+    points: PointIter<'a>,
+    x: FieldReader,
+    y: FieldReader,
+    z: FieldReader,
+}
+
+impl<'a> Position3DIter<'a> {
+    /// Names tried, in order, for each coordinate field.
+    pub const FIELD_NAMES: [[&'static str; 2]; 3] = [["x", "X"], ["y", "Y"], ["z", "Z"]];
+
+    /// Create the iterator, or `None` if the cloud has no coordinate fields.
+    pub fn try_new(cloud: &'a PointCloud2) -> Option<Self> {
+        let [x, y, z] = position_readers(cloud)?;
+        if layout_error(cloud).is_some() {
+            return None;
+        }
+        Some(Self {
+            points: PointIter::new(cloud),
+            x,
+            y,
+            z,
+        })
+    }
+}
+
+impl Iterator for Position3DIter<'_> {
+    type Item = [f32; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        let read = |reader: &FieldReader| reader.read(point).map_or(f32::NAN, |v| v as f32);
+        Some([read(&self.x), read(&self.y), read(&self.z)])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.points.size_hint()
+    }
+}
+
+/// Iterates over the values of a single field of the points in a `PointCloud2`,
+/// e.g. `intensity`, in the standard point-major (AOS) layout.
+///
+/// Yields NaN for values that cannot be read, one value per point.
+#[derive(Clone, Debug)]
+pub struct FieldIter<'a> {
+    points: PointIter<'a>,
+    reader: FieldReader,
+}
+
+impl<'a> FieldIter<'a> {
+    /// Create the iterator, or `None` if the cloud has no such field.
+    pub fn try_new(cloud: &'a PointCloud2, name: &str) -> Option<Self> {
+        let reader = find_field(&cloud.fields, &[name])
+            .and_then(|field| FieldReader::new(field, cloud.is_bigendian))?;
+        if layout_error(cloud).is_some() {
+            return None;
+        }
+        Some(Self {
+            points: PointIter::new(cloud),
+            reader,
+        })
+    }
+}
+
+impl Iterator for FieldIter<'_> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        Some(self.reader.read(point).map_or(f32::NAN, |v| v as f32))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.points.size_hint()
+    }
+}
+
+/// Iterates over the x/y/z coordinates of the points in a `PointCloud2`
+/// together with the value of another field, e.g. `intensity`.
+///
+/// Like `Position3DIter` zipped with a `FieldIter`, but reads each point
+/// once. Yields NaN for values that cannot be read.
+#[derive(Clone, Debug)]
+pub struct Position3DValueIter<'a> {
+    positions: Position3DIter<'a>,
+    value: FieldReader,
+}
+
+impl<'a> Position3DValueIter<'a> {
+    /// Create the iterator, or `None` if the cloud has no coordinate fields
+    /// or no such field.
+    pub fn try_new(cloud: &'a PointCloud2, name: &str) -> Option<Self> {
+        let value = find_field(&cloud.fields, &[name])
+            .and_then(|field| FieldReader::new(field, cloud.is_bigendian))?;
+        Some(Self {
+            positions: Position3DIter::try_new(cloud)?,
+            value,
+        })
+    }
+}
+
+impl Iterator for Position3DValueIter<'_> {
+    type Item = ([f32; 3], f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.positions.points.next()?;
+        let read = |reader: &FieldReader| reader.read(point).map_or(f32::NAN, |v| v as f32);
+        let positions = &self.positions;
+        Some((
+            [read(&positions.x), read(&positions.y), read(&positions.z)],
+            read(&self.value),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+/// Split a color packed as `0xAARRGGBB` into `[r, g, b, a]`, see `PackedColorIter`.
+///
+/// The alpha byte is only used with `alpha` set, the color is opaque otherwise.
+pub fn unpack_color(bits: u32, alpha: bool) -> [u8; 4] {
+    let [a, r, g, b] = bits.to_be_bytes();
+    [r, g, b, if alpha { a } else { u8::MAX }]
+}
+
+/// Iterates over the x/y/z coordinates of the points in a `PointCloud2`
+/// together with the raw bits of an optional 4 byte attribute field,
+/// e.g. a packed `rgb` color, see `unpack_color`.
+///
+/// Like `Position3DIter` zipped with the attribute, but reads each point
+/// once. Yields `None` for the attribute when there is none or it cannot
+/// be read.
+#[derive(Clone, Debug)]
+pub struct ColoredPosition3DIter<'a> {
+    positions: Position3DIter<'a>,
+    attribute: Option<FieldReader>,
+}
+
+impl<'a> ColoredPosition3DIter<'a> {
+    /// Create the iterator, or `None` if the cloud has no coordinate fields,
+    /// or no 4 byte field named `attribute`.
+    pub fn try_new(cloud: &'a PointCloud2, attribute: Option<&str>) -> Option<Self> {
+        let attribute = match attribute {
+            Some(name) => Some(
+                find_field(&cloud.fields, &[name])
+                    .and_then(|field| FieldReader::new(field, cloud.is_bigendian))
+                    .filter(|reader| reader.size() == 4)?,
+            ),
+            None => None,
+        };
+        Some(Self {
+            positions: Position3DIter::try_new(cloud)?,
+            attribute,
+        })
+    }
+}
+
+impl Iterator for ColoredPosition3DIter<'_> {
+    type Item = ([f32; 3], Option<u32>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.positions.points.next()?;
+        let read = |reader: &FieldReader| reader.read(point).map_or(f32::NAN, |v| v as f32);
+        let positions = &self.positions;
+        Some((
+            [read(&positions.x), read(&positions.y), read(&positions.z)],
+            self.attribute.and_then(|reader| reader.read_bits(point)),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+/// Iterates over the colors packed into a 4 byte field of the points in a
+/// `PointCloud2`, like the `rgb` and `rgba` fields of PCL and RGBD cameras.
+///
+/// The field holds `0xAARRGGBB` as an integer, even when it is declared as
+/// FLOAT32. Yields `[r, g, b, a]`, where the alpha byte is only used with
+/// `alpha` set and is opaque otherwise. Unreadable colors are transparent.
+#[derive(Clone, Debug)]
+pub struct PackedColorIter<'a> {
+    points: PointIter<'a>,
+    reader: FieldReader,
+    alpha: bool,
+}
+
+impl<'a> PackedColorIter<'a> {
+    /// Create the iterator, or `None` if the cloud has no such 4 byte field.
+    pub fn try_new(cloud: &'a PointCloud2, name: &str, alpha: bool) -> Option<Self> {
+        let reader = find_field(&cloud.fields, &[name])
+            .and_then(|field| FieldReader::new(field, cloud.is_bigendian))
+            .filter(|reader| reader.size() == 4)?;
+        if layout_error(cloud).is_some() {
+            return None;
+        }
+        Some(Self {
+            points: PointIter::new(cloud),
+            reader,
+            alpha,
+        })
+    }
+}
+
+impl Iterator for PackedColorIter<'_> {
+    type Item = [u8; 4];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        Some(
+            self.reader
+                .read_bits(point)
+                .map_or([0; 4], |bits| unpack_color(bits, self.alpha)),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.points.size_hint()
+    }
+}
+
+/// Iterates over the x/y/z coordinates of a field-major (SOA) `PointCloud2`.
+///
+/// Some custom producers do not interleave the fields of each point, but
+/// store every field in its own contiguous region of `data`. In this layout
+/// the `offset` of a field is the start of its region in bytes, which holds
+/// `width * height` consecutive values of the field's datatype.
+/// `point_step` and `row_step` are ignored.
+#[derive(Clone, Debug)]
+pub struct SoaPosition3DIter<'a> {
+    data: &'a [u8],
+    x: FieldReader,
+    y: FieldReader,
+    z: FieldReader,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> SoaPosition3DIter<'a> {
+    /// Create the iterator, or `None` if the cloud has no coordinate fields.
+    pub fn try_new(cloud: &'a PointCloud2) -> Option<Self> {
+        let [x, y, z] = position_readers(cloud)?;
+        Some(Self {
+            data: &cloud.data,
+            x,
+            y,
+            z,
+            index: 0,
+            len: cloud.width as usize * cloud.height as usize,
+        })
+    }
+}
+
+impl Iterator for SoaPosition3DIter<'_> {
+    type Item = [f32; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let read = |reader: &FieldReader| {
+            reader
+                .read_soa(self.data, index)
+                .map_or(f32::NAN, |v| v as f32)
+        };
+        Some([read(&self.x), read(&self.y), read(&self.z)])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, offset: u32) -> PointField {
+        PointField {
+            name: name.to_owned(),
+            offset,
+            datatype: PointField::FLOAT32,
+            count: 1,
+        }
+    }
+
+    fn cloud(names: [&str; 3]) -> PointCloud2 {
+        let data = [1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        PointCloud2 {
+            height: 1,
+            width: 2,
+            fields: vec![field(names[0], 0), field(names[1], 4), field(names[2], 8)],
+            point_step: 12,
+            row_step: 24,
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_positions() {
+        let cloud = cloud(["x", "y", "z"]);
+        let points = Position3DIter::try_new(&cloud)
+            .expect("has coordinates")
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn reads_positions_with_values() {
+        let cloud = cloud(["x", "y", "z"]);
+        assert!(Position3DValueIter::try_new(&cloud, "intensity").is_none());
+        let points = Position3DValueIter::try_new(&cloud, "z")
+            .expect("has coordinates and z")
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![([1.0, 2.0, 3.0], 3.0), ([4.0, 5.0, 6.0], 6.0)]);
+    }
+
+    #[test]
+    fn reads_soa_positions() {
+        let data = [1.0_f32, 4.0, 2.0, 5.0, 3.0, 6.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let cloud = PointCloud2 {
+            height: 1,
+            width: 2,
+            fields: vec![field("x", 0), field("y", 8), field("z", 16)],
+            data,
+            ..Default::default()
+        };
+        let points = SoaPosition3DIter::try_new(&cloud)
+            .expect("has coordinates")
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn unpacks_colors() {
+        let rgb = f32::from_bits(0x80_ff_80_00);
+        let data = [rgb, f32::NAN]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let cloud = PointCloud2 {
+            height: 1,
+            width: 2,
+            fields: vec![field("rgb", 0)],
+            point_step: 4,
+            row_step: 8,
+            data,
+            ..Default::default()
+        };
+        let colors = PackedColorIter::try_new(&cloud, "rgb", false)
+            .expect("has rgb")
+            .collect::<Vec<_>>();
+        assert_eq!(colors[0], [255, 128, 0, 255]);
+        let colors = PackedColorIter::try_new(&cloud, "rgb", true)
+            .expect("has rgb")
+            .collect::<Vec<_>>();
+        assert_eq!(colors[0], [255, 128, 0, 128]);
+        assert!(PackedColorIter::try_new(&cloud, "rgba", false).is_none());
+    }
+
+    #[test]
+    fn reads_colored_positions() {
+        for big_endian in [false, true] {
+            let to_bytes = |bytes: [u8; 4]| {
+                if big_endian {
+                    bytes
+                } else {
+                    let mut bytes = bytes;
+                    bytes.reverse();
+                    bytes
+                }
+            };
+            let data = [1.0_f32, 2.0, 3.0]
+                .iter()
+                .flat_map(|v| to_bytes(v.to_be_bytes()))
+                .chain(to_bytes(0x00_ff_80_00_u32.to_be_bytes()))
+                .collect();
+            let cloud = PointCloud2 {
+                height: 1,
+                width: 1,
+                fields: vec![
+                    field("x", 0),
+                    field("y", 4),
+                    field("z", 8),
+                    field("rgb", 12),
+                ],
+                is_bigendian: big_endian,
+                point_step: 16,
+                row_step: 16,
+                data,
+                ..Default::default()
+            };
+            let points = ColoredPosition3DIter::try_new(&cloud, Some("rgb"))
+                .expect("has coordinates and rgb")
+                .collect::<Vec<_>>();
+            assert_eq!(
+                points,
+                [([1.0, 2.0, 3.0], Some(0x00_ff_80_00))],
+                "{big_endian}"
+            );
+            assert_eq!(unpack_color(0x00_ff_80_00, false), [255, 128, 0, 255]);
+
+            let points = ColoredPosition3DIter::try_new(&cloud, None)
+                .expect("has coordinates")
+                .collect::<Vec<_>>();
+            assert_eq!(points, [([1.0, 2.0, 3.0], None)]);
+            assert!(ColoredPosition3DIter::try_new(&cloud, Some("rgba")).is_none());
+        }
+    }
+
+    #[test]
+    fn reads_organized_integer_clouds() {
+        let int_field = |name: &str, offset| PointField {
+            datatype: PointField::INT16,
+            ..field(name, offset)
+        };
+        let data: Vec<u8> = (0..6_i16)
+            .flat_map(|i| [i, -i, 2 * i])
+            .flat_map(i16::to_le_bytes)
+            .collect();
+        let mut cloud = PointCloud2 {
+            height: 2,
+            width: 3,
+            fields: vec![int_field("x", 0), int_field("y", 2), int_field("z", 4)],
+            point_step: 6,
+            row_step: 18,
+            data,
+            ..Default::default()
+        };
+        assert_eq!(layout_error(&cloud), None);
+        let points = Position3DIter::try_new(&cloud)
+            .expect("valid layout")
+            .collect::<Vec<_>>();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[5], [5.0, -5.0, 10.0]);
+
+        cloud.data.pop();
+        assert!(
+            layout_error(&cloud).is_some(),
+            "not a multiple of point_step"
+        );
+        assert!(Position3DIter::try_new(&cloud).is_none());
+        cloud.data.truncate(30);
+        assert!(layout_error(&cloud).is_some(), "missing a row");
+        cloud.row_step = 12;
+        assert!(
+            layout_error(&cloud).is_some(),
+            "rows shorter than their points"
+        );
+    }
+
+    #[test]
+    fn alternate_field_names() {
+        let cloud = cloud(["X", "Y", "Z"]);
+        assert_eq!(
+            Position3DIter::try_new(&cloud).map(Iterator::count),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn missing_fields() {
+        let cloud = cloud(["x", "y", "intensity"]);
+        assert!(Position3DIter::try_new(&cloud).is_none(), "z is missing");
+        assert_eq!(
+            describe_fields(&cloud.fields),
+            "x (offset 0, FLOAT32), y (offset 4, FLOAT32), intensity (offset 8, FLOAT32)"
+        );
+    }
+}
@@ -1,6 +1,119 @@
+use rerun::Archetype as _;
+
 use crate::converter::ConverterRegistry;
+use crate::converters::battery::SensorBatteryStateToScalars;
+use crate::converters::geometry::{LinearAngularToArrows3D, LinearAngularToScalars};
+use crate::converters::grid_cells::{CellOutput, NavGridCellsToPoints3D};
+use crate::converters::heartbeat::{StdEmptyToScalars, StdHeaderToScalars};
+use crate::converters::image::SensorImageToImage;
+use crate::converters::imu::SensorImuToTransform3D;
+use crate::converters::inertia::InertiaToTextDocument;
+use crate::converters::laser_scan::SensorLaserScanToPoints3D;
+use crate::converters::marker::VisualizationMarkerToArchetypes;
+use crate::converters::occupancy_grid::{
+    GridOutput, MapOccupancyGridUpdateToImage, NavOccupancyGridToImage,
+};
+use crate::converters::odometry::NavOdometryToTransform3D;
+use crate::converters::path::NavPathToLineStrips3D;
+use crate::converters::point_cloud::{
+    PointCloudOutput, SensorPointCloud2ToPoints3D, SensorPointCloudToPoints3D,
+};
+use crate::converters::pose_array::{
+    AnyTransformArrayToPoses, GeometryPoseArrayToPoints3D, PoseOutput,
+};
+use crate::converters::radar::AnyRadarToPoints3D;
+use crate::converters::range::SensorRangeToArrows3D;
+use crate::converters::roi::SensorRegionOfInterestToBoxes2D;
+use crate::converters::scalar_sensor::{
+    SensorMagneticFieldToScalars, SingleScalarSensor, SENSOR_SCALARS, STD_SCALARS, STD_SCALAR_FIELD,
+};
+use crate::converters::time_reference::SensorTimeReferenceToScalars;
+use crate::converters::trajectory::TrajectoryJointTrajectoryToScalars;
+use crate::converters::transform::{TFMessageToTransform3D, TransformStampedToTransform3D};
+use crate::converters::vision::VisionDetection3DArrayToBoxes3D;
+use crate::definitions::geometry_msgs::{
+    Accel, AccelStamped, Inertia, InertiaStamped, Twist, TwistStamped,
+};
+use crate::definitions::sensor_msgs::Imu;
 
 pub(crate) fn register_converters(r: &mut ConverterRegistry) {
     r.register(&crate::converters::text::StdStringToTextDocument::default());
     r.register(&crate::converters::text::AnyToTextDocument::default());
+    r.register(&crate::converters::text::StdStringToTextLog::default());
+    r.register(&crate::converters::text::AnyToTextLog::default());
+
+    r.register(&StdEmptyToScalars::default());
+    r.register(&StdHeaderToScalars::default());
+
+    r.register(&LinearAngularToArrows3D::<Twist>::default());
+    r.register(&LinearAngularToScalars::<Twist>::default());
+    r.register(&LinearAngularToArrows3D::<TwistStamped>::default());
+    r.register(&LinearAngularToScalars::<TwistStamped>::default());
+    r.register(&LinearAngularToArrows3D::<Accel>::default());
+    r.register(&LinearAngularToScalars::<Accel>::default());
+    r.register(&LinearAngularToArrows3D::<AccelStamped>::default());
+    r.register(&LinearAngularToScalars::<AccelStamped>::default());
+    r.register(&LinearAngularToArrows3D::<Imu>::default());
+    r.register(&LinearAngularToScalars::<Imu>::default());
+
+    r.register(&InertiaToTextDocument::<Inertia>::default());
+    r.register(&InertiaToTextDocument::<InertiaStamped>::default());
+
+    r.register(&SensorPointCloud2ToPoints3D::new(PointCloudOutput::Points));
+    r.register(&SensorPointCloud2ToPoints3D::new(PointCloudOutput::Boxes));
+    r.register(&SensorPointCloud2ToPoints3D::new(PointCloudOutput::Mesh));
+    r.register(&SensorPointCloudToPoints3D::default());
+    r.register(&SensorLaserScanToPoints3D::default());
+
+    r.register(&SensorImageToImage::default());
+
+    r.register(&NavOccupancyGridToImage::new(GridOutput::Image));
+    r.register(&NavOccupancyGridToImage::new(GridOutput::SegmentationImage));
+    r.register(&MapOccupancyGridUpdateToImage::new(GridOutput::Image));
+    r.register(&MapOccupancyGridUpdateToImage::new(
+        GridOutput::SegmentationImage,
+    ));
+
+    r.register(&VisionDetection3DArrayToBoxes3D::default());
+
+    r.register(&NavGridCellsToPoints3D::new(CellOutput::Points));
+    r.register(&NavGridCellsToPoints3D::new(CellOutput::Boxes));
+
+    r.register(&TransformStampedToTransform3D::default());
+    r.register(&TFMessageToTransform3D::default());
+    r.register(&NavOdometryToTransform3D::default());
+    r.register(&SensorImuToTransform3D::default());
+    r.register(&NavPathToLineStrips3D::default());
+
+    for (ros_type, field) in &SENSOR_SCALARS {
+        r.register(&SingleScalarSensor::new(ros_type, field));
+    }
+    for ros_type in &STD_SCALARS {
+        r.register(&SingleScalarSensor::new(ros_type, STD_SCALAR_FIELD));
+    }
+    r.register(&SingleScalarSensor::default());
+    r.register(&SensorMagneticFieldToScalars);
+    r.register(&SensorRangeToArrows3D::default());
+
+    r.register(&SensorTimeReferenceToScalars::default());
+    r.register(&SensorBatteryStateToScalars::default());
+    r.register(&TrajectoryJointTrajectoryToScalars);
+
+    r.register(&AnyTransformArrayToPoses::new(PoseOutput::Poses));
+    r.register(&AnyTransformArrayToPoses::new(PoseOutput::Points));
+    r.register(&GeometryPoseArrayToPoints3D::default());
+    r.register(&AnyRadarToPoints3D::default());
+
+    r.register(&VisualizationMarkerToArchetypes::new(
+        rerun::Points3D::name(),
+    ));
+    r.register(&VisualizationMarkerToArchetypes::new(
+        rerun::LineStrips3D::name(),
+    ));
+
+    r.register(&SensorRegionOfInterestToBoxes2D::default());
+    r.register(&SensorRegionOfInterestToBoxes2D::any());
+
+    #[cfg(feature = "video")]
+    r.register(&crate::converters::video::SensorCompressedImageToVideoStream::default());
 }